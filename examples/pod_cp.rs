@@ -6,9 +6,8 @@ use kube::{
     api::{Api, AttachParams, DeleteParams, PostParams, ResourceExt, WatchEvent, WatchParams},
     Client,
 };
-use tokio::io::AsyncWriteExt;
 
-// A `kubectl cp` analog example.
+// A `kubectl cp` analog example, using `Api::copy_to` and `Api::copy_from`.
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
@@ -53,32 +52,17 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let data = "data for pod";
-    let file_name = "foo.txt";
+    let local_dir = std::env::temp_dir().join("kube-pod-cp");
+    std::fs::create_dir_all(&local_dir)?;
+    std::fs::write(local_dir.join("foo.txt"), data)?;
 
-    // Write the data to pod
-    {
-        let mut header = tar::Header::new_gnu();
-        header.set_path(file_name).unwrap();
-        header.set_size(data.len() as u64);
-        header.set_cksum();
-
-        let mut ar = tar::Builder::new(Vec::new());
-        ar.append(&header, &mut data.as_bytes()).unwrap();
-        let data = ar.into_inner().unwrap();
-
-        let ap = AttachParams::default().stdin(true).stderr(false);
-        let mut tar = pods
-            .exec("example", vec!["tar", "xf", "-", "-C", "/"], &ap)
-            .await?;
-        tar.stdin().unwrap().write_all(&data).await?;
-    }
+    // Copy the local directory to the pod
+    pods.copy_to("example", &local_dir, "/data").await?;
 
     // Check that the file was written
     {
         let ap = AttachParams::default().stderr(false);
-        let mut cat = pods
-            .exec("example", vec!["cat", &format!("/{file_name}")], &ap)
-            .await?;
+        let mut cat = pods.exec("example", vec!["cat", "/data/foo.txt"], &ap).await?;
         let mut cat_out = tokio_util::io::ReaderStream::new(cat.stdout().unwrap());
         let next_stdout = cat_out.next().await.unwrap()?;
 
@@ -86,6 +70,11 @@ async fn main() -> anyhow::Result<()> {
         assert_eq!(next_stdout, data);
     }
 
+    // Copy it back from the pod
+    let copied = local_dir.join("copy");
+    pods.copy_from("example", "/data/foo.txt", &copied).await?;
+    assert_eq!(std::fs::read_to_string(&copied)?, data);
+
     // Clean up the pod
     pods.delete("example", &DeleteParams::default())
         .await?
@@ -1,11 +1,12 @@
+use futures::{StreamExt, TryStreamExt};
 use k8s_openapi::api::core::v1::Pod;
 use kube::{
-    api::{Api, ListParams, ResourceExt},
+    api::{Api, ExpiredContinue, ListParams, ResourceExt},
     Client,
 };
 use tracing::*;
 
-// This example shows how to do pagination with the raw `Api` only.
+// This example shows how to stream a paginated list with the raw `Api` only.
 // In many realistic setups that need a continual, paginated, safe list-watch;
 // the `watcher` is an easier abstraction that has configurable pagination built in.
 
@@ -17,31 +18,14 @@ async fn main() -> anyhow::Result<()> {
     let client = Client::try_default().await?;
     let api = Api::<Pod>::default_namespaced(client);
 
-    let mut continue_token: Option<String> = None;
-    for page in 1.. {
-        info!("Fetching Page #{page}");
-        continue_token = fetch_page(&api, continue_token).await?;
-
-        if continue_token.is_none() {
-            info!("End of list");
-            break;
-        }
-    }
-
-    Ok(())
-}
-
-async fn fetch_page(api: &Api<Pod>, continue_token: Option<String>) -> anyhow::Result<Option<String>> {
-    let mut lp = ListParams::default().limit(PAGE_SIZE);
-    if let Some(token) = continue_token {
-        lp = lp.continue_token(&token);
-    }
-
-    let pods = api.list(&lp).await?;
-    let continue_token = pods.metadata.continue_.clone();
-    for p in pods {
+    // The stream follows the continue token of each page,
+    // and resumes after the last pod if the token expires
+    let lp = ListParams::default().limit(PAGE_SIZE);
+    let mut pods = api.list_stream_with(&lp, ExpiredContinue::Resume).boxed();
+    while let Some(p) = pods.try_next().await? {
         info!("Found Pod: {}", p.name_any());
     }
+    info!("End of list");
 
-    Ok(continue_token)
+    Ok(())
 }
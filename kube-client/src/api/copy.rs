@@ -0,0 +1,356 @@
+//! Copy files to and from pods with tar archives over [`exec`](crate::Api::exec), like `kubectl cp`
+use std::{
+    io,
+    path::{Component, Path, PathBuf},
+};
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
+use thiserror::Error;
+use tokio::io::AsyncReadExt;
+use tokio_util::io::SyncIoBridge;
+
+use super::{remote_command, AttachParams, AttachedProcess, Execute};
+use crate::{Api, Error, Result};
+
+/// Errors from copying files to and from a pod
+#[derive(Debug, Error)]
+pub enum CopyError {
+    /// Failed to read or write a local file
+    #[error("failed to access local path {}: {source}", path.display())]
+    Local {
+        /// The local path
+        path: PathBuf,
+        /// The underlying error
+        #[source]
+        source: io::Error,
+    },
+
+    /// Failed to stream the archive to or from the pod
+    #[error("failed to stream tar archive: {0}")]
+    Stream(#[source] io::Error),
+
+    /// Failed to create the archive of local files, or to extract the archive sent by the pod
+    #[error("failed to process tar archive: {0}")]
+    Archive(#[source] io::Error),
+
+    /// The archive sent by the pod contains a path outside of the copied directory
+    #[error("refusing to extract unsafe path {0:?}")]
+    UnsafePath(String),
+
+    /// The `tar` command failed in the container
+    ///
+    /// This is returned when the [`Status`] of the command is not a success,
+    /// which includes a missing `tar` binary in the container.
+    #[error("remote tar failed: {message}")]
+    RemoteTar {
+        /// Message of the status
+        message: String,
+        /// Output of `tar` on stderr
+        stderr: String,
+        /// The status returned by the apiserver
+        status: Box<Status>,
+    },
+
+    /// The connection to the remote process failed
+    #[error("remote command failed: {0}")]
+    RemoteCommand(#[source] Box<remote_command::Error>),
+}
+
+/// Methods for copying files with a container that has `tar`.
+impl<K> Api<K>
+where
+    K: Clone + serde::de::DeserializeOwned + Execute,
+{
+    /// Copy a local file or directory into a pod
+    ///
+    /// Directories are copied recursively along with file modes.
+    /// The archive is streamed to `tar` in the default container of the pod, which must have `tar` installed.
+    ///
+    /// As with `kubectl cp`, `remote_path` is the path of the copy, unless it ends with `/`,
+    /// in which case the copy is placed inside that directory with the name of the local path.
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # use kube::{api::Api, Client};
+    /// # let client: Client = todo!();
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// pods.copy_to("my-pod", "./config", "/etc/app/config").await?;
+    /// pods.copy_from("my-pod", "/var/log/app", "./logs").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_to(&self, name: &str, local_path: impl AsRef<Path>, remote_path: &str) -> Result<()> {
+        let local_path = local_path.as_ref().to_path_buf();
+        let (dir, entry) = if let Some(dir) = remote_path.strip_suffix('/') {
+            let entry = local_path.file_name().and_then(|n| n.to_str()).ok_or_else(|| {
+                Error::Copy(CopyError::Local {
+                    path: local_path.clone(),
+                    source: io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"),
+                })
+            })?;
+            (if dir.is_empty() { "/" } else { dir }, entry.to_string())
+        } else {
+            let (dir, entry) = split_remote_path(remote_path);
+            (dir, entry.to_string())
+        };
+
+        let ap = AttachParams::default().stdin(true).stdout(false).stderr(true);
+        let mut process = self.exec(name, ["tar", "xmf", "-", "-C", dir], &ap).await?;
+        let stdin = SyncIoBridge::new(process.stdin().expect("stdin is attached"));
+        let write = blocking(move || {
+            let mut stdin = write_archive(stdin, &local_path, &entry)?;
+            stdin.shutdown().map_err(CopyError::Stream)
+        });
+        let (written, stderr) = tokio::join!(write, read_stderr(&mut process));
+        // a failing tar stops reading stdin, so prefer its status over write errors
+        finish(process, stderr).await.map_err(Error::Copy)?;
+        written.map_err(Error::Copy)
+    }
+
+    /// Copy a file or directory from a pod to the local filesystem
+    ///
+    /// Directories are copied recursively along with file modes. Symbolic links and other special files are skipped.
+    /// The archive is streamed from `tar` in the default container of the pod, which must have `tar` installed.
+    ///
+    /// The copy is written to `local_path`, and existing files are overwritten.
+    /// See [`Api::copy_to`] for an example.
+    pub async fn copy_from(&self, name: &str, remote_path: &str, local_path: impl AsRef<Path>) -> Result<()> {
+        let local_path = local_path.as_ref().to_path_buf();
+        let (dir, entry) = split_remote_path(remote_path);
+
+        let ap = AttachParams::default().stdout(true).stderr(true);
+        let mut process = self.exec(name, ["tar", "cf", "-", "-C", dir, entry], &ap).await?;
+        let stdout = SyncIoBridge::new(process.stdout().expect("stdout is attached"));
+        let entry = entry.to_string();
+        let read = blocking(move || read_archive(stdout, &entry, &local_path));
+        let (extracted, stderr) = tokio::join!(read, read_stderr(&mut process));
+        // a failing tar produces an empty or truncated archive, so prefer its status over read errors
+        finish(process, stderr).await.map_err(Error::Copy)?;
+        extracted.map_err(Error::Copy)
+    }
+}
+
+/// Split a remote path into the directory to run `tar` in and the name of the entry
+fn split_remote_path(path: &str) -> (&str, &str) {
+    let path = path.trim_end_matches('/');
+    match path.rsplit_once('/') {
+        // the root directory itself
+        None if path.is_empty() => ("/", "."),
+        Some(("", entry)) => ("/", entry),
+        Some((dir, entry)) => (dir, entry),
+        None => (".", path),
+    }
+}
+
+/// Run the synchronous archive handling on the blocking thread pool
+async fn blocking<F>(f: F) -> Result<(), CopyError>
+where
+    F: FnOnce() -> Result<(), CopyError> + Send + 'static,
+{
+    tokio::task::spawn_blocking(f)
+        .await
+        .unwrap_or_else(|err| Err(CopyError::Stream(io::Error::other(err))))
+}
+
+async fn read_stderr(process: &mut AttachedProcess) -> String {
+    let mut stderr = String::new();
+    if let Some(mut reader) = process.stderr() {
+        if let Err(err) = reader.read_to_string(&mut stderr).await {
+            tracing::debug!(%err, "failed to read stderr of tar");
+        }
+    }
+    stderr
+}
+
+/// Wait for the remote tar to exit, and turn a failure into an error
+async fn finish(mut process: AttachedProcess, stderr: String) -> Result<(), CopyError> {
+    let status = match process.take_status() {
+        Some(status) => status.await,
+        None => None,
+    };
+    process
+        .join()
+        .await
+        .map_err(|err| CopyError::RemoteCommand(Box::new(err)))?;
+    match status {
+        Some(status) if status.status.as_deref() != Some("Success") => Err(CopyError::RemoteTar {
+            message: status
+                .message
+                .clone()
+                .unwrap_or_else(|| stderr.trim().to_string()),
+            stderr,
+            status: Box::new(status),
+        }),
+        // older apiservers close the connection when stdin is closed, before sending a status
+        _ => Ok(()),
+    }
+}
+
+/// Write a tar archive of `path` with the entry name `name` into `writer`
+fn write_archive<W: io::Write>(writer: W, path: &Path, name: &str) -> Result<W, CopyError> {
+    let meta = std::fs::symlink_metadata(path).map_err(|source| CopyError::Local {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    let mut builder = tar::Builder::new(writer);
+    builder.follow_symlinks(false);
+    let appended = if meta.is_dir() {
+        builder.append_dir_all(name, path)
+    } else {
+        builder.append_path_with_name(path, name)
+    };
+    appended.map_err(CopyError::Archive)?;
+    builder.into_inner().map_err(CopyError::Archive)
+}
+
+/// Extract a tar archive whose entries are all named `name` or below it into `dest`
+fn read_archive<R: io::Read>(reader: R, name: &str, dest: &Path) -> Result<(), CopyError> {
+    let mut archive = tar::Archive::new(reader);
+    // modes of directories are set last, so that read-only directories can be filled
+    let mut dir_modes = vec![];
+    for entry in archive.entries().map_err(CopyError::Archive)? {
+        let mut entry = entry.map_err(CopyError::Archive)?;
+        let entry_name = entry.path().map_err(CopyError::Archive)?.display().to_string();
+        let relative = entry_path(&entry_name, name)?;
+        // the copied entry itself, which is the only entry when copying a single file
+        let path = if relative.as_os_str().is_empty() {
+            dest.to_path_buf()
+        } else {
+            dest.join(relative)
+        };
+        let local_err = |source| CopyError::Local {
+            path: path.clone(),
+            source,
+        };
+
+        let kind = entry.header().entry_type();
+        if kind.is_file() || kind.is_contiguous() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent).map_err(local_err)?;
+            }
+            entry.unpack(&path).map_err(CopyError::Archive)?;
+        } else if kind.is_dir() {
+            std::fs::create_dir_all(&path).map_err(local_err)?;
+            dir_modes.push((path, entry.header().mode().map_err(CopyError::Archive)?));
+        } else {
+            tracing::warn!(entry = %entry_name, kind = ?kind, "skipping unsupported file type");
+        }
+    }
+    for (path, mode) in dir_modes.into_iter().rev() {
+        set_mode(&path, mode).map_err(|source| CopyError::Local { path, source })?;
+    }
+    // drain the end of the archive, so that the status can be received
+    io::copy(&mut archive.into_inner(), &mut io::sink()).map_err(CopyError::Stream)?;
+    Ok(())
+}
+
+/// Path of an entry relative to the copied entry `name`, rejecting anything outside of it
+fn entry_path(entry: &str, name: &str) -> Result<PathBuf, CopyError> {
+    let unsafe_path = || CopyError::UnsafePath(entry.to_string());
+    let not_current = |c: &Component| *c != Component::CurDir;
+    let mut components = Path::new(entry).components().filter(not_current);
+    for expected in Path::new(name).components().filter(not_current) {
+        if components.next() != Some(expected) {
+            return Err(unsafe_path());
+        }
+    }
+    components
+        .map(|c| match c {
+            Component::Normal(part) => Ok(part),
+            _ => Err(unsafe_path()),
+        })
+        .collect()
+}
+
+#[cfg(unix)]
+fn set_mode(path: &Path, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    // setuid, setgid and sticky bits are not restored, like tar does for unprivileged users
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode & 0o777))
+}
+
+#[cfg(not(unix))]
+fn set_mode(_path: &Path, _mode: u32) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remote_paths() {
+        assert_eq!(split_remote_path("/tmp/foo"), ("/tmp", "foo"));
+        assert_eq!(split_remote_path("/tmp/foo/"), ("/tmp", "foo"));
+        assert_eq!(split_remote_path("/foo"), ("/", "foo"));
+        assert_eq!(split_remote_path("foo"), (".", "foo"));
+        assert_eq!(split_remote_path("/"), ("/", "."));
+    }
+
+    #[test]
+    fn entry_paths_stay_inside_destination() {
+        assert_eq!(entry_path("foo", "foo").unwrap(), PathBuf::new());
+        assert_eq!(entry_path("./foo/a/b", "foo").unwrap(), PathBuf::from("a/b"));
+        assert_eq!(
+            entry_path("./etc/hosts", ".").unwrap(),
+            PathBuf::from("etc/hosts")
+        );
+        assert!(entry_path("foobar", "foo").is_err());
+        assert!(entry_path("foo/../../etc/passwd", "foo").is_err());
+        assert!(entry_path("/etc/passwd", "foo").is_err());
+        assert!(entry_path("../etc/passwd", ".").is_err());
+    }
+    #[test]
+    fn archive_roundtrip() {
+        let src = tempfile::tempdir().unwrap();
+        let root = src.path().join("data");
+        let long_dir = root.join("d".repeat(60)).join("e".repeat(60));
+        std::fs::create_dir_all(&long_dir).unwrap();
+        std::fs::write(root.join("small.txt"), "hello").unwrap();
+        std::fs::write(long_dir.join("big.bin"), vec![7; 1537]).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let exec = std::fs::Permissions::from_mode(0o751);
+            std::fs::set_permissions(root.join("small.txt"), exec).unwrap();
+        }
+
+        let archive = write_archive(vec![], &root, "data").unwrap();
+        let dest = tempfile::tempdir().unwrap();
+        let copy = dest.path().join("copy");
+        read_archive(archive.as_slice(), "data", &copy).unwrap();
+        assert_eq!(std::fs::read_to_string(copy.join("small.txt")).unwrap(), "hello");
+        let big = copy.join("d".repeat(60)).join("e".repeat(60)).join("big.bin");
+        assert_eq!(std::fs::read(big).unwrap(), vec![7; 1537]);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(copy.join("small.txt"))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o751);
+        }
+
+        // a single file is copied to the destination itself
+        let archive = write_archive(vec![], &root.join("small.txt"), "small.txt").unwrap();
+        let copied = dest.path().join("copied.txt");
+        read_archive(archive.as_slice(), "small.txt", &copied).unwrap();
+        assert_eq!(std::fs::read_to_string(&copied).unwrap(), "hello");
+
+        // entries outside of the copied path are rejected
+        let archive = write_archive(vec![], &root.join("small.txt"), "../small.txt");
+        assert!(matches!(archive, Err(CopyError::Archive(_))));
+        let mut builder = tar::Builder::new(vec![]);
+        let mut header = tar::Header::new_gnu();
+        header.set_size(0);
+        let name = b"data/../../small.txt";
+        header.as_gnu_mut().unwrap().name[..name.len()].copy_from_slice(name);
+        header.set_cksum();
+        builder.append(&header, io::empty()).unwrap();
+        let archive = builder.into_inner().unwrap();
+        let err = read_archive(archive.as_slice(), "data", &copy);
+        assert!(matches!(err, Err(CopyError::UnsafePath(_))));
+    }
+}
@@ -3,7 +3,11 @@ use futures::Stream;
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
 
-use crate::{api::Api, Error, Result};
+use crate::{
+    api::{Api, Table},
+    client::Warning,
+    Error, Result,
+};
 use kube_core::{
     metadata::PartialObjectMeta, object::ObjectList, params::*, response::Status, ErrorResponse, WatchEvent,
 };
@@ -245,6 +249,66 @@ where
         self.client.request::<ObjectList<PartialObjectMeta<K>>>(req).await
     }
 
+    /// Get a named resource as a [`Table`] with the columns that `kubectl get` prints
+    ///
+    /// The columns are defined by the apiserver, and include the
+    /// `additionalPrinterColumns` of custom resources.
+    ///
+    /// ```no_run
+    /// # use kube::Api;
+    /// use k8s_openapi::api::core::v1::Pod;
+    ///
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::namespaced(client, "apps");
+    /// let table = pods.get_table("blog").await?;
+    /// for row in table.rows {
+    ///     println!("{:?}", row.cells);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// This function assumes that the object is expected to always exist, and returns [`Error`] if it does not.
+    pub async fn get_table(&self, name: &str) -> Result<Table> {
+        let mut req = self
+            .request
+            .get_table(name, &GetParams::default())
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("get_table");
+        self.client.request::<Table>(req).await
+    }
+
+    /// Get a list of resources as a [`Table`] with the columns that `kubectl get` prints
+    ///
+    /// Similar to [list](`Api::list`), you use this to get everything, or a
+    /// subset matching fields/labels. For example
+    ///
+    /// ```no_run
+    /// use kube::api::{Api, ListParams};
+    /// use k8s_openapi::api::core::v1::Pod;
+    ///
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::namespaced(client, "apps");
+    /// let table = pods.list_table(&ListParams::default()).await?;
+    /// let names: Vec<_> = table.column_definitions.iter().map(|c| c.name.as_str()).collect();
+    /// println!("{}", names.join("\t"));
+    /// for row in table.rows {
+    ///     let cells: Vec<_> = row.cells.iter().map(|c| c.to_string()).collect();
+    ///     println!("{}", cells.join("\t"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_table(&self, lp: &ListParams) -> Result<Table> {
+        let mut req = self.request.list_table(lp).map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("list_table");
+        self.client.request::<Table>(req).await
+    }
+
     /// Create a resource
     ///
     /// This function requires a type that Serializes to `K`, which can be:
@@ -384,6 +448,39 @@ where
         self.client.request::<K>(req).await
     }
 
+    /// Patch a subset of a resource's properties, and return the warnings of the apiserver
+    ///
+    /// Like [`Api::patch`], but also returns the warnings of the response. With
+    /// [`PatchParams::validation_warn`], these include the unknown and duplicate fields of the patch.
+    ///
+    /// ```no_run
+    /// use kube::api::{Api, PatchParams, Patch};
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::namespaced(client, "apps");
+    /// let patch = serde_json::json!({ "spec": { "activeDeadlineSecond": 5 } });
+    /// let params = PatchParams::default().validation_warn();
+    /// let (pod, warnings) = pods.patch_with_warnings("blog", &params, &Patch::Merge(&patch)).await?;
+    /// for warning in warnings {
+    ///     println!("{warning}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`PatchParams::validation_warn`]: super::PatchParams::validation_warn
+    pub async fn patch_with_warnings<P: Serialize + Debug>(
+        &self,
+        name: &str,
+        pp: &PatchParams,
+        patch: &Patch<P>,
+    ) -> Result<(K, Vec<Warning>)> {
+        let mut req = self.request.patch(name, pp, patch).map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("patch");
+        self.client.request_with_warnings::<K>(req).await
+    }
+
     /// Patch a metadata subset of a resource's properties from [`PartialObjectMeta`]
     ///
     /// Takes a [`Patch`] along with [`PatchParams`] for the call.
@@ -434,6 +531,25 @@ where
         self.client.request::<PartialObjectMeta<K>>(req).await
     }
 
+    /// Patch a metadata subset of a resource's properties, and return the warnings of the apiserver
+    ///
+    /// Like [`Api::patch_metadata`], but also returns the warnings of the response.
+    pub async fn patch_metadata_with_warnings<P: Serialize + Debug>(
+        &self,
+        name: &str,
+        pp: &PatchParams,
+        patch: &Patch<P>,
+    ) -> Result<(PartialObjectMeta<K>, Vec<Warning>)> {
+        let mut req = self
+            .request
+            .patch_metadata(name, pp, patch)
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("patch_metadata");
+        self.client
+            .request_with_warnings::<PartialObjectMeta<K>>(req)
+            .await
+    }
+
     /// Replace a resource entirely with a new one
     ///
     /// This is used just like [`Api::create`], but with one additional instruction:
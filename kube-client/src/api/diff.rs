@@ -0,0 +1,354 @@
+//! Preview the changes a patch would make, similar to `kubectl diff`
+//!
+//! [`Api::diff`] is the primary entry point for this API.
+use std::fmt::Debug;
+
+use json_patch::PatchOperation;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+use crate::{Api, Error, Result};
+use kube_core::{
+    params::{Patch, PatchParams},
+    Resource,
+};
+
+/// Metadata fields that change on every write, and are left out of diffs
+const NOISY_METADATA: [&str; 3] = ["managedFields", "resourceVersion", "generation"];
+
+/// Number of unchanged lines shown around each change in [`ObjectDiff::unified`]
+const CONTEXT_LINES: usize = 3;
+
+impl<K> Api<K>
+where
+    K: Resource + Clone + DeserializeOwned + Serialize + Debug,
+{
+    /// Preview what a patch would change, without persisting it
+    ///
+    /// This runs the patch as a dry run, fetches the live object, and compares the two
+    /// after dropping fields that change on every write (`managedFields`, `resourceVersion` and `generation`).
+    /// If the object does not exist yet, every field of the dry run result is reported as added.
+    ///
+    /// ```no_run
+    /// use kube::api::{Api, Patch, PatchParams};
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let deploys: Api<Deployment> = Api::namespaced(client, "apps");
+    /// let patch = serde_json::json!({
+    ///     "apiVersion": "apps/v1",
+    ///     "kind": "Deployment",
+    ///     "spec": { "replicas": 3 }
+    /// });
+    /// let diff = deploys.diff("blog", &PatchParams::apply("myapp"), &Patch::Apply(&patch)).await?;
+    /// for change in diff.changes() {
+    ///     println!("{:?} {}", change.op, change.path);
+    /// }
+    /// print!("{}", diff.unified());
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the dry run is rejected by the apiserver, or if the live object cannot be fetched.
+    pub async fn diff<P: Serialize + Debug>(
+        &self,
+        name: &str,
+        pp: &PatchParams,
+        patch: &Patch<P>,
+    ) -> Result<ObjectDiff> {
+        let live = self.get_opt(name).await?;
+        let preview = self.patch(name, &pp.clone().dry_run(), patch).await?;
+        let live = live
+            .map(|obj| serde_json::to_value(obj).map(without_noisy_fields))
+            .transpose()
+            .map_err(Error::SerdeError)?;
+        let preview = serde_json::to_value(preview)
+            .map(without_noisy_fields)
+            .map_err(Error::SerdeError)?;
+        Ok(ObjectDiff::new(name, live, preview))
+    }
+}
+
+fn without_noisy_fields(mut obj: Value) -> Value {
+    if let Some(meta) = obj.get_mut("metadata").and_then(Value::as_object_mut) {
+        for field in NOISY_METADATA {
+            meta.remove(field);
+        }
+    }
+    obj
+}
+
+/// Differences between a live object and the result of a dry run patch
+///
+/// Returned by [`Api::diff`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ObjectDiff {
+    name: String,
+    live: Option<Value>,
+    preview: Value,
+    changes: Vec<DiffChange>,
+}
+
+/// A single changed field in an [`ObjectDiff`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffChange {
+    /// JSON pointer to the changed field, e.g. `/spec/replicas`
+    pub path: String,
+    /// Kind of the change
+    pub op: DiffOp,
+    /// Value of the field in the live object, unless the field was added
+    pub old: Option<Value>,
+    /// Value of the field after the patch, unless the field was removed
+    pub new: Option<Value>,
+}
+
+/// Kind of a [`DiffChange`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffOp {
+    /// The field does not exist in the live object
+    Added,
+    /// The field is removed by the patch
+    Removed,
+    /// The field has a different value after the patch
+    Changed,
+}
+
+impl ObjectDiff {
+    /// Compare a live object, if it exists, with the result of a patch
+    pub fn new(name: &str, live: Option<Value>, preview: Value) -> Self {
+        let base = live.clone().unwrap_or(Value::Null);
+        let changes = json_patch::diff(&base, &preview)
+            .0
+            .into_iter()
+            .filter_map(|op| {
+                let (path, op, new) = match op {
+                    PatchOperation::Add(op) => (op.path.to_string(), DiffOp::Added, Some(op.value)),
+                    PatchOperation::Remove(op) => (op.path.to_string(), DiffOp::Removed, None),
+                    PatchOperation::Replace(op)
+                        if base.pointer(op.path.as_str()).is_some_and(|v| !v.is_null()) =>
+                    {
+                        (op.path.to_string(), DiffOp::Changed, Some(op.value))
+                    }
+                    PatchOperation::Replace(op) => (op.path.to_string(), DiffOp::Added, Some(op.value)),
+                    // not produced by json_patch::diff
+                    _ => return None,
+                };
+                let old = base.pointer(&path).filter(|v| !v.is_null()).cloned();
+                Some(DiffChange { path, op, old, new })
+            })
+            .collect();
+        Self {
+            name: name.to_string(),
+            live,
+            preview,
+            changes,
+        }
+    }
+
+    /// Returns true if the patch would not change the object
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Returns the changed fields
+    pub fn changes(&self) -> &[DiffChange] {
+        &self.changes
+    }
+
+    /// Returns the live object without noisy fields, or `None` if the patch would create it
+    pub fn live(&self) -> Option<&Value> {
+        self.live.as_ref()
+    }
+
+    /// Returns the object as it would be after the patch, without noisy fields
+    pub fn preview(&self) -> &Value {
+        &self.preview
+    }
+
+    /// Renders the diff of both objects as YAML in the unified format used by `diff -u` and `kubectl diff`
+    ///
+    /// Returns an empty string if there are no changes.
+    pub fn unified(&self) -> String {
+        if self.is_empty() {
+            return String::new();
+        }
+        let live = match &self.live {
+            Some(live) => serde_yaml::to_string(live).unwrap_or_default(),
+            None => String::new(),
+        };
+        let preview = serde_yaml::to_string(&self.preview).unwrap_or_default();
+        let old: Vec<_> = live.lines().collect();
+        let new: Vec<_> = preview.lines().collect();
+        let header = format!("--- live/{name}\n+++ merged/{name}\n", name = self.name);
+        header + &unified_hunks(&line_diff(&old, &new), CONTEXT_LINES)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Line<'a> {
+    Same(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+impl Line<'_> {
+    fn in_old(self) -> bool {
+        !matches!(self, Line::Added(_))
+    }
+
+    fn in_new(self) -> bool {
+        !matches!(self, Line::Removed(_))
+    }
+}
+
+/// Line based diff using the longest common subsequence, after trimming the common prefix and suffix
+fn line_diff<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<Line<'a>> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+
+    // lcs[i][j] is the length of the longest common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut lines: Vec<_> = old[..prefix].iter().map(|l| Line::Same(l)).collect();
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            lines.push(Line::Same(a[i]));
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            lines.push(Line::Removed(a[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Added(b[j]));
+            j += 1;
+        }
+    }
+    lines.extend(old[old.len() - suffix..].iter().map(|l| Line::Same(l)));
+    lines
+}
+
+/// Group changed lines into hunks with `context` unchanged lines around them
+fn unified_hunks(lines: &[Line], context: usize) -> String {
+    let changed: Vec<usize> = (0..lines.len())
+        .filter(|&i| !matches!(lines[i], Line::Same(_)))
+        .collect();
+    let mut out = String::new();
+    let mut idx = 0;
+    while idx < changed.len() {
+        // extend the hunk while the next change is within reach of the context
+        let mut last = idx;
+        while last + 1 < changed.len() && changed[last + 1] - changed[last] <= 2 * context {
+            last += 1;
+        }
+        let from = changed[idx].saturating_sub(context);
+        let to = (changed[last] + context + 1).min(lines.len());
+        let hunk = &lines[from..to];
+
+        let old_start = lines[..from].iter().filter(|l| l.in_old()).count();
+        let new_start = lines[..from].iter().filter(|l| l.in_new()).count();
+        let old_len = hunk.iter().filter(|l| l.in_old()).count();
+        let new_len = hunk.iter().filter(|l| l.in_new()).count();
+        // line numbers are 1-based, except for empty ranges which refer to the line before
+        let start = |start: usize, len: usize| if len == 0 { start } else { start + 1 };
+        out += &format!(
+            "@@ -{},{} +{},{} @@\n",
+            start(old_start, old_len),
+            old_len,
+            start(new_start, new_len),
+            new_len
+        );
+        for line in hunk {
+            let (sign, text) = match line {
+                Line::Same(text) => (' ', text),
+                Line::Removed(text) => ('-', text),
+                Line::Added(text) => ('+', text),
+            };
+            out.push(sign);
+            out += text;
+            out.push('\n');
+        }
+        idx = last + 1;
+    }
+    out
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn diff_reports_changed_fields_without_noise() {
+        let live = json!({
+            "metadata": { "name": "blog", "resourceVersion": "1", "generation": 1, "labels": { "app": "blog" } },
+            "spec": { "replicas": 1, "paused": true }
+        });
+        let preview = json!({
+            "metadata": { "name": "blog", "resourceVersion": "2", "generation": 2, "managedFields": [],
+                          "labels": { "app": "blog", "tier": "web" } },
+            "spec": { "replicas": 3 }
+        });
+        let diff = ObjectDiff::new(
+            "blog",
+            Some(without_noisy_fields(live)),
+            without_noisy_fields(preview),
+        );
+        let mut changes: Vec<_> = diff
+            .changes()
+            .iter()
+            .map(|c| (c.path.as_str(), c.op, c.old.clone(), c.new.clone()))
+            .collect();
+        changes.sort_by_key(|c| c.0);
+        assert_eq!(changes, [
+            ("/metadata/labels/tier", DiffOp::Added, None, Some(json!("web"))),
+            ("/spec/paused", DiffOp::Removed, Some(json!(true)), None),
+            ("/spec/replicas", DiffOp::Changed, Some(json!(1)), Some(json!(3))),
+        ]);
+        assert!(!diff.is_empty());
+    }
+
+    #[test]
+    fn missing_objects_are_added() {
+        let diff = ObjectDiff::new("blog", None, json!({ "spec": { "replicas": 3 } }));
+        assert_eq!(diff.changes().len(), 1);
+        assert_eq!(diff.changes()[0].op, DiffOp::Added);
+        assert_eq!(
+            diff.unified(),
+            "--- live/blog\n+++ merged/blog\n@@ -0,0 +1,2 @@\n+spec:\n+  replicas: 3\n"
+        );
+    }
+
+    #[test]
+    fn unified_diff_has_context() {
+        let old: Vec<_> = (1..=10).map(|i| format!("line{i}")).collect();
+        let mut new = old.clone();
+        new[4] = "changed".into();
+        new.push("line11".into());
+        let old: Vec<_> = old.iter().map(String::as_str).collect();
+        let new: Vec<_> = new.iter().map(String::as_str).collect();
+        let rendered = unified_hunks(&line_diff(&old, &new), 1);
+        assert_eq!(
+            rendered,
+            "@@ -4,3 +4,3 @@\n line4\n-line5\n+changed\n line6\n@@ -10,1 +10,2 @@\n line10\n+line11\n"
+        );
+    }
+}
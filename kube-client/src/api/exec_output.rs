@@ -0,0 +1,217 @@
+use std::{fmt::Debug, time::Duration};
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+
+use crate::{
+    api::{remote_command, Api, AttachParams, Execute},
+    Error, Result,
+};
+
+/// Errors from [`Api::exec_output`]
+#[derive(Debug, Error)]
+pub enum ExecError {
+    /// The command did not complete within the timeout of the [`AttachParams`]
+    #[error("command did not complete within {0:?}")]
+    Timeout(Duration),
+
+    /// Failed to read the output of the command
+    #[error("failed to read output: {0}")]
+    ReadOutput(#[source] std::io::Error),
+
+    /// The command could not be run, for example because the executable was not found
+    ///
+    /// Commands that run and exit with a non-zero code are not errors,
+    /// and return an [`ExecOutput`] with the [`ExecOutput::exit_code`] instead.
+    #[error("command failed: {message}")]
+    Failed {
+        /// Message of the status
+        message: String,
+        /// The status returned by the apiserver
+        status: Box<Status>,
+    },
+
+    /// The exit code in the status is not an integer
+    #[error("invalid exit code {0:?}")]
+    InvalidExitCode(String),
+
+    /// The connection closed before the apiserver sent the status of the command
+    #[error("connection closed without a command status")]
+    MissingStatus,
+
+    /// The connection to the remote process failed
+    #[error("remote command failed: {0}")]
+    RemoteCommand(#[source] Box<remote_command::Error>),
+}
+
+/// The collected output of a command run with [`Api::exec_output`]
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ExecOutput {
+    /// Everything written to stdout
+    pub stdout: Vec<u8>,
+    /// Everything written to stderr
+    pub stderr: Vec<u8>,
+    /// The exit code of the command
+    pub exit_code: i32,
+}
+
+impl ExecOutput {
+    /// Whether the command exited with code `0`
+    pub fn success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// Methods for running commands to completion.
+impl<K> Api<K>
+where
+    K: Clone + serde::de::DeserializeOwned + Execute,
+{
+    /// Run a command in a pod, and collect its output and exit code
+    ///
+    /// When [`AttachParams::stdin_data`] is set, it is written to the stdin of the command,
+    /// which is then closed. [`AttachParams::timeout`] limits how long the command can run.
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # use kube::{api::{Api, AttachParams}, Client};
+    /// # use std::time::Duration;
+    /// # let client: Client = todo!();
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// let ap = AttachParams::default()
+    ///     .stdin_data("hello")
+    ///     .timeout(Duration::from_secs(10));
+    /// let output = pods.exec_output("blog", ["sh", "-c", "cat; exit 3"], &ap).await?;
+    /// assert_eq!(output.stdout, b"hello");
+    /// assert_eq!(output.exit_code, 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn exec_output<I, T>(&self, name: &str, command: I, ap: &AttachParams) -> Result<ExecOutput>
+    where
+        I: IntoIterator<Item = T> + Debug,
+        T: Into<String>,
+    {
+        let mut process = self.exec(name, command, ap).await?;
+        let stdin = process.stdin();
+        let stdout = process.stdout();
+        let stderr = process.stderr();
+        let status = process.take_status();
+        let collect = async {
+            let write = async {
+                if let Some(mut stdin) = stdin {
+                    if let Some(data) = &ap.stdin_data {
+                        stdin.write_all(data).await?;
+                    }
+                    stdin.shutdown().await?;
+                }
+                Ok::<_, std::io::Error>(())
+            };
+            let (written, stdout, stderr) = tokio::join!(write, read_to_end(stdout), read_to_end(stderr));
+            if let Err(err) = written {
+                // the command may exit without reading stdin, so prefer its status
+                tracing::debug!(%err, "failed to write stdin of command");
+            }
+            let status = match status {
+                Some(status) => status.await,
+                None => None,
+            };
+            Ok::<_, ExecError>((stdout?, stderr?, status))
+        };
+        let collected = match ap.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, collect)
+                .await
+                .unwrap_or(Err(ExecError::Timeout(timeout))),
+            None => collect.await,
+        };
+        let (stdout, stderr, status) = match collected {
+            Ok(collected) => collected,
+            Err(err) => {
+                process.abort();
+                return Err(Error::Exec(err));
+            }
+        };
+        process
+            .join()
+            .await
+            .map_err(|err| Error::Exec(ExecError::RemoteCommand(Box::new(err))))?;
+        let status = status.ok_or(Error::Exec(ExecError::MissingStatus))?;
+        let exit_code = exit_code(status).map_err(Error::Exec)?;
+        Ok(ExecOutput {
+            stdout,
+            stderr,
+            exit_code,
+        })
+    }
+}
+
+async fn read_to_end(reader: Option<impl AsyncRead + Unpin>) -> Result<Vec<u8>, ExecError> {
+    let mut buf = Vec::new();
+    if let Some(mut reader) = reader {
+        reader
+            .read_to_end(&mut buf)
+            .await
+            .map_err(ExecError::ReadOutput)?;
+    }
+    Ok(buf)
+}
+
+/// Find the exit code in the status of a command
+///
+/// Failing commands have the `NonZeroExitCode` reason, with the code in the `ExitCode` cause.
+fn exit_code(status: Status) -> Result<i32, ExecError> {
+    if status.status.as_deref() == Some("Success") {
+        return Ok(0);
+    }
+    if status.reason.as_deref() == Some("NonZeroExitCode") {
+        let code = status
+            .details
+            .iter()
+            .flat_map(|details| details.causes.iter().flatten())
+            .find(|cause| cause.reason.as_deref() == Some("ExitCode"))
+            .and_then(|cause| cause.message.as_deref());
+        if let Some(code) = code {
+            return code
+                .parse()
+                .map_err(|_| ExecError::InvalidExitCode(code.to_string()));
+        }
+    }
+    Err(ExecError::Failed {
+        message: status.message.clone().unwrap_or_default(),
+        status: Box::new(status),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(value: serde_json::Value) -> Status {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn exit_code_from_status() {
+        assert_eq!(
+            exit_code(status(serde_json::json!({ "status": "Success" }))).unwrap(),
+            0
+        );
+
+        let failed = status(serde_json::json!({
+            "status": "Failure",
+            "message": "command terminated with non-zero exit code: error executing command [sh -c exit 3], exit code 3",
+            "reason": "NonZeroExitCode",
+            "details": { "causes": [{ "reason": "ExitCode", "message": "3" }] }
+        }));
+        assert_eq!(exit_code(failed).unwrap(), 3);
+
+        let not_found = status(serde_json::json!({
+            "status": "Failure",
+            "message": "executable file not found in $PATH",
+            "reason": "InternalError"
+        }));
+        assert!(matches!(exit_code(not_found), Err(ExecError::Failed { .. })));
+    }
+}
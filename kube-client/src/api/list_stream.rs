@@ -0,0 +1,377 @@
+use std::{fmt::Debug, future::Future};
+
+use either::Either;
+use futures::{stream, Stream, TryStreamExt};
+use serde::de::DeserializeOwned;
+
+use crate::{api::Api, Error, Result};
+use kube_core::{
+    metadata::{ListMeta, PartialObjectMeta},
+    object::ObjectList,
+    params::ListParams,
+    Resource,
+};
+
+/// The page size of list streams when [`ListParams::limit`] is not set, the same as `kubectl`
+const DEFAULT_PAGE_SIZE: u32 = 500;
+
+/// How many times a list stream restarts after its continue token expired
+const MAX_RESTARTS: usize = 3;
+
+/// How a paginated list stream handles a continue token that has expired
+///
+/// Continue tokens expire after a few minutes, when the apiserver responds with HTTP 410 "Gone".
+/// A list restarts at most 3 times.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExpiredContinue {
+    /// Restart the list while no more objects were returned than the apiserver reported as remaining
+    ///
+    /// The list resumes otherwise, or when the apiserver did not report a `remainingItemCount`,
+    /// which it does not for lists with selectors.
+    #[default]
+    Auto,
+
+    /// Restart the list from a fresh snapshot
+    ///
+    /// Objects that were returned before the token expired are returned again.
+    /// The stream fails when the token expires after the last restart.
+    Restart,
+
+    /// Continue the list from a fresh snapshot, after the last object that was returned
+    ///
+    /// This uses the fresh continue token that the apiserver sends with the expiry, or lists from
+    /// the start and skips the objects up to the last one that was returned. The apiserver lists
+    /// objects in the order of their namespace and name, so objects are returned at most once,
+    /// but the list is not a consistent snapshot. Objects that are created during the list may be missed.
+    Resume,
+}
+
+/// The result of a list request, or the error and list metadata of its HTTP 410 "Gone" response
+type ListResult<T> = Result<Either<ObjectList<T>, (Error, ListMeta)>>;
+
+/// Paginated lists
+impl<K> Api<K>
+where
+    K: Resource + Clone + DeserializeOwned + Debug + Send + 'static,
+{
+    /// Stream a list of resources, fetching them page by page
+    ///
+    /// Pages have [`ListParams::limit`] objects, or 500 when it is not set, and the stream follows
+    /// the continue token of each page until the list is complete. When a continue token expires,
+    /// the list continues as described by [`ExpiredContinue::Auto`].
+    ///
+    /// ```no_run
+    /// use futures::{StreamExt, TryStreamExt};
+    /// use kube::api::{Api, ListParams, ResourceExt};
+    /// use k8s_openapi::api::core::v1::Pod;
+    ///
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::all(client);
+    /// let mut stream = pods.list_stream(&ListParams::default().limit(100)).boxed();
+    /// while let Some(p) = stream.try_next().await? {
+    ///     println!("Found Pod: {}", p.name_any());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_stream(&self, lp: &ListParams) -> impl Stream<Item = Result<K>> + Send + 'static {
+        self.list_stream_with(lp, ExpiredContinue::default())
+    }
+
+    /// Stream a list of resources, and handle an expired continue token as configured
+    ///
+    /// See [`Api::list_stream`] and [`ExpiredContinue`].
+    pub fn list_stream_with(
+        &self,
+        lp: &ListParams,
+        expired: ExpiredContinue,
+    ) -> impl Stream<Item = Result<K>> + Send + 'static {
+        let api = self.clone();
+        paginate(lp.clone(), expired, move |lp| {
+            let api = api.clone();
+            async move {
+                let mut req = api.request.list(&lp).map_err(Error::BuildRequest)?;
+                req.extensions_mut().insert("list");
+                api.client.request_list::<ObjectList<K>>(req).await
+            }
+        })
+    }
+
+    /// Stream a list of resources that contain only their metadata, fetching them page by page
+    ///
+    /// See [`Api::list_stream`] and [`Api::list_metadata`].
+    pub fn list_metadata_stream(
+        &self,
+        lp: &ListParams,
+    ) -> impl Stream<Item = Result<PartialObjectMeta<K>>> + Send + 'static {
+        self.list_metadata_stream_with(lp, ExpiredContinue::default())
+    }
+
+    /// Stream a list of resources that contain only their metadata, and handle an expired continue token as configured
+    ///
+    /// See [`Api::list_metadata_stream`] and [`ExpiredContinue`].
+    pub fn list_metadata_stream_with(
+        &self,
+        lp: &ListParams,
+        expired: ExpiredContinue,
+    ) -> impl Stream<Item = Result<PartialObjectMeta<K>>> + Send + 'static {
+        let api = self.clone();
+        paginate(lp.clone(), expired, move |lp| {
+            let api = api.clone();
+            async move {
+                let mut req = api.request.list_metadata(&lp).map_err(Error::BuildRequest)?;
+                req.extensions_mut().insert("list_metadata");
+                api.client
+                    .request_list::<ObjectList<PartialObjectMeta<K>>>(req)
+                    .await
+            }
+        })
+    }
+}
+
+fn paginate<T, F, Fut>(mut lp: ListParams, expired: ExpiredContinue, list: F) -> impl Stream<Item = Result<T>>
+where
+    T: Resource + Clone,
+    F: Fn(ListParams) -> Fut,
+    Fut: Future<Output = ListResult<T>>,
+{
+    lp.limit = lp.limit.or(Some(DEFAULT_PAGE_SIZE));
+    let pager = Pager {
+        lp,
+        list,
+        expired,
+        last: None,
+        resume_after: None,
+        listed: 0,
+        remaining: None,
+        restarts: 0,
+        done: false,
+    };
+    stream::try_unfold(pager, |mut pager| async move {
+        let page = pager.next_page().await?;
+        Ok(page.map(|items| (stream::iter(items.into_iter().map(Ok)), pager)))
+    })
+    .try_flatten()
+}
+
+struct Pager<F> {
+    lp: ListParams,
+    list: F,
+    expired: ExpiredContinue,
+    /// The key of the last object that was returned
+    last: Option<String>,
+    /// Skip the objects up to this key, after resuming an expired list
+    resume_after: Option<String>,
+    /// How many objects were listed since the list started
+    listed: usize,
+    /// The `remainingItemCount` of the last page
+    remaining: Option<i64>,
+    restarts: usize,
+    done: bool,
+}
+
+impl<F> Pager<F> {
+    async fn next_page<T, Fut>(&mut self) -> Result<Option<Vec<T>>>
+    where
+        T: Resource + Clone,
+        F: Fn(ListParams) -> Fut,
+        Fut: Future<Output = ListResult<T>>,
+    {
+        while !self.done {
+            match (self.list)(self.lp.clone()).await? {
+                Either::Left(list) => {
+                    self.lp.continue_token = list.metadata.continue_.filter(|token| !token.is_empty());
+                    self.done = self.lp.continue_token.is_none();
+                    self.remaining = list.metadata.remaining_item_count;
+                    self.listed += list.items.len();
+                    let mut items = list.items;
+                    if let Some(after) = &self.resume_after {
+                        items.retain(|obj| object_key(obj) > *after);
+                    }
+                    if let Some(last) = items.last() {
+                        self.last = Some(object_key(last));
+                    }
+                    return Ok(Some(items));
+                }
+                Either::Right((err, _)) if self.lp.continue_token.is_none() => return Err(err),
+                Either::Right((err, metadata)) => {
+                    if self.restart() {
+                        if self.restarts == MAX_RESTARTS {
+                            return Err(err);
+                        }
+                        tracing::debug!(
+                            restarts = self.restarts,
+                            "continue token expired, restarting list"
+                        );
+                        self.restarts += 1;
+                        self.lp.continue_token = None;
+                        self.resume_after = None;
+                        self.listed = 0;
+                    } else if let Some(token) = metadata.continue_.filter(|token| !token.is_empty()) {
+                        tracing::debug!("continue token expired, resuming list with a fresh token");
+                        self.lp.continue_token = Some(token);
+                    } else {
+                        tracing::debug!("continue token expired, resuming list after the last object");
+                        self.lp.continue_token = None;
+                        self.resume_after = self.last.clone();
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Whether to restart the list after its continue token expired
+    fn restart(&self) -> bool {
+        match self.expired {
+            ExpiredContinue::Restart => true,
+            ExpiredContinue::Resume => false,
+            ExpiredContinue::Auto => {
+                self.restarts < MAX_RESTARTS
+                    && self
+                        .remaining
+                        .is_some_and(|remaining| self.listed as i64 <= remaining)
+            }
+        }
+    }
+}
+
+/// The key that the apiserver orders lists by
+fn object_key<T: Resource>(obj: &T) -> String {
+    let meta = obj.meta();
+    let name = meta.name.as_deref().unwrap_or_default();
+    match &meta.namespace {
+        Some(ns) => format!("{ns}/{name}"),
+        None => name.to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use futures::TryStreamExt;
+    use http::{Request, Response};
+    use k8s_openapi::api::core::v1::Pod;
+    use kube_core::{ErrorResponse, ResourceExt};
+    use tower_test::mock;
+
+    use super::*;
+    use crate::{client::Body, Client};
+
+    fn page(names: &[&str], continue_token: &str, remaining: Option<i64>) -> Response<Body> {
+        let items = names
+            .iter()
+            .map(|name| serde_json::json!({ "metadata": { "name": name, "namespace": "default" } }))
+            .collect::<Vec<_>>();
+        let list = serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "PodList",
+            "metadata": { "continue": continue_token, "remainingItemCount": remaining },
+            "items": items,
+        });
+        Response::builder()
+            .body(Body::from(serde_json::to_vec(&list).unwrap()))
+            .unwrap()
+    }
+
+    fn expired(continue_token: &str) -> Response<Body> {
+        let status = serde_json::json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "metadata": { "continue": continue_token },
+            "status": "Failure",
+            "message": "The provided continue parameter is too old",
+            "reason": "Expired",
+            "code": 410,
+        });
+        Response::builder()
+            .status(410)
+            .body(Body::from(serde_json::to_vec(&status).unwrap()))
+            .unwrap()
+    }
+
+    async fn list_names(
+        expired: ExpiredContinue,
+        responses: Vec<(&'static str, Response<Body>)>,
+    ) -> Result<Vec<String>> {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            for (query, response) in responses {
+                let (request, send) = handle.next_request().await.expect("service not called");
+                assert_eq!(request.uri().query(), Some(query));
+                send.send_response(response);
+            }
+        });
+
+        let pods: Api<Pod> = Api::default_namespaced(Client::new(mock_service, "default"));
+        let lp = ListParams::default().limit(2);
+        let names = pods
+            .list_stream_with(&lp, expired)
+            .map_ok(|pod| pod.name_any())
+            .try_collect()
+            .await;
+        spawned.await.unwrap();
+        names
+    }
+
+    #[tokio::test]
+    async fn list_stream_restarts_expired_lists() {
+        let names = list_names(ExpiredContinue::Restart, vec![
+            ("&limit=2", page(&["a", "b"], "first", Some(1))),
+            ("&limit=2&continue=first", expired("fresh")),
+            ("&limit=2", page(&["a", "b"], "second", Some(1))),
+            ("&limit=2&continue=second", page(&["c"], "", None)),
+        ])
+        .await;
+        assert_eq!(names.unwrap(), ["a", "b", "a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn list_stream_resumes_expired_lists() {
+        let names = list_names(ExpiredContinue::Resume, vec![
+            ("&limit=2", page(&["a", "b"], "first", Some(1))),
+            ("&limit=2&continue=first", expired("fresh")),
+            ("&limit=2&continue=fresh", page(&["c"], "", None)),
+        ])
+        .await;
+        assert_eq!(names.unwrap(), ["a", "b", "c"]);
+
+        // Without a fresh token, the list starts again after the last object
+        let names = list_names(ExpiredContinue::Resume, vec![
+            ("&limit=2", page(&["a", "b"], "first", Some(1))),
+            ("&limit=2&continue=first", expired("")),
+            ("&limit=2", page(&["a", "b"], "second", Some(1))),
+            ("&limit=2&continue=second", page(&["c"], "", None)),
+        ])
+        .await;
+        assert_eq!(names.unwrap(), ["a", "b", "c"]);
+    }
+
+    #[tokio::test]
+    async fn list_stream_restarts_or_resumes_by_remaining_items() {
+        let names = list_names(ExpiredContinue::Auto, vec![
+            ("&limit=2", page(&["a", "b"], "first", Some(3))),
+            ("&limit=2&continue=first", expired("fresh")),
+            ("&limit=2", page(&["a", "b"], "second", Some(3))),
+            ("&limit=2&continue=second", page(&["c", "d"], "third", Some(1))),
+            ("&limit=2&continue=third", expired("fresh")),
+            ("&limit=2&continue=fresh", page(&["e"], "", None)),
+        ])
+        .await;
+        assert_eq!(names.unwrap(), ["a", "b", "a", "b", "c", "d", "e"]);
+    }
+
+    #[tokio::test]
+    async fn list_stream_caps_restarts() {
+        let mut responses = vec![];
+        for _ in 0..=MAX_RESTARTS {
+            responses.push(("&limit=2", page(&["a", "b"], "first", Some(1))));
+            responses.push(("&limit=2&continue=first", expired("")));
+        }
+        let err = list_names(ExpiredContinue::Restart, responses).await.unwrap_err();
+        assert!(matches!(err, Error::Api(ErrorResponse { code: 410, .. })));
+    }
+}
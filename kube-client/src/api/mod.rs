@@ -1,23 +1,41 @@
 //! API helpers for structured interaction with the Kubernetes API
 
 mod core_methods;
+mod list_stream;
+pub use list_stream::ExpiredContinue;
 #[cfg(feature = "ws")] mod remote_command;
 use std::fmt::Debug;
 
 #[cfg(feature = "ws")] pub use remote_command::{AttachedProcess, TerminalSize};
 #[cfg(feature = "ws")] mod portforward;
 #[cfg(feature = "ws")] pub use portforward::Portforwarder;
+#[cfg(feature = "ws")] mod portforward_session;
+#[cfg(feature = "ws")]
+pub use portforward_session::{PortForwardSession, PortForwardSessionError, PortForwardTarget};
+#[cfg(feature = "ws")] mod copy;
+#[cfg(feature = "ws")] pub use copy::CopyError;
+#[cfg(feature = "ws")] mod exec_output;
+#[cfg(feature = "ws")] pub use exec_output::{ExecError, ExecOutput};
 
 mod subresource;
 #[cfg(feature = "ws")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
 pub use subresource::{Attach, AttachParams, Ephemeral, Execute, Portforward};
-pub use subresource::{Evict, EvictParams, Log, LogParams, ScaleSpec, ScaleStatus};
+pub use subresource::{
+    Bind, Evict, EvictParams, Log, LogParams, Proxy, ProxyService, Resize, ScaleSpec, ScaleStatus,
+};
 
 mod util;
 
 pub mod entry;
 
+#[cfg(feature = "jsonpatch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jsonpatch")))]
+mod diff;
+#[cfg(feature = "jsonpatch")]
+#[cfg_attr(docsrs, doc(cfg(feature = "jsonpatch")))]
+pub use diff::{DiffChange, DiffOp, ObjectDiff};
+
 // Re-exports from kube-core
 #[cfg(feature = "admission")]
 #[cfg_attr(docsrs, doc(cfg(feature = "admission")))]
@@ -29,6 +47,7 @@ pub use kube_core::{
     metadata::{ListMeta, ObjectMeta, PartialObjectMeta, PartialObjectMetaExt, TypeMeta},
     object::{NotUsed, Object, ObjectList},
     request::Request,
+    table::Table,
     watch::WatchEvent,
     Resource, ResourceExt,
 };
@@ -38,7 +57,7 @@ pub use params::{
     ValidationDirective, VersionMatch, WatchParams,
 };
 
-use crate::Client;
+use crate::{client::RequestOptions, Client};
 /// The generic Api abstraction
 ///
 /// This abstracts over a [`Request`] and a type `K` so that
@@ -112,6 +131,15 @@ impl<K: Resource> Api<K> {
         Self::namespaced_with(client, &ns, dyntype)
     }
 
+    /// Create a scoped [`Api`] that applies [`RequestOptions`] to every request
+    ///
+    /// This includes watches and subresources. See [`Client::with_options`].
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.client = self.client.with_options(options);
+        self
+    }
+
     /// Consume self and return the [`Client`]
     pub fn into_client(self) -> Client {
         self.into()
@@ -126,6 +154,9 @@ impl<K: Resource> Api<K> {
 /// Api constructors for Resource implementors with Default DynamicTypes
 ///
 /// This generally means structs implementing `k8s_openapi::Resource`.
+///
+/// With the `protobuf` feature, these read Pods, Nodes, Endpoints and EndpointSlices in the protobuf encoding.
+/// The `_with` constructors, and raw requests of the [`Client`], keep using JSON.
 impl<K: Resource> Api<K>
 where
     <K as Resource>::DynamicType: Default,
@@ -155,6 +186,8 @@ where
     /// This variant **can only `list` and `watch` namespaced resources** and is commonly used with a `watcher`.
     /// If you need to create/patch/replace/get on a namespaced resource, you need a separate `Api::namespaced`.
     pub fn all(client: Client) -> Self {
+        #[cfg(feature = "protobuf")]
+        let client = client.with_protobuf(true);
         Self::all_with(client, &K::DynamicType::default())
     }
 
@@ -181,6 +214,8 @@ where
     where
         K: Resource<Scope = NamespaceResourceScope>,
     {
+        #[cfg(feature = "protobuf")]
+        let client = client.with_protobuf(true);
         let dyntype = K::DynamicType::default();
         let url = K::url_path(&dyntype, Some(ns));
         Self {
@@ -223,6 +258,12 @@ where
 
 impl<K> From<Api<K>> for Client {
     fn from(api: Api<K>) -> Self {
+        // raw requests of the client stay on JSON
+        #[cfg(feature = "protobuf")]
+        let api = Api {
+            client: api.client.with_protobuf(false),
+            ..api
+        };
         api.client
     }
 }
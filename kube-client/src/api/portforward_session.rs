@@ -0,0 +1,576 @@
+use std::{
+    collections::HashMap,
+    fmt,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+use k8s_openapi::{
+    api::{
+        apps::v1::Deployment,
+        core::v1::{Pod, Service},
+    },
+    apimachinery::pkg::util::intstr::IntOrString,
+};
+use kube_core::{
+    labels::{ParseExpressionError, Selector},
+    params::ListParams,
+};
+use thiserror::Error;
+use tokio::{net::TcpListener, task::JoinHandle};
+
+use crate::{
+    api::{Api, Portforwarder},
+    Client, Error, Result,
+};
+
+/// Errors from [`PortForwardSession`]
+#[derive(Debug, Error)]
+pub enum PortForwardSessionError {
+    /// Failed to bind a local port
+    #[error("failed to bind {addr}: {source}")]
+    Bind {
+        /// The local address
+        addr: SocketAddr,
+        /// The underlying error
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// The service has no selector, so it has no pods to forward to
+    #[error("{0} has no pod selector")]
+    NoSelector(PortForwardTarget),
+
+    /// The deployment has an invalid selector
+    #[error("{target} has an invalid selector: {source}")]
+    InvalidSelector {
+        /// The deployment
+        target: PortForwardTarget,
+        /// The underlying error
+        #[source]
+        source: ParseExpressionError,
+    },
+
+    /// None of the pods of the target are ready
+    #[error("{0} has no ready pods")]
+    NoReadyPods(PortForwardTarget),
+
+    /// The service does not expose the port
+    #[error("{target} does not expose port {port}")]
+    PortNotFound {
+        /// The service
+        target: PortForwardTarget,
+        /// The requested port of the service
+        port: u16,
+    },
+
+    /// The pod has no container port with the name of a service's `targetPort`
+    #[error("pod {pod} has no port named {name}")]
+    NamedPortNotFound {
+        /// The pod
+        pod: String,
+        /// The name of the port
+        name: String,
+    },
+}
+
+/// The resource that a [`PortForwardSession`] forwards to
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum PortForwardTarget {
+    /// A pod, with ports of its containers
+    Pod(String),
+    /// A ready pod of a service, with ports of the service
+    ///
+    /// Service ports are mapped to the `targetPort` of the pod, which may be a named container port.
+    Service(String),
+    /// A ready pod of a deployment, with ports of its containers
+    Deployment(String),
+}
+
+impl fmt::Display for PortForwardTarget {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pod(name) => write!(f, "pod/{name}"),
+            Self::Service(name) => write!(f, "service/{name}"),
+            Self::Deployment(name) => write!(f, "deployment/{name}"),
+        }
+    }
+}
+
+/// Forwards local TCP ports to a pod, a service or a deployment
+///
+/// This is the library equivalent of `kubectl port-forward`.
+/// Every accepted connection is forwarded through its own [`Api::portforward`] connection,
+/// to the pod that the target resolved to.
+/// When forwarding to that pod fails, e.g. because it was deleted, the target is resolved again
+/// to forward the connection to another ready pod.
+///
+/// Ports stop being forwarded when the session is dropped.
+///
+/// ```no_run
+/// use kube::{api::{PortForwardSession, PortForwardTarget}, Client};
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client: Client = todo!();
+/// let target = PortForwardTarget::Service("web".into());
+/// // forward localhost:8080 to port 80 of the service, and a free local port to its port 9090
+/// let session = PortForwardSession::bind(client, "default", target, &[(8080, 80), (0, 9090)]).await?;
+/// println!("metrics at http://{}", session.local_addr(9090).unwrap());
+/// tokio::signal::ctrl_c().await?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct PortForwardSession {
+    addrs: Vec<(SocketAddr, u16)>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl PortForwardSession {
+    /// Bind `(local_port, remote_port)` pairs on localhost, and forward their connections to the `target`
+    ///
+    /// A local port of `0` binds a free port, which can be found with [`PortForwardSession::local_addr`].
+    /// The target is resolved once before binding, to fail early if it has no ready pods.
+    pub async fn bind(
+        client: Client,
+        namespace: &str,
+        target: PortForwardTarget,
+        ports: &[(u16, u16)],
+    ) -> Result<Self> {
+        let remote_ports = ports.iter().map(|(_, remote)| *remote).collect();
+        let resolver = Arc::new(Resolver::new(client, namespace, target, remote_ports));
+        resolver.resolve().await?;
+
+        let mut listeners = Vec::with_capacity(ports.len());
+        for (local, remote) in ports {
+            let addr = SocketAddr::from(([127, 0, 0, 1], *local));
+            let listener = TcpListener::bind(addr)
+                .await
+                .and_then(|listener| Ok((listener.local_addr()?, listener)))
+                .map_err(|source| {
+                    Error::PortForwardSession(PortForwardSessionError::Bind { addr, source })
+                })?;
+            listeners.push((listener, *remote));
+        }
+        let addrs = listeners
+            .iter()
+            .map(|((addr, _), remote)| (*addr, *remote))
+            .collect();
+        let tasks = listeners
+            .into_iter()
+            .map(|((_, listener), remote)| tokio::spawn(accept_loop(listener, remote, resolver.clone())))
+            .collect();
+        Ok(Self { addrs, tasks })
+    }
+
+    /// The local address that forwards to `remote_port`
+    pub fn local_addr(&self, remote_port: u16) -> Option<SocketAddr> {
+        self.addrs
+            .iter()
+            .find(|(_, remote)| *remote == remote_port)
+            .map(|(addr, _)| *addr)
+    }
+
+    /// Stop accepting connections, and abort the forwarded connections
+    pub fn abort(&self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+impl Drop for PortForwardSession {
+    fn drop(&mut self) {
+        self.abort();
+    }
+}
+
+/// How long to wait before accepting connections again after an error
+const ACCEPT_ERROR_DELAY: Duration = Duration::from_secs(1);
+
+async fn accept_loop(listener: TcpListener, remote_port: u16, resolver: Arc<Resolver>) {
+    // aborts the forwarded connections when the session is dropped
+    let mut connections = tokio::task::JoinSet::new();
+    loop {
+        let (mut conn, peer) = match listener.accept().await {
+            Ok(accepted) => accepted,
+            Err(err) => {
+                tracing::warn!(
+                    error = &err as &dyn std::error::Error,
+                    "failed to accept connection"
+                );
+                // errors like running out of file descriptors persist for a while, so retrying at once would spin
+                tokio::time::sleep(ACCEPT_ERROR_DELAY).await;
+                continue;
+            }
+        };
+        let resolver = resolver.clone();
+        connections.spawn(async move {
+            if let Err(err) = forward(&resolver, remote_port, &mut conn).await {
+                tracing::warn!(%peer, remote_port, error = &*err as &dyn std::error::Error, "failed to forward connection");
+            }
+        });
+        while connections.try_join_next().is_some() {}
+    }
+}
+
+async fn forward(
+    resolver: &Resolver,
+    remote_port: u16,
+    conn: &mut tokio::net::TcpStream,
+) -> Result<(), tower::BoxError> {
+    let (mut forwarder, pod_port) = resolver.portforward(remote_port).await?;
+    let mut upstream = forwarder
+        .take_stream(pod_port)
+        .expect("forwarder has a stream for its port");
+    tokio::io::copy_bidirectional(conn, &mut upstream).await?;
+    drop(upstream);
+    forwarder.join().await?;
+    Ok(())
+}
+
+/// A pod to forward to, and the pod ports for the remote ports of the target
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Resolved {
+    pod: String,
+    ports: HashMap<u16, u16>,
+}
+
+struct Resolver {
+    pods: Api<Pod>,
+    services: Api<Service>,
+    deployments: Api<Deployment>,
+    target: PortForwardTarget,
+    remote_ports: Vec<u16>,
+    current: Mutex<Option<Resolved>>,
+}
+
+impl Resolver {
+    fn new(client: Client, namespace: &str, target: PortForwardTarget, remote_ports: Vec<u16>) -> Self {
+        Self {
+            pods: Api::namespaced(client.clone(), namespace),
+            services: Api::namespaced(client.clone(), namespace),
+            deployments: Api::namespaced(client, namespace),
+            target,
+            remote_ports,
+            current: Mutex::new(None),
+        }
+    }
+
+    /// Forward `remote_port` of the target, resolving the target again if forwarding to the current pod fails
+    async fn portforward(&self, remote_port: u16) -> Result<(Portforwarder, u16)> {
+        let resolved = self.resolve().await?;
+        let pod_port = resolved.ports[&remote_port];
+        match self.pods.portforward(&resolved.pod, &[pod_port]).await {
+            Ok(forwarder) => return Ok((forwarder, pod_port)),
+            // a pod target always resolves to the same pod
+            Err(err) if matches!(self.target, PortForwardTarget::Pod(_)) => return Err(err),
+            Err(err) => tracing::debug!(
+                pod = %resolved.pod,
+                target = %self.target,
+                error = &err as &dyn std::error::Error,
+                "failed to forward to pod"
+            ),
+        }
+        self.forget(&resolved);
+        let resolved = self.resolve().await?;
+        let pod_port = resolved.ports[&remote_port];
+        let forwarder = self.pods.portforward(&resolved.pod, &[pod_port]).await?;
+        Ok((forwarder, pod_port))
+    }
+
+    /// The current pod, or a newly resolved one
+    async fn resolve(&self) -> Result<Resolved> {
+        if let Some(resolved) = self.lock_current().clone() {
+            return Ok(resolved);
+        }
+        // not locked while resolving, so connections that race to resolve the target may resolve it more than once
+        let resolved = self.resolve_target().await?;
+        tracing::debug!(pod = %resolved.pod, target = %self.target, "forwarding to pod");
+        *self.lock_current() = Some(resolved.clone());
+        Ok(resolved)
+    }
+
+    /// Resolve the target again on the next connection, unless another connection already did
+    fn forget(&self, stale: &Resolved) {
+        let mut current = self.lock_current();
+        if current.as_ref() == Some(stale) {
+            *current = None;
+        }
+    }
+
+    fn lock_current(&self) -> std::sync::MutexGuard<'_, Option<Resolved>> {
+        self.current.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    async fn resolve_target(&self) -> Result<Resolved> {
+        let resolved = match &self.target {
+            PortForwardTarget::Pod(name) => Resolved {
+                pod: name.clone(),
+                ports: self.remote_ports.iter().map(|port| (*port, *port)).collect(),
+            },
+            PortForwardTarget::Deployment(name) => {
+                let deployment = self.deployments.get(name).await?;
+                let selector = deployment.spec.map(|spec| spec.selector).unwrap_or_default();
+                let selector = Selector::try_from(selector).map_err(|source| {
+                    Error::PortForwardSession(PortForwardSessionError::InvalidSelector {
+                        target: self.target.clone(),
+                        source,
+                    })
+                })?;
+                let pod = self.ready_pod(&selector).await?;
+                Resolved {
+                    pod: pod.metadata.name.unwrap_or_default(),
+                    ports: self.remote_ports.iter().map(|port| (*port, *port)).collect(),
+                }
+            }
+            PortForwardTarget::Service(name) => {
+                let service = self.services.get(name).await?;
+                let selector = service
+                    .spec
+                    .as_ref()
+                    .and_then(|spec| spec.selector.clone())
+                    .filter(|selector| !selector.is_empty())
+                    .ok_or_else(|| {
+                        Error::PortForwardSession(PortForwardSessionError::NoSelector(self.target.clone()))
+                    })?;
+                let pod = self.ready_pod(&selector.into_iter().collect()).await?;
+                let ports = self
+                    .remote_ports
+                    .iter()
+                    .map(|port| Ok((*port, service_target_port(&self.target, &service, &pod, *port)?)))
+                    .collect::<Result<_, PortForwardSessionError>>()
+                    .map_err(Error::PortForwardSession)?;
+                Resolved {
+                    pod: pod.metadata.name.unwrap_or_default(),
+                    ports,
+                }
+            }
+        };
+        Ok(resolved)
+    }
+
+    async fn ready_pod(&self, selector: &Selector) -> Result<Pod> {
+        let pods = self
+            .pods
+            .list(&ListParams::default().labels_from(selector))
+            .await?;
+        pods.items.into_iter().find(is_ready).ok_or_else(|| {
+            Error::PortForwardSession(PortForwardSessionError::NoReadyPods(self.target.clone()))
+        })
+    }
+}
+
+/// Whether the pod is ready and not being deleted
+fn is_ready(pod: &Pod) -> bool {
+    pod.metadata.deletion_timestamp.is_none()
+        && pod
+            .status
+            .as_ref()
+            .and_then(|status| status.conditions.as_ref())
+            .is_some_and(|conditions| {
+                conditions
+                    .iter()
+                    .any(|condition| condition.type_ == "Ready" && condition.status == "True")
+            })
+}
+
+/// The pod port that a service port is routed to
+fn service_target_port(
+    target: &PortForwardTarget,
+    service: &Service,
+    pod: &Pod,
+    port: u16,
+) -> Result<u16, PortForwardSessionError> {
+    let service_port = service
+        .spec
+        .as_ref()
+        .and_then(|spec| spec.ports.as_ref())
+        .and_then(|ports| ports.iter().find(|p| p.port == i32::from(port)))
+        .ok_or_else(|| PortForwardSessionError::PortNotFound {
+            target: target.clone(),
+            port,
+        })?;
+    let name = match &service_port.target_port {
+        None => return Ok(port),
+        Some(IntOrString::Int(target_port)) => {
+            return u16::try_from(*target_port).map_err(|_| PortForwardSessionError::PortNotFound {
+                target: target.clone(),
+                port,
+            })
+        }
+        Some(IntOrString::String(name)) => name,
+    };
+    let pod_name = pod.metadata.name.clone().unwrap_or_default();
+    pod.spec
+        .iter()
+        .flat_map(|spec| &spec.containers)
+        .flat_map(|container| container.ports.iter().flatten())
+        .find(|container_port| container_port.name.as_ref() == Some(name))
+        .and_then(|container_port| u16::try_from(container_port.container_port).ok())
+        .ok_or_else(|| PortForwardSessionError::NamedPortNotFound {
+            pod: pod_name,
+            name: name.clone(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use http::{Request, Response};
+    use tower_test::mock;
+
+    use super::*;
+    use crate::client::Body;
+
+    fn json(value: serde_json::Value) -> Response<Body> {
+        Response::builder()
+            .body(Body::from(serde_json::to_vec(&value).unwrap()))
+            .unwrap()
+    }
+
+    fn pods(names: &[(&str, bool)]) -> Response<Body> {
+        let items = names
+            .iter()
+            .map(|(name, ready)| {
+                let ready = if *ready { "True" } else { "False" };
+                serde_json::json!({
+                    "metadata": { "name": name },
+                    "spec": {
+                        "containers": [{ "name": "web", "ports": [{ "name": "http", "containerPort": 8080 }] }]
+                    },
+                    "status": { "conditions": [{ "type": "Ready", "status": ready }] }
+                })
+            })
+            .collect::<Vec<_>>();
+        json(serde_json::json!({ "apiVersion": "v1", "kind": "PodList", "metadata": {}, "items": items }))
+    }
+
+    fn not_found() -> Response<Body> {
+        Response::builder().status(404).body(Body::empty()).unwrap()
+    }
+
+    /// A resolver for `target` whose requests must match `expected` in order
+    fn resolver(
+        target: PortForwardTarget,
+        remote_ports: Vec<u16>,
+        expected: Vec<(&'static str, Response<Body>)>,
+    ) -> (Resolver, JoinHandle<()>) {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            for (uri, response) in expected {
+                let (request, send) = handle.next_request().await.expect("service not called");
+                assert_eq!(request.uri().to_string(), uri);
+                send.send_response(response);
+            }
+            if let Some((request, _)) = handle.next_request().await {
+                panic!("unexpected request {}", request.uri());
+            }
+        });
+        let client = Client::new(mock_service, "default");
+        (Resolver::new(client, "default", target, remote_ports), spawned)
+    }
+
+    #[tokio::test]
+    async fn deployments_resolve_to_a_ready_pod_once() {
+        let deployment = serde_json::json!({
+            "metadata": { "name": "web" },
+            "spec": {
+                "selector": { "matchLabels": { "app": "web" } },
+                "template": {}
+            }
+        });
+        let (resolver, spawned) = resolver(PortForwardTarget::Deployment("web".into()), vec![8080], vec![
+            (
+                "/apis/apps/v1/namespaces/default/deployments/web",
+                json(deployment),
+            ),
+            (
+                "/api/v1/namespaces/default/pods?&labelSelector=app%3Dweb",
+                pods(&[("web-0", false), ("web-1", true)]),
+            ),
+        ]);
+        let expected = Resolved {
+            pod: "web-1".into(),
+            ports: [(8080, 8080)].into(),
+        };
+        assert_eq!(resolver.resolve().await.unwrap(), expected);
+        // the pod is kept without checking it again
+        assert_eq!(resolver.resolve().await.unwrap(), expected);
+        drop(resolver);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn services_resolve_again_when_forwarding_fails() {
+        let service = serde_json::json!({
+            "metadata": { "name": "web" },
+            "spec": {
+                "selector": { "app": "web" },
+                "ports": [{ "name": "http", "port": 80, "targetPort": "http" }]
+            }
+        });
+        let (resolver, spawned) = resolver(PortForwardTarget::Service("web".into()), vec![80], vec![
+            ("/api/v1/namespaces/default/services/web", json(service.clone())),
+            (
+                "/api/v1/namespaces/default/pods?&labelSelector=app%3Dweb",
+                pods(&[("web-0", true)]),
+            ),
+            // web-0 was deleted
+            (
+                "/api/v1/namespaces/default/pods/web-0/portforward?&ports=8080",
+                not_found(),
+            ),
+            ("/api/v1/namespaces/default/services/web", json(service)),
+            (
+                "/api/v1/namespaces/default/pods?&labelSelector=app%3Dweb",
+                pods(&[("web-1", true)]),
+            ),
+            (
+                "/api/v1/namespaces/default/pods/web-1/portforward?&ports=8080",
+                not_found(),
+            ),
+        ]);
+        resolver.resolve().await.unwrap();
+        assert!(resolver.portforward(80).await.is_err());
+        drop(resolver);
+        spawned.await.unwrap();
+    }
+
+    #[test]
+    fn service_ports_map_to_target_ports() {
+        let service: Service = serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "web" },
+            "spec": {
+                "selector": { "app": "web" },
+                "ports": [
+                    { "name": "http", "port": 80, "targetPort": "http" },
+                    { "name": "metrics", "port": 9090, "targetPort": 9091 },
+                    { "name": "grpc", "port": 50051 }
+                ]
+            }
+        }))
+        .unwrap();
+        let pod: Pod = serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "web-0" },
+            "spec": {
+                "containers": [{ "name": "web", "ports": [{ "name": "http", "containerPort": 8080 }] }]
+            }
+        }))
+        .unwrap();
+        let target = PortForwardTarget::Service("web".into());
+        let port = |port| service_target_port(&target, &service, &pod, port);
+        assert_eq!(port(80).unwrap(), 8080);
+        assert_eq!(port(9090).unwrap(), 9091);
+        assert_eq!(port(50051).unwrap(), 50051);
+        assert!(matches!(
+            port(443),
+            Err(PortForwardSessionError::PortNotFound { .. })
+        ));
+        assert!(matches!(
+            service_target_port(&target, &service, &Pod::default(), 80),
+            Err(PortForwardSessionError::NamedPortNotFound { .. })
+        ));
+    }
+}
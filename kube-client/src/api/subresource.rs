@@ -1,9 +1,13 @@
-use futures::AsyncBufRead;
+use futures::{future::BoxFuture, AsyncBufRead};
 use serde::{de::DeserializeOwned, Serialize};
-use std::fmt::Debug;
+use std::{
+    fmt::Debug,
+    task::{Context, Poll},
+};
 
 use crate::{
     api::{Api, Patch, PatchParams, PostParams},
+    client::{Body, Warning},
     Error, Result,
 };
 
@@ -110,6 +114,24 @@ where
         self.client.request::<K>(req).await
     }
 
+    /// Patch an instance of the subresource, and return the warnings of the apiserver
+    ///
+    /// Like [`Api::patch_subresource`], but also returns the warnings of the response.
+    pub async fn patch_subresource_with_warnings<P: serde::Serialize + Debug>(
+        &self,
+        subresource_name: &str,
+        name: &str,
+        pp: &PatchParams,
+        patch: &Patch<P>,
+    ) -> Result<(K, Vec<Warning>)> {
+        let mut req = self
+            .request
+            .patch_subresource(subresource_name, name, pp, patch)
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("patch_subresource");
+        self.client.request_with_warnings::<K>(req).await
+    }
+
     /// Replace an instance of the subresource
     pub async fn replace_subresource(
         &self,
@@ -283,6 +305,107 @@ where
     }
 }
 
+// ----------------------------------------------------------------------------
+// Resize subresource
+// ----------------------------------------------------------------------------
+
+/// Marker trait for objects that support in-place resizing through the resize sub resource.
+///
+/// See [`Api::patch_resize`] et al.
+pub trait Resize {}
+
+impl Resize for k8s_openapi::api::core::v1::Pod {}
+
+impl<K> Api<K>
+where
+    K: Clone + DeserializeOwned + Resize,
+{
+    /// Patch the resize sub resource to change container resources without recreating the pod
+    ///
+    /// Only `resources` (and `resizePolicy`) of the containers in `.spec` can be changed, everything else is ignored.
+    /// The kubelet applies the change asynchronously, use
+    /// [`is_pod_resized`](https://docs.rs/kube/latest/kube/runtime/wait/conditions/fn.is_pod_resized.html)
+    /// to wait for it to settle.
+    ///
+    /// This requires the `InPlacePodVerticalScaling` feature, enabled by default since Kubernetes 1.33.
+    /// See the Kubernetes [documentation](https://kubernetes.io/docs/tasks/configure-pod-container/resize-container-resources/) for more details.
+    ///
+    /// ```no_run
+    /// use kube::api::{Api, PatchParams, Patch};
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client = kube::Client::try_default().await?;
+    /// let pods: Api<Pod> = Api::namespaced(client, "apps");
+    /// let patch = serde_json::json!({
+    ///     "spec": {
+    ///         "containers": [{
+    ///             "name": "app",
+    ///             "resources": { "requests": { "cpu": "800m" }, "limits": { "cpu": "800m" } }
+    ///         }]
+    ///     }
+    /// });
+    /// pods.patch_resize("mypod", &PatchParams::default(), &Patch::Strategic(patch)).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn patch_resize<P: serde::Serialize>(
+        &self,
+        name: &str,
+        pp: &PatchParams,
+        patch: &Patch<P>,
+    ) -> Result<K> {
+        let mut req = self
+            .request
+            .patch_subresource("resize", name, pp, patch)
+            .map_err(Error::BuildRequest)?;
+
+        req.extensions_mut().insert("patch_resize");
+        self.client.request::<K>(req).await
+    }
+
+    /// Replace the resize sub resource
+    ///
+    /// This functions in the same way as [`Api::replace`] except only container resources in `.spec` are replaced.
+    pub async fn replace_resize(&self, name: &str, pp: &PostParams, data: &K) -> Result<K>
+    where
+        K: Serialize,
+    {
+        let mut req = self
+            .request
+            .replace_subresource(
+                "resize",
+                name,
+                pp,
+                serde_json::to_vec(data).map_err(Error::SerdeError)?,
+            )
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("replace_resize");
+        self.client.request::<K>(req).await
+    }
+
+    /// Get the named resource through the resize subresource.
+    ///
+    /// This returns the whole K, with metadata and spec.
+    pub async fn get_resize(&self, name: &str) -> Result<K> {
+        let mut req = self
+            .request
+            .get_subresource("resize", name)
+            .map_err(Error::BuildRequest)?;
+
+        req.extensions_mut().insert("get_resize");
+        self.client.request::<K>(req).await
+    }
+}
+
+#[test]
+fn resize_path() {
+    use crate::api::{Request, Resource};
+    use k8s_openapi::api::core::v1 as corev1;
+    let url = corev1::Pod::url_path(&(), Some("ns"));
+    let req = Request::new(url).get_subresource("resize", "foo").unwrap();
+    assert_eq!(req.uri(), "/api/v1/namespaces/ns/pods/foo/resize");
+}
+
 // ----------------------------------------------------------------------------
 
 // TODO: Replace examples with owned custom resources. Bad practice to write to owned objects
@@ -340,6 +463,23 @@ where
         self.client.request::<K>(req).await
     }
 
+    /// Patch fields on the status object, and return the warnings of the apiserver
+    ///
+    /// Like [`Api::patch_status`], but also returns the warnings of the response.
+    pub async fn patch_status_with_warnings<P: serde::Serialize + Debug>(
+        &self,
+        name: &str,
+        pp: &PatchParams,
+        patch: &Patch<P>,
+    ) -> Result<(K, Vec<Warning>)> {
+        let mut req = self
+            .request
+            .patch_subresource("status", name, pp, patch)
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("patch_status");
+        self.client.request_with_warnings::<K>(req).await
+    }
+
     /// Replace every field on the status object
     ///
     /// This works similarly to the [`Api::replace`] method, but `.spec` is ignored.
@@ -468,6 +608,50 @@ where
     }
 }
 
+// ----------------------------------------------------------------------------
+// Binding subresource
+// ----------------------------------------------------------------------------
+
+#[test]
+fn bind_path() {
+    use crate::api::{Request, Resource};
+    use k8s_openapi::api::core::v1 as corev1;
+    let pp = PostParams::default();
+    let url = corev1::Pod::url_path(&(), Some("ns"));
+    let req = Request::new(url).bind("foo", "node-1", &pp).unwrap();
+    assert_eq!(req.uri(), "/api/v1/namespaces/ns/pods/foo/binding?");
+}
+
+/// Marker trait for objects that can be bound to a node
+///
+/// See [`Api::bind`] for usage.
+pub trait Bind {}
+
+impl Bind for k8s_openapi::api::core::v1::Pod {}
+
+impl<K> Api<K>
+where
+    K: DeserializeOwned + Bind,
+{
+    /// Bind a pending pod to a node, as done by schedulers
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # use kube::{api::{Api, PostParams}, Client};
+    /// # let client: Client = todo!();
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// pods.bind("my-pod", "node-1", &PostParams::default()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn bind(&self, name: &str, node: &str, pp: &PostParams) -> Result<Status> {
+        let mut req = self.request.bind(name, node, pp).map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("bind");
+        self.client.request::<Status>(req).await
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Attach subresource
 // ----------------------------------------------------------------------------
@@ -610,3 +794,105 @@ where
         Ok(Portforwarder::new(connection.into_stream(), ports))
     }
 }
+
+// ----------------------------------------------------------------------------
+// Proxy subresource
+// ----------------------------------------------------------------------------
+
+#[test]
+fn proxy_path() {
+    use crate::api::{Request, Resource};
+    use k8s_openapi::api::core::v1 as corev1;
+    let url = corev1::Service::url_path(&(), Some("ns"));
+    let req = http::Request::get("/healthz").body(Vec::<u8>::new()).unwrap();
+    let req = Request::new(url).proxy("foo", 80, req).unwrap();
+    assert_eq!(req.uri(), "/api/v1/namespaces/ns/services/foo:80/proxy/healthz");
+}
+
+/// Marker trait for objects that can be reached through the apiserver proxy
+///
+/// See [`Api::proxy`] and [`Api::proxy_service`] for usage.
+pub trait Proxy {}
+
+impl Proxy for k8s_openapi::api::core::v1::Pod {}
+impl Proxy for k8s_openapi::api::core::v1::Service {}
+impl Proxy for k8s_openapi::api::core::v1::Node {}
+
+impl<K> Api<K>
+where
+    K: Clone + Proxy,
+{
+    /// Send an HTTP request to a port of an object through the apiserver proxy
+    ///
+    /// The path and query of the request are forwarded to the object, along with its method, headers and body.
+    /// The response is returned as is, so error statuses from the target are not converted into an [`Error`].
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # use kube::{api::Api, Client};
+    /// # let client: Client = todo!();
+    /// use http_body_util::BodyExt;
+    ///
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// let req = http::Request::get("/metrics").body(vec![])?;
+    /// let res = pods.proxy("my-pod", 9090, req).await?;
+    /// let metrics = res.into_body().collect().await?.to_bytes();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn proxy<B: Into<Body>>(
+        &self,
+        name: &str,
+        port: u16,
+        req: http::Request<B>,
+    ) -> Result<http::Response<Body>> {
+        let mut req = self
+            .request
+            .proxy(name, port, req.map(Into::into))
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("proxy");
+        self.client.send(req).await
+    }
+
+    /// Create a [`tower::Service`] sending requests to a port of an object through the apiserver proxy
+    ///
+    /// This allows using HTTP clients built on tower with the object, see [`Api::proxy`] for details.
+    pub fn proxy_service(&self, name: &str, port: u16) -> ProxyService<K> {
+        ProxyService {
+            api: self.clone(),
+            name: name.to_string(),
+            port,
+        }
+    }
+}
+
+/// A [`tower::Service`] for a port of an object, reached through the apiserver proxy
+///
+/// Created with [`Api::proxy_service`].
+#[derive(Clone)]
+pub struct ProxyService<K> {
+    api: Api<K>,
+    name: String,
+    port: u16,
+}
+
+impl<K, B> tower::Service<http::Request<B>> for ProxyService<K>
+where
+    K: Clone + Proxy + 'static,
+    B: Into<Body>,
+{
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<http::Response<Body>>>;
+    type Response = http::Response<Body>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: http::Request<B>) -> Self::Future {
+        let svc = self.clone();
+        let req = req.map(Into::into);
+        Box::pin(async move { svc.api.proxy(&svc.name, svc.port, req).await })
+    }
+}
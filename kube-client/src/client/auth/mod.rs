@@ -14,10 +14,13 @@ use jsonpath_rust::JsonPath;
 use secrecy::{ExposeSecret, SecretString};
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
-use tokio::sync::{Mutex, RwLock};
+use tokio::sync::Mutex;
 use tower::{filter::AsyncPredicate, BoxError};
 
-use crate::config::{AuthInfo, AuthProviderConfig, ExecAuthCluster, ExecConfig, ExecInteractiveMode};
+use super::reload::Reloading;
+use crate::config::{
+    AuthInfo, AuthProviderConfig, ExecAuthCluster, ExecConfig, ExecCredentialCache, ExecInteractiveMode,
+};
 
 #[cfg(feature = "oauth")] mod oauth;
 #[cfg(feature = "oauth")] pub use oauth::Error as OAuthError;
@@ -120,46 +123,26 @@ pub(crate) enum Auth {
 
 // Token file reference. Reloads at least once per minute.
 #[derive(Debug)]
-pub struct TokenFile {
-    path: PathBuf,
-    token: SecretString,
-    expires_at: DateTime<Utc>,
-}
+pub struct TokenFile(Reloading<SecretString>);
 
 impl TokenFile {
     fn new<P: AsRef<Path>>(path: P) -> Result<TokenFile, Error> {
-        let token = std::fs::read_to_string(&path)
-            .map_err(|source| Error::ReadTokenFile(source, path.as_ref().to_owned()))?;
-        Ok(Self {
-            path: path.as_ref().to_owned(),
-            token: SecretString::from(token),
-            // Try to reload at least once a minute
-            expires_at: Utc::now() + SIXTY_SEC,
+        let path = path.as_ref().to_owned();
+        // > If reload from file fails, the last-read token should be used to avoid breaking
+        // > clients that make token files available on process start and then remove them to
+        // > limit credential exposure.
+        // > https://github.com/kubernetes/kubernetes/issues/68164
+        Reloading::new(move || {
+            std::fs::read_to_string(&path)
+                .map(SecretString::from)
+                .map_err(|source| Error::ReadTokenFile(source, path.clone()))
         })
-    }
-
-    fn is_expiring(&self) -> bool {
-        Utc::now() + TEN_SEC > self.expires_at
-    }
-
-    /// Get the cached token. Returns `None` if it's expiring.
-    fn cached_token(&self) -> Option<&str> {
-        (!self.is_expiring()).then(|| self.token.expose_secret())
+        .map(Self)
     }
 
     /// Get a token. Reloads from file if the cached token is expiring.
-    fn token(&mut self) -> &str {
-        if self.is_expiring() {
-            // > If reload from file fails, the last-read token should be used to avoid breaking
-            // > clients that make token files available on process start and then remove them to
-            // > limit credential exposure.
-            // > https://github.com/kubernetes/kubernetes/issues/68164
-            if let Ok(token) = std::fs::read_to_string(&self.path) {
-                self.token = SecretString::from(token);
-            }
-            self.expires_at = Utc::now() + SIXTY_SEC;
-        }
-        self.token.expose_secret()
+    fn token(&self) -> SecretString {
+        self.0.get()
     }
 }
 
@@ -174,6 +157,7 @@ macro_rules! const_unwrap {
 }
 
 /// Common constant for checking if an auth token is close to expiring
+#[cfg_attr(not(feature = "oidc"), allow(dead_code))]
 pub const TEN_SEC: chrono::TimeDelta = const_unwrap!(Duration::try_seconds(10));
 /// Common duration for time between reloads
 const SIXTY_SEC: chrono::TimeDelta = const_unwrap!(Duration::try_seconds(60));
@@ -191,7 +175,7 @@ const SIXTY_SEC: chrono::TimeDelta = const_unwrap!(Duration::try_seconds(60));
 #[derive(Debug, Clone)]
 pub enum RefreshableToken {
     Exec(Arc<Mutex<(SecretString, DateTime<Utc>, AuthInfo)>>),
-    File(Arc<RwLock<TokenFile>>),
+    File(Arc<TokenFile>),
     #[cfg(feature = "oauth")]
     GcpOauth(Arc<Mutex<oauth::Gcp>>),
     #[cfg(feature = "oidc")]
@@ -252,17 +236,7 @@ impl RefreshableToken {
                 bearer_header(locked_data.0.expose_secret())
             }
 
-            RefreshableToken::File(token_file) => {
-                let guard = token_file.read().await;
-                if let Some(header) = guard.cached_token().map(bearer_header) {
-                    return header;
-                }
-                // Drop the read guard before a write lock attempt to prevent deadlock.
-                drop(guard);
-                // Note that `token()` only reloads if the cached token is expiring.
-                // A separate method to conditionally reload minimizes the need for an exclusive access.
-                bearer_header(token_file.write().await.token())
-            }
+            RefreshableToken::File(token_file) => bearer_header(token_file.token().expose_secret()),
 
             #[cfg(feature = "oauth")]
             RefreshableToken::GcpOauth(data) => {
@@ -343,12 +317,15 @@ impl TryFrom<&AuthInfo> for Auth {
         // Token file reference. Must be reloaded at least once a minute.
         if let Some(file) = &auth_info.token_file {
             return Ok(Self::RefreshableToken(RefreshableToken::File(Arc::new(
-                RwLock::new(TokenFile::new(file)?),
+                TokenFile::new(file)?,
             ))));
         }
 
         if let Some(exec) = &auth_info.exec {
-            let creds = auth_exec(exec)?;
+            let creds = match &exec.credential_cache {
+                Some(cache) => cached_auth_exec(exec, cache)?,
+                None => auth_exec(exec)?,
+            };
             let status = creds.status.ok_or(Error::ExecPluginFailed)?;
             if let (Some(client_certificate_data), Some(client_key_data)) =
                 (status.client_certificate_data, status.client_key_data)
@@ -629,6 +606,158 @@ fn auth_exec(auth: &ExecConfig) -> Result<ExecCredential, Error> {
     Ok(creds)
 }
 
+/// Run the exec plugin, unless the cache has credentials that do not expire soon
+fn cached_auth_exec(exec: &ExecConfig, cache: &ExecCredentialCache) -> Result<ExecCredential, Error> {
+    let path = credential_cache_path(exec, cache);
+    if let Some(creds) = read_cached_credential(&path) {
+        return Ok(creds);
+    }
+    let creds = auth_exec(exec)?;
+    if let Err(err) = write_cached_credential(&path, &creds) {
+        tracing::warn!(path = %path.display(), %err, "failed to cache exec credential");
+    }
+    Ok(creds)
+}
+
+/// Inherited environment variables that select the identity of common exec plugins
+///
+/// Plugins run with the environment of the process, so the same exec config can return credentials
+/// for other users depending on e.g. the selected AWS profile or gcloud configuration.
+const CREDENTIAL_CACHE_ENV: &[&str] = &[
+    "AAD_SERVICE_PRINCIPAL_CLIENT_ID",
+    "AWS_ACCESS_KEY_ID",
+    "AWS_CONFIG_FILE",
+    "AWS_DEFAULT_PROFILE",
+    "AWS_DEFAULT_REGION",
+    "AWS_PROFILE",
+    "AWS_REGION",
+    "AWS_ROLE_ARN",
+    "AWS_SHARED_CREDENTIALS_FILE",
+    "AWS_WEB_IDENTITY_TOKEN_FILE",
+    "AZURE_CLIENT_ID",
+    "AZURE_CONFIG_DIR",
+    "AZURE_TENANT_ID",
+    "CLOUDSDK_ACTIVE_CONFIG_NAME",
+    "CLOUDSDK_CONFIG",
+    "CLOUDSDK_CORE_ACCOUNT",
+    "GOOGLE_APPLICATION_CREDENTIALS",
+    "HOME",
+    "KUBECONFIG",
+];
+
+/// The file for the credentials of an exec config and cluster
+fn credential_cache_path(exec: &ExecConfig, cache: &ExecCredentialCache) -> PathBuf {
+    credential_cache_path_with_env(exec, cache, |name| std::env::var(name).ok())
+}
+
+fn credential_cache_path_with_env(
+    exec: &ExecConfig,
+    cache: &ExecCredentialCache,
+    env: impl Fn(&str) -> Option<String>,
+) -> PathBuf {
+    let mut key = format!(
+        "{}\n{}\n{:?}",
+        cache.cluster,
+        serde_json::to_string(exec).unwrap_or_default(),
+        exec.drop_env
+    );
+    let dropped = exec.drop_env.iter().flatten();
+    let overridden = exec.env.iter().flatten().filter_map(|env| env.get("name"));
+    let excluded = dropped.chain(overridden).collect::<Vec<_>>();
+    for name in CREDENTIAL_CACHE_ENV {
+        if let Some(value) = env(name).filter(|_| !excluded.iter().any(|e| e == name)) {
+            key.push_str(&format!("\n{name}={value}"));
+        }
+    }
+    // Hash with FNV-1a, which unlike `DefaultHasher` is stable across Rust versions
+    let hash = key.bytes().fold(0xcbf29ce484222325_u64, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x100000001b3)
+    });
+    cache.dir.join(format!("{hash:016x}.json"))
+}
+
+fn read_cached_credential(path: &Path) -> Option<ExecCredential> {
+    if let Err(err) = check_cache_dir(path.parent()?) {
+        tracing::warn!(path = %path.display(), %err, "not using exec credential cache");
+        return None;
+    }
+    let creds: ExecCredential = serde_json::from_slice(&std::fs::read(path).ok()?).ok()?;
+    let expiration = creds.status.as_ref()?.expiration_timestamp.as_ref()?;
+    let expiration: DateTime<Utc> = expiration.parse().ok()?;
+    // Refresh a bit before expiry, like refreshable tokens
+    (Utc::now() + SIXTY_SEC < expiration).then_some(creds)
+}
+
+fn write_cached_credential(path: &Path, creds: &ExecCredential) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let expires = creds
+        .status
+        .as_ref()
+        .is_some_and(|status| status.expiration_timestamp.is_some());
+    if !expires {
+        return Ok(());
+    }
+    if let Some(dir) = path.parent() {
+        // Credentials are only readable by the current user
+        let mut builder = std::fs::DirBuilder::new();
+        builder.recursive(true);
+        #[cfg(unix)]
+        std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+        builder.create(dir)?;
+        check_cache_dir(dir)?;
+    }
+    // Write to a temporary file and rename it, so that concurrent readers never see a partial file
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = match options.open(&tmp) {
+        // Left behind by an earlier process with the same id, in a directory that only we can write to
+        Err(err) if err.kind() == std::io::ErrorKind::AlreadyExists => {
+            std::fs::remove_file(&tmp)?;
+            options.open(&tmp)?
+        }
+        res => res?,
+    };
+    let written = file
+        .write_all(&serde_json::to_vec(creds)?)
+        .and_then(|()| std::fs::rename(&tmp, path));
+    if written.is_err() {
+        let _ = std::fs::remove_file(&tmp);
+    }
+    written
+}
+
+/// Check that the cache directory is owned by the current user, and that other users cannot write to it
+///
+/// Otherwise other users could plant credentials, or read them through files they created in advance.
+#[cfg(unix)]
+fn check_cache_dir(dir: &Path) -> std::io::Result<()> {
+    use std::{io, os::unix::fs::MetadataExt};
+
+    let meta = std::fs::metadata(dir)?;
+    if meta.uid() != rustix::process::geteuid().as_raw() {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is not owned by the current user", dir.display()),
+        ));
+    }
+    if meta.mode() & 0o022 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is writable by other users", dir.display()),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_cache_dir(_dir: &Path) -> std::io::Result<()> {
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
     use crate::config::Kubeconfig;
@@ -681,23 +810,90 @@ mod test {
         Ok(())
     }
 
+    #[test]
+    fn exec_credential_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let exec: ExecConfig = serde_yaml::from_str("command: aws\nargs: [eks, get-token]").unwrap();
+        let cache = ExecCredentialCache {
+            dir: dir.path().join("creds"),
+            cluster: "https://a.example.com".into(),
+        };
+        let path = credential_cache_path(&exec, &cache);
+        let other_cluster = ExecCredentialCache {
+            cluster: "https://b.example.com".into(),
+            ..cache.clone()
+        };
+        assert_ne!(path, credential_cache_path(&exec, &other_cluster));
+        let profile =
+            |profile: &'static str| move |name: &str| (name == "AWS_PROFILE").then(|| profile.to_string());
+        assert_ne!(
+            credential_cache_path_with_env(&exec, &cache, profile("dev")),
+            credential_cache_path_with_env(&exec, &cache, profile("prod"))
+        );
+        let dropped = ExecConfig {
+            drop_env: Some(vec!["AWS_PROFILE".into()]),
+            ..exec.clone()
+        };
+        assert_eq!(
+            credential_cache_path_with_env(&dropped, &cache, profile("dev")),
+            credential_cache_path_with_env(&dropped, &cache, profile("prod"))
+        );
+
+        let creds = |expiration: Option<DateTime<Utc>>| ExecCredential {
+            kind: Some("ExecCredential".into()),
+            api_version: Some("client.authentication.k8s.io/v1".into()),
+            spec: None,
+            status: Some(ExecCredentialStatus {
+                expiration_timestamp: expiration.map(|ts| ts.to_rfc3339()),
+                token: None,
+                client_certificate_data: Some("cert".into()),
+                client_key_data: Some("key".into()),
+            }),
+        };
+        write_cached_credential(&path, &creds(None)).unwrap();
+        assert!(read_cached_credential(&path).is_none());
+
+        write_cached_credential(&path, &creds(Some(Utc::now() + SIXTY_SEC * 10))).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&cache.dir).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o700);
+        }
+        let cached = read_cached_credential(&path).unwrap().status.unwrap();
+        assert_eq!(cached.client_certificate_data.as_deref(), Some("cert"));
+
+        // Expiring soon
+        write_cached_credential(&path, &creds(Some(Utc::now() + TEN_SEC))).unwrap();
+        assert!(read_cached_credential(&path).is_none());
+
+        // Directories that other users can write to are not used
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            write_cached_credential(&path, &creds(Some(Utc::now() + SIXTY_SEC * 10))).unwrap();
+            std::fs::set_permissions(&cache.dir, std::fs::Permissions::from_mode(0o777)).unwrap();
+            assert!(read_cached_credential(&path).is_none());
+            let err = write_cached_credential(&path, &creds(Some(Utc::now() + SIXTY_SEC * 10))).unwrap_err();
+            assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        }
+    }
+
     #[test]
     fn token_file() {
         let file = tempfile::NamedTempFile::new().unwrap();
         std::fs::write(file.path(), "token1").unwrap();
-        let mut token_file = TokenFile::new(file.path()).unwrap();
-        assert_eq!(token_file.cached_token().unwrap(), "token1");
-        assert!(!token_file.is_expiring());
-        assert_eq!(token_file.token(), "token1");
+        let token_file = TokenFile::new(file.path()).unwrap();
+        assert_eq!(token_file.token().expose_secret(), "token1");
         // Doesn't reload unless expiring
         std::fs::write(file.path(), "token2").unwrap();
-        assert_eq!(token_file.token(), "token1");
-
-        token_file.expires_at = Utc::now();
-        assert!(token_file.is_expiring());
-        assert_eq!(token_file.cached_token(), None);
-        assert_eq!(token_file.token(), "token2");
-        assert!(!token_file.is_expiring());
-        assert_eq!(token_file.cached_token().unwrap(), "token2");
+        assert_eq!(token_file.token().expose_secret(), "token1");
+
+        token_file.0.expire();
+        assert_eq!(token_file.token().expose_secret(), "token2");
+        // Keeps the last token when the file is removed
+        std::fs::remove_file(file.path()).unwrap();
+        token_file.0.expire();
+        assert_eq!(token_file.token().expose_secret(), "token2");
     }
 }
@@ -49,6 +49,14 @@ impl Body {
         Body::new(Kind::Wrap(body.map_err(Into::into).boxed_unsync()))
     }
 
+    // Clone a body that is fully buffered in memory, streaming bodies cannot be cloned
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        match &self.kind {
+            Kind::Once(val) => Some(Self::new(Kind::Once(val.clone()))),
+            Kind::Wrap(_) => None,
+        }
+    }
+
     /// Collect all the data frames and trailers of this request body and return the data frame
     pub async fn collect_bytes(self) -> Result<Bytes, crate::Error> {
         Ok(self.collect().await?.to_bytes())
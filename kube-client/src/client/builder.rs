@@ -21,6 +21,10 @@ use tracing::Span;
 use super::body::Body;
 use crate::{client::ConfigExt, Client, Config, Error, Result};
 
+// Response headers identifying the API Priority and Fairness classification of a request
+const APF_FLOW_SCHEMA_UID: &str = "x-kubernetes-pf-flowschema-uid";
+const APF_PRIORITY_LEVEL_UID: &str = "x-kubernetes-pf-prioritylevel-uid";
+
 /// HTTP body of a dynamic backing type.
 ///
 /// The suggested implementation type is [`crate::client::Body`].
@@ -188,6 +192,8 @@ where
         .into_inner();
 
     let service = ServiceBuilder::new()
+        .option_layer(config.retry_layer())
+        .option_layer(config.rate_limit_layer())
         .layer(stack)
         .option_layer(auth_layer)
         .layer(config.extra_headers_layer()?)
@@ -204,6 +210,8 @@ where
                          otel.name = req.extensions().get::<&'static str>().unwrap_or(&"HTTP"),
                          otel.kind = "client",
                          otel.status_code = tracing::field::Empty,
+                         k8s.apf.flow_schema_uid = tracing::field::Empty,
+                         k8s.apf.priority_level_uid = tracing::field::Empty,
                     )
                 })
                 .on_request(|_req: &Request<Body>, _span: &Span| {
@@ -215,6 +223,23 @@ where
                     if status.is_client_error() || status.is_server_error() {
                         span.record("otel.status_code", "ERROR");
                     }
+                    // API Priority and Fairness classification, to tell server-side throttling apart
+                    let headers = res.headers();
+                    let flow_schema = headers.get(APF_FLOW_SCHEMA_UID).and_then(|v| v.to_str().ok());
+                    let priority_level = headers.get(APF_PRIORITY_LEVEL_UID).and_then(|v| v.to_str().ok());
+                    if let Some(flow_schema) = flow_schema {
+                        span.record("k8s.apf.flow_schema_uid", flow_schema);
+                    }
+                    if let Some(priority_level) = priority_level {
+                        span.record("k8s.apf.priority_level_uid", priority_level);
+                    }
+                    if status == http::StatusCode::TOO_MANY_REQUESTS {
+                        tracing::warn!(
+                            flow_schema_uid = flow_schema,
+                            priority_level_uid = priority_level,
+                            "throttled by apiserver priority and fairness"
+                        );
+                    }
                 })
                 // Explicitly disable `on_body_chunk`. The default does nothing.
                 .on_body_chunk(())
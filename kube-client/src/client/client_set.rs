@@ -0,0 +1,259 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use futures::{stream, StreamExt};
+use tokio::sync::OnceCell;
+
+use crate::{
+    config::{KubeConfigOptions, Kubeconfig, KubeconfigError},
+    Client, Config, Error, Result,
+};
+
+/// The default number of clusters that [`ClientSet`] operations run on at once
+const DEFAULT_CONCURRENCY: usize = 10;
+
+/// A set of [`Client`]s for the contexts and clusters of a [`Kubeconfig`]
+///
+/// Clients are built the first time they are used, and cached for later calls.
+/// Cloning a `ClientSet` is cheap, and the clones share the cached clients.
+///
+/// ```no_run
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// use k8s_openapi::api::core::v1::Namespace;
+/// use kube::{api::ListParams, client::ClientSet, Api};
+///
+/// let clients = ClientSet::read()?.concurrency(4);
+/// for (context, namespaces) in clients
+///     .for_each_context(|_, client| async move {
+///         let namespaces: Api<Namespace> = Api::all(client);
+///         namespaces.list(&ListParams::default()).await.map(|list| list.items.len())
+///     })
+///     .await
+/// {
+///     println!("{context}: {namespaces:?}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ClientSet {
+    kubeconfig: Arc<Kubeconfig>,
+    clients: Arc<Mutex<HashMap<KubeConfigOptions, Arc<OnceCell<Client>>>>>,
+    concurrency: usize,
+}
+
+impl ClientSet {
+    /// Create a `ClientSet` for the contexts and clusters of a [`Kubeconfig`]
+    pub fn new(kubeconfig: Kubeconfig) -> Self {
+        Self {
+            kubeconfig: Arc::new(kubeconfig),
+            clients: Arc::default(),
+            concurrency: DEFAULT_CONCURRENCY,
+        }
+    }
+
+    /// Create a `ClientSet` from `KUBECONFIG` or the default kubeconfig location
+    ///
+    /// See [`Kubeconfig::read`].
+    pub fn read() -> Result<Self, KubeconfigError> {
+        Ok(Self::new(Kubeconfig::read()?))
+    }
+
+    /// Set the maximum number of clusters that [`ClientSet::for_each_context`]
+    /// and [`ClientSet::for_each_cluster`] run on at once
+    ///
+    /// Defaults to 10.
+    #[must_use]
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// The [`Kubeconfig`] that clients are built from
+    pub fn kubeconfig(&self) -> &Kubeconfig {
+        &self.kubeconfig
+    }
+
+    /// The names of the contexts in the kubeconfig
+    pub fn contexts(&self) -> impl Iterator<Item = &str> {
+        self.kubeconfig.contexts.iter().map(|x| x.name.as_str())
+    }
+
+    /// The names of the clusters in the kubeconfig
+    pub fn clusters(&self) -> impl Iterator<Item = &str> {
+        self.kubeconfig.clusters.iter().map(|x| x.name.as_str())
+    }
+
+    /// Get the client for the given [`KubeConfigOptions`]
+    ///
+    /// The client is built the first time the options are used, in the same way as
+    /// [`Config::from_custom_kubeconfig`].
+    pub async fn client(&self, options: &KubeConfigOptions) -> Result<Client> {
+        let cell = self
+            .clients
+            .lock()
+            .unwrap()
+            .entry(options.clone())
+            .or_default()
+            .clone();
+        let client = cell
+            .get_or_try_init(|| async {
+                let config = Config::from_custom_kubeconfig(Kubeconfig::clone(&self.kubeconfig), options)
+                    .await
+                    .map_err(Error::Kubeconfig)?;
+                Client::try_from(config)
+            })
+            .await?;
+        Ok(client.clone())
+    }
+
+    /// Get the client for a context
+    pub async fn context(&self, context: &str) -> Result<Client> {
+        self.client(&self.context_options(context)).await
+    }
+
+    /// Get the client for a cluster
+    ///
+    /// The user and default namespace are taken from the first context that uses the cluster,
+    /// or the current context if no context uses it.
+    pub async fn cluster(&self, cluster: &str) -> Result<Client> {
+        self.client(&self.cluster_options(cluster)).await
+    }
+
+    fn context_options(&self, context: &str) -> KubeConfigOptions {
+        KubeConfigOptions {
+            context: Some(context.to_owned()),
+            ..KubeConfigOptions::default()
+        }
+    }
+
+    fn cluster_options(&self, cluster: &str) -> KubeConfigOptions {
+        let context = self
+            .kubeconfig
+            .contexts
+            .iter()
+            .find(|x| {
+                x.context
+                    .as_ref()
+                    .is_some_and(|context| context.cluster == cluster)
+            })
+            .map(|x| x.name.clone());
+        KubeConfigOptions {
+            context,
+            cluster: Some(cluster.to_owned()),
+            user: None,
+        }
+    }
+
+    /// Run an operation with the client of each context
+    ///
+    /// Returns the result for each context, in the order of the kubeconfig.
+    /// A context whose client cannot be built gets the error instead of running the operation.
+    pub async fn for_each_context<F, Fut, T, E>(&self, f: F) -> Vec<(String, Result<T, E>)>
+    where
+        F: Fn(String, Client) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<Error>,
+    {
+        let contexts = self.contexts().map(str::to_owned).collect::<Vec<_>>();
+        self.run(contexts, Self::context_options, f).await
+    }
+
+    /// Run an operation with the client of each cluster
+    ///
+    /// Returns the result for each cluster, in the order of the kubeconfig.
+    /// A cluster whose client cannot be built gets the error instead of running the operation.
+    pub async fn for_each_cluster<F, Fut, T, E>(&self, f: F) -> Vec<(String, Result<T, E>)>
+    where
+        F: Fn(String, Client) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<Error>,
+    {
+        let clusters = self.clusters().map(str::to_owned).collect::<Vec<_>>();
+        self.run(clusters, Self::cluster_options, f).await
+    }
+
+    async fn run<F, Fut, T, E>(
+        &self,
+        names: Vec<String>,
+        options: fn(&Self, &str) -> KubeConfigOptions,
+        f: F,
+    ) -> Vec<(String, Result<T, E>)>
+    where
+        F: Fn(String, Client) -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+        E: From<Error>,
+    {
+        let f = &f;
+        stream::iter(names)
+            .map(|name| async move {
+                let result = match self.client(&options(self, &name)).await {
+                    Ok(client) => f(name.clone(), client).await,
+                    Err(err) => Err(err.into()),
+                };
+                (name, result)
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::KubeconfigError;
+
+    const KUBECONFIG: &str = r#"
+apiVersion: v1
+kind: Config
+clusters:
+- name: dev
+  cluster:
+    server: http://dev.example.com
+- name: prod
+  cluster:
+    server: http://prod.example.com
+- name: broken
+  cluster: {}
+contexts:
+- name: dev
+  context:
+    cluster: dev
+    namespace: dev-ns
+- name: prod
+  context:
+    cluster: prod
+- name: broken
+  context:
+    cluster: broken
+current-context: dev
+"#;
+
+    #[tokio::test]
+    async fn clients_are_cached_per_context() {
+        let clients = ClientSet::new(Kubeconfig::from_yaml(KUBECONFIG).unwrap()).concurrency(2);
+        let results = clients
+            .for_each_context(
+                |_, client| async move { Ok::<_, Error>(client.default_namespace().to_owned()) },
+            )
+            .await;
+
+        let names = results.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, ["dev", "prod", "broken"]);
+        assert_eq!(results[0].1.as_ref().unwrap(), "dev-ns");
+        assert_eq!(results[1].1.as_ref().unwrap(), "default");
+        assert!(matches!(
+            results[2].1,
+            Err(Error::Kubeconfig(KubeconfigError::MissingClusterUrl))
+        ));
+
+        clients.context("dev").await.unwrap();
+        clients.cluster("prod").await.unwrap();
+        // one client per context, and one for the cluster
+        assert_eq!(clients.clients.lock().unwrap().len(), 4);
+    }
+}
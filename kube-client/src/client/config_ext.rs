@@ -4,12 +4,14 @@ use http::{header::HeaderName, HeaderValue};
 #[cfg(feature = "openssl-tls")] use hyper::rt::{Read, Write};
 use hyper_util::client::legacy::connect::HttpConnector;
 use secrecy::ExposeSecret;
-use tower::{filter::AsyncFilterLayer, util::Either};
+use tower::{filter::AsyncFilterLayer, retry::RetryLayer, util::Either};
 
 #[cfg(any(feature = "rustls-tls", feature = "openssl-tls"))] use super::tls;
 use super::{
     auth::Auth,
-    middleware::{AddAuthorizationLayer, AuthLayer, BaseUriLayer, ExtraHeadersLayer},
+    middleware::{
+        AddAuthorizationLayer, AuthLayer, BaseUriLayer, ExtraHeadersLayer, RateLimitLayer, RetryPolicy,
+    },
 };
 use crate::{Config, Error, Result};
 
@@ -28,6 +30,14 @@ pub trait ConfigExt: private::Sealed {
     /// Layer to add non-authn HTTP headers depending on the config.
     fn extra_headers_layer(&self) -> Result<ExtraHeadersLayer>;
 
+    /// Optional layer to retry transient failures depending on the config.
+    ///
+    /// The retried [`Service`](tower::Service) must be [`Clone`], so this layer should wrap the rest of the stack.
+    fn retry_layer(&self) -> Option<RetryLayer<RetryPolicy>>;
+
+    /// Optional layer to throttle requests client-side depending on the config.
+    fn rate_limit_layer(&self) -> Option<RateLimitLayer>;
+
     /// Create [`hyper_rustls::HttpsConnector`] based on config.
     ///
     /// # Example
@@ -205,6 +215,17 @@ impl ConfigExt for Config {
         })
     }
 
+    fn retry_layer(&self) -> Option<RetryLayer<RetryPolicy>> {
+        self.retry.clone().map(|retry| RetryPolicy::new(retry).layer())
+    }
+
+    fn rate_limit_layer(&self) -> Option<RateLimitLayer> {
+        self.rate_limit
+            .as_ref()
+            .filter(|rate_limit| rate_limit.qps > 0.0)
+            .map(|rate_limit| RateLimitLayer::new(rate_limit.qps, rate_limit.burst))
+    }
+
     #[cfg(feature = "rustls-tls")]
     fn rustls_client_config(&self) -> Result<rustls::ClientConfig> {
         let identity = self.exec_identity_pem().or_else(|| self.identity_pem());
@@ -212,6 +233,7 @@ impl ConfigExt for Config {
             identity.as_deref(),
             self.root_cert.as_deref(),
             self.accept_invalid_certs,
+            self.tls_reload.as_ref(),
         )
         .map_err(Error::RustlsTls)
     }
@@ -249,6 +271,9 @@ impl ConfigExt for Config {
 
     #[cfg(feature = "openssl-tls")]
     fn openssl_ssl_connector_builder(&self) -> Result<openssl::ssl::SslConnectorBuilder> {
+        if self.tls_reload.is_some() {
+            return Err(Error::OpensslTls(tls::openssl_tls::Error::TlsReloadUnsupported));
+        }
         let identity = self.exec_identity_pem().or_else(|| self.identity_pem());
         // TODO: pass self.tls_server_name for openssl
         tls::openssl_tls::ssl_connector_builder(identity.as_ref(), self.root_cert.as_ref())
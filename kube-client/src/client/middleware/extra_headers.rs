@@ -40,7 +40,58 @@ where
     }
 
     fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
-        req.headers_mut().extend(self.headers.iter().cloned());
+        // Headers set on the request take precedence, and impersonation is replaced as a whole
+        // so that an impersonated user does not inherit the groups impersonated here
+        let impersonating = req.headers().keys().any(is_impersonation);
+        let skip = self
+            .headers
+            .iter()
+            .map(|(name, _)| req.headers().contains_key(name) || impersonating && is_impersonation(name))
+            .collect::<Vec<_>>();
+        for ((name, value), skip) in self.headers.iter().zip(skip) {
+            if !skip {
+                req.headers_mut().append(name.clone(), value.clone());
+            }
+        }
         self.inner.call(req)
     }
 }
+
+fn is_impersonation(name: &HeaderName) -> bool {
+    name.as_str().starts_with("impersonate-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tower::ServiceExt;
+
+    #[tokio::test]
+    async fn request_headers_take_precedence() {
+        let headers = [
+            ("impersonate-user", "bob"),
+            ("impersonate-group", "admins"),
+            ("x-tenant", "default"),
+            ("x-extra", "1"),
+        ]
+        .map(|(name, value)| (HeaderName::from_static(name), HeaderValue::from_static(value)));
+        let layer = ExtraHeadersLayer {
+            headers: Arc::new(headers.to_vec()),
+        };
+        let service = layer.layer(tower::service_fn(|req: Request<()>| async move {
+            Ok::<_, std::convert::Infallible>(req.headers().clone())
+        }));
+
+        let req = Request::builder()
+            .header("impersonate-user", "alice")
+            .header("x-tenant", "a")
+            .body(())
+            .unwrap();
+        let headers = service.oneshot(req).await.unwrap();
+        assert_eq!(headers["impersonate-user"], "alice");
+        assert!(!headers.contains_key("impersonate-group"));
+        assert_eq!(headers.get_all("x-tenant").iter().count(), 1);
+        assert_eq!(headers["x-extra"], "1");
+    }
+}
@@ -4,9 +4,13 @@ pub(crate) use tower_http::auth::AddAuthorizationLayer;
 
 mod base_uri;
 mod extra_headers;
+mod rate_limit;
+mod retry;
 
 pub use base_uri::{BaseUri, BaseUriLayer};
 pub use extra_headers::{ExtraHeaders, ExtraHeadersLayer};
+pub use rate_limit::{RateLimit, RateLimitLayer};
+pub use retry::RetryPolicy;
 
 use super::auth::RefreshableToken;
 /// Layer to set up `Authorization` header depending on the config.
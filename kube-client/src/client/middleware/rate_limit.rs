@@ -0,0 +1,191 @@
+//! Client-side throttling of requests.
+use std::{
+    future::Future,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{ready, Context, Poll},
+    time::Duration,
+};
+
+use tokio::time::{Instant, Sleep};
+use tower::{Layer, Service};
+
+// client-go logs waits above this threshold, since they are easily mistaken for server-side throttling
+const LOG_WAIT_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Layer that applies [`RateLimit`], a token bucket shared by all clones of the layer and its services.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl RateLimitLayer {
+    /// Allow `qps` requests per second on average, with bursts of up to `burst` requests.
+    pub fn new(qps: f32, burst: u32) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(TokenBucket::new(qps, burst, Instant::now()))),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            bucket: self.bucket.clone(),
+            state: State::Idle,
+        }
+    }
+}
+
+/// Middleware that delays requests to stay within the configured rate.
+pub struct RateLimit<S> {
+    inner: S,
+    bucket: Arc<Mutex<TokenBucket>>,
+    state: State,
+}
+
+enum State {
+    Idle,
+    Waiting(Pin<Box<Sleep>>),
+    Ready,
+}
+
+impl<S: Clone> Clone for RateLimit<S> {
+    fn clone(&self) -> Self {
+        // Reservations belong to the service that made them
+        Self {
+            inner: self.inner.clone(),
+            bucket: self.bucket.clone(),
+            state: State::Idle,
+        }
+    }
+}
+
+impl<S, Req> Service<Req> for RateLimit<S>
+where
+    S: Service<Req>,
+{
+    type Error = S::Error;
+    type Future = S::Future;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        loop {
+            match &mut self.state {
+                State::Idle => {
+                    let wait = self
+                        .bucket
+                        .lock()
+                        .expect("rate limit lock poisoned")
+                        .reserve(Instant::now());
+                    if wait.is_zero() {
+                        self.state = State::Ready;
+                    } else {
+                        if wait >= LOG_WAIT_THRESHOLD {
+                            tracing::info!(
+                                ?wait,
+                                "waiting due to client-side throttling, not priority and fairness"
+                            );
+                        }
+                        self.state = State::Waiting(Box::pin(tokio::time::sleep(wait)));
+                    }
+                }
+                State::Waiting(sleep) => {
+                    ready!(sleep.as_mut().poll(cx));
+                    self.state = State::Ready;
+                }
+                State::Ready => return self.inner.poll_ready(cx),
+            }
+        }
+    }
+
+    fn call(&mut self, req: Req) -> Self::Future {
+        self.state = State::Idle;
+        self.inner.call(req)
+    }
+}
+
+/// Token bucket that allows tokens to be reserved ahead of time, keeping waiters in order
+#[derive(Debug)]
+struct TokenBucket {
+    qps: f64,
+    burst: f64,
+    tokens: f64,
+    last: Instant,
+}
+
+impl TokenBucket {
+    fn new(qps: f32, burst: u32, now: Instant) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            qps: f64::from(qps),
+            burst,
+            tokens: burst,
+            last: now,
+        }
+    }
+
+    /// Take a token, returning how long the caller must wait before using it
+    fn reserve(&mut self, now: Instant) -> Duration {
+        let elapsed = now.saturating_duration_since(self.last).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.qps).min(self.burst);
+        self.last = now;
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.qps)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::{Request, Response};
+    use tower::ServiceExt;
+    use tower_test::mock;
+
+    use crate::client::Body;
+
+    #[test]
+    fn token_bucket_allows_bursts_then_throttles() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(2.0, 2, start);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::ZERO);
+        assert_eq!(bucket.reserve(start), Duration::from_millis(500));
+        // waiters queue up behind earlier reservations
+        assert_eq!(bucket.reserve(start), Duration::from_secs(1));
+        // tokens are refilled over time, up to the burst
+        let later = start + Duration::from_secs(10);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::ZERO);
+        assert_eq!(bucket.reserve(later), Duration::from_millis(500));
+    }
+
+    #[tokio::test]
+    async fn requests_are_delayed_over_the_burst() {
+        let (svc, mut handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut svc = RateLimitLayer::new(10.0, 1).layer(svc);
+
+        let spawned = tokio::spawn(async move {
+            for _ in 0..2 {
+                let (_, send) = handle.next_request().await.expect("service not called");
+                send.send_response(Response::builder().body(Body::empty()).unwrap());
+            }
+        });
+
+        let start = Instant::now();
+        for _ in 0..2 {
+            let req = Request::get("/api/v1/pods").body(Body::empty()).unwrap();
+            svc.ready().await.unwrap().call(req).await.unwrap();
+        }
+        assert!(start.elapsed() >= Duration::from_millis(90));
+        spawned.await.unwrap();
+    }
+}
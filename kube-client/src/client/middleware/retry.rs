@@ -0,0 +1,305 @@
+//! Retry requests that failed for transient reasons.
+use std::{error::Error as StdError, sync::Arc, time::Duration};
+
+use http::{header::RETRY_AFTER, Method, Request, Response, StatusCode};
+use rand::Rng;
+use tower::{
+    retry::{
+        budget::{Budget, TpsBudget},
+        Policy, RetryLayer,
+    },
+    BoxError,
+};
+
+use crate::{client::Body, config::RetryConfig};
+
+/// [`Policy`] that retries transient failures with exponential backoff, limited by a retry budget.
+///
+/// Create one from a [`RetryConfig`] and install it with [`RetryPolicy::layer`].
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    config: Arc<RetryConfig>,
+    budget: Arc<TpsBudget>,
+    attempts: u32,
+}
+
+impl RetryPolicy {
+    /// Create a policy from the given settings.
+    ///
+    /// The retry budget is shared between all clones of the policy.
+    pub fn new(config: RetryConfig) -> Self {
+        let budget = TpsBudget::new(
+            config.budget_ttl,
+            config.min_retries_per_sec,
+            config.retry_percent,
+        );
+        Self {
+            config: Arc::new(config),
+            budget: Arc::new(budget),
+            attempts: 0,
+        }
+    }
+
+    /// [`RetryLayer`] applying this policy.
+    pub fn layer(self) -> RetryLayer<Self> {
+        RetryLayer::new(self)
+    }
+
+    fn backoff(&self) -> Duration {
+        let exponential = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(self.attempts))
+            .min(self.config.max_backoff);
+        // Jitter between half and the full backoff to avoid retrying in lockstep with other clients
+        exponential.mul_f64(rand::rng().random_range(0.5..=1.0))
+    }
+}
+
+/// How a request failed, if it failed for a transient reason
+#[derive(Debug, PartialEq, Eq)]
+enum Transient {
+    /// The request was rejected before the apiserver acted on it
+    NotProcessed,
+    /// The apiserver may or may not have acted on the request
+    MaybeProcessed,
+}
+
+impl<B> Policy<Request<Body>, Response<B>, BoxError> for RetryPolicy {
+    type Future = tokio::time::Sleep;
+
+    fn retry(
+        &mut self,
+        req: &mut Request<Body>,
+        result: &mut Result<Response<B>, BoxError>,
+    ) -> Option<Self::Future> {
+        let (transient, retry_after) = match result {
+            Ok(res) => (classify_status(res.status()), retry_after(res)),
+            Err(err) => (classify_error(err.as_ref()), None),
+        };
+        let Some(transient) = transient else {
+            if self.attempts == 0 {
+                self.budget.deposit();
+            }
+            return None;
+        };
+        if transient == Transient::MaybeProcessed
+            && !is_idempotent(req.method())
+            && !self.config.retry_non_idempotent
+        {
+            return None;
+        }
+        if self.attempts >= self.config.max_retries {
+            return None;
+        }
+        if !self.budget.withdraw() {
+            tracing::debug!("retry budget exhausted");
+            return None;
+        }
+
+        // a far-off Retry-After would otherwise stall the request for as long as the server asks
+        let delay = retry_after.map_or_else(|| self.backoff(), |delay| delay.min(self.config.max_backoff));
+        self.attempts += 1;
+        tracing::debug!(
+            http.method = %req.method(),
+            http.url = %req.uri(),
+            attempt = self.attempts,
+            ?delay,
+            "retrying request"
+        );
+        Some(tokio::time::sleep(delay))
+    }
+
+    fn clone_request(&mut self, req: &Request<Body>) -> Option<Request<Body>> {
+        let mut clone = Request::new(req.body().try_clone()?);
+        *clone.method_mut() = req.method().clone();
+        *clone.uri_mut() = req.uri().clone();
+        *clone.version_mut() = req.version();
+        *clone.headers_mut() = req.headers().clone();
+        *clone.extensions_mut() = req.extensions().clone();
+        Some(clone)
+    }
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+fn classify_status(status: StatusCode) -> Option<Transient> {
+    match status {
+        // Rejected by API Priority and Fairness or max-in-flight limits
+        StatusCode::TOO_MANY_REQUESTS => Some(Transient::NotProcessed),
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => Some(Transient::MaybeProcessed),
+        _ => None,
+    }
+}
+
+fn classify_error(err: &(dyn StdError + 'static)) -> Option<Transient> {
+    let mut source = Some(err);
+    while let Some(err) = source {
+        if let Some(err) = err.downcast_ref::<hyper_util::client::legacy::Error>() {
+            if err.is_connect() {
+                return Some(Transient::NotProcessed);
+            }
+        }
+        if let Some(err) = err.downcast_ref::<std::io::Error>() {
+            use std::io::ErrorKind;
+            if matches!(
+                err.kind(),
+                ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe
+            ) {
+                return Some(Transient::MaybeProcessed);
+            }
+        }
+        source = err.source();
+    }
+    None
+}
+
+// Only the delay-seconds form is used by the apiserver
+fn retry_after<B>(res: &Response<B>) -> Option<Duration> {
+    let seconds = res
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tower::{Layer, Service, ServiceExt};
+    use tower_test::mock;
+
+    fn policy(config: RetryConfig) -> RetryPolicy {
+        RetryPolicy::new(RetryConfig {
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+            ..config
+        })
+    }
+
+    fn status(code: StatusCode) -> Response<Body> {
+        Response::builder().status(code).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn retries_idempotent_requests_until_success() {
+        let (svc, mut handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut svc = policy(RetryConfig::default()).layer().layer(svc);
+
+        let spawned = tokio::spawn(async move {
+            for code in [
+                StatusCode::SERVICE_UNAVAILABLE,
+                StatusCode::GATEWAY_TIMEOUT,
+                StatusCode::OK,
+            ] {
+                let (request, send) = handle.next_request().await.expect("service not called");
+                assert_eq!(request.method(), Method::GET);
+                assert_eq!(request.uri(), "/api/v1/pods");
+                send.send_response(status(code));
+            }
+        });
+
+        let req = Request::get("/api/v1/pods").body(Body::empty()).unwrap();
+        let res = svc.ready().await.unwrap().call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_retries() {
+        let (svc, mut handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut svc = policy(RetryConfig {
+            max_retries: 1,
+            ..RetryConfig::default()
+        })
+        .layer()
+        .layer(svc);
+
+        let spawned = tokio::spawn(async move {
+            for _ in 0..2 {
+                let (_, send) = handle.next_request().await.expect("service not called");
+                send.send_response(status(StatusCode::INTERNAL_SERVER_ERROR));
+            }
+        });
+
+        let req = Request::get("/api/v1/pods").body(Body::empty()).unwrap();
+        let res = svc.ready().await.unwrap().call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn caps_retry_after_at_max_backoff() {
+        let (svc, mut handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut svc = policy(RetryConfig::default()).layer().layer(svc);
+
+        let spawned = tokio::spawn(async move {
+            let (_, send) = handle.next_request().await.expect("service not called");
+            let throttled = Response::builder()
+                .status(StatusCode::TOO_MANY_REQUESTS)
+                .header(RETRY_AFTER, "3600")
+                .body(Body::empty())
+                .unwrap();
+            send.send_response(throttled);
+            let (_, send) = handle.next_request().await.expect("service not called");
+            send.send_response(status(StatusCode::OK));
+        });
+
+        let req = Request::get("/api/v1/pods").body(Body::empty()).unwrap();
+        let res = tokio::time::timeout(Duration::from_secs(1), svc.ready().await.unwrap().call(req))
+            .await
+            .expect("retried before the requested hour")
+            .unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn retries_non_idempotent_requests_only_when_throttled() {
+        let (svc, mut handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut svc = policy(RetryConfig::default()).layer().layer(svc);
+
+        let spawned = tokio::spawn(async move {
+            let (_, send) = handle.next_request().await.expect("service not called");
+            send.send_response(status(StatusCode::TOO_MANY_REQUESTS));
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.into_body().collect_bytes().await.unwrap(), "{}");
+            send.send_response(status(StatusCode::SERVICE_UNAVAILABLE));
+        });
+
+        let req = Request::post("/api/v1/namespaces/default/pods")
+            .body(Body::from(b"{}".to_vec()))
+            .unwrap();
+        let res = svc.ready().await.unwrap().call(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SERVICE_UNAVAILABLE);
+        spawned.await.unwrap();
+    }
+
+    #[test]
+    fn parses_retry_after_seconds() {
+        let res = Response::builder()
+            .status(StatusCode::TOO_MANY_REQUESTS)
+            .header(RETRY_AFTER, "3")
+            .body(())
+            .unwrap();
+        assert_eq!(retry_after(&res), Some(Duration::from_secs(3)));
+        let res = Response::builder()
+            .header(RETRY_AFTER, "Wed, 21 Oct 2015 07:28:00 GMT")
+            .body(())
+            .unwrap();
+        assert_eq!(retry_after(&res), None);
+    }
+}
@@ -7,17 +7,22 @@
 //!
 //! The [`Client`] can also be used with [`Discovery`](crate::Discovery) to dynamically
 //! retrieve the resources served by the kubernetes API.
+use std::sync::Arc;
+
 use either::{Either, Left, Right};
 use futures::{future::BoxFuture, AsyncBufRead, StreamExt, TryStream, TryStreamExt};
+#[cfg(feature = "protobuf")] use http::HeaderValue;
 use http::{self, Request, Response};
 use http_body_util::BodyExt;
 #[cfg(feature = "ws")] use hyper_util::rt::TokioIo;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1 as k8s_meta_v1;
+#[cfg(feature = "protobuf")] use kube_core::protobuf;
 pub use kube_core::response::Status;
 use serde::de::DeserializeOwned;
 use serde_json::{self, Value};
 #[cfg(feature = "ws")]
 use tokio_tungstenite::{tungstenite as ws, WebSocketStream};
+#[cfg(feature = "protobuf")] use tokio_util::codec::LengthDelimitedCodec;
 use tokio_util::{
     codec::{FramedRead, LinesCodec, LinesCodecError},
     io::StreamReader,
@@ -31,6 +36,13 @@ use crate::{api::WatchEvent, error::ErrorResponse, Config, Error, Result};
 mod auth;
 mod body;
 mod builder;
+mod client_set;
+pub use client_set::ClientSet;
+mod reload;
+mod request_options;
+pub use request_options::RequestOptions;
+mod warnings;
+pub use warnings::{CollectWarnings, LogWarnings, StrictWarnings, Warning, WarningHandler};
 #[cfg_attr(docsrs, doc(cfg(feature = "unstable-client")))]
 #[cfg(feature = "unstable-client")]
 mod client_ext;
@@ -78,6 +90,11 @@ pub struct Client {
     // - `BoxFuture` for dynamic response future type
     inner: Buffer<Request<Body>, BoxFuture<'static, Result<Response<Body>, BoxError>>>,
     default_ns: String,
+    options: Option<Arc<RequestOptions>>,
+    warning_handler: Option<Arc<dyn WarningHandler>>,
+    /// Whether reads ask for the protobuf encoding, which [`Api`](crate::Api) sets for typed resources
+    #[cfg(feature = "protobuf")]
+    protobuf: bool,
 }
 
 /// Represents a WebSocket connection.
@@ -154,7 +171,64 @@ impl Client {
         Self {
             inner: Buffer::new(BoxService::new(service), 1024),
             default_ns: default_namespace.into(),
+            options: None,
+            warning_handler: None,
+            #[cfg(feature = "protobuf")]
+            protobuf: false,
+        }
+    }
+
+    /// Handle the warnings that the apiserver sends with responses
+    ///
+    /// Warnings are ignored by default. See [`LogWarnings`], [`CollectWarnings`] and [`StrictWarnings`]
+    /// for the built-in handlers.
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// use kube::{client::LogWarnings, Client};
+    ///
+    /// let client = Client::try_default().await?.with_warning_handler(LogWarnings::deduplicated());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_warning_handler(mut self, handler: impl WarningHandler + 'static) -> Self {
+        self.warning_handler = Some(Arc::new(handler));
+        self
+    }
+
+    /// Create a scoped [`Client`] that applies [`RequestOptions`] to every request
+    ///
+    /// The options replace any options of this client, and the scoped client shares
+    /// its connections with this client.
+    #[must_use]
+    pub fn with_options(mut self, options: RequestOptions) -> Self {
+        self.options = Some(Arc::new(options));
+        self
+    }
+
+    /// Ask for the protobuf encoding of reads of the resources that can be decoded from it
+    ///
+    /// This is only enabled for typed resources, since the protobuf messages do not keep unknown fields.
+    #[cfg(feature = "protobuf")]
+    pub(crate) fn with_protobuf(mut self, protobuf: bool) -> Self {
+        self.protobuf = protobuf;
+        self
+    }
+
+    #[cfg(feature = "protobuf")]
+    fn accept_protobuf(&self, mut request: Request<Vec<u8>>) -> Request<Vec<u8>> {
+        if self.protobuf
+            && request.method() == http::Method::GET
+            && !request.headers().contains_key(http::header::ACCEPT)
+            && protobuf::is_supported_path(request.uri().path())
+        {
+            request.headers_mut().insert(
+                http::header::ACCEPT,
+                HeaderValue::from_static(protobuf::ACCEPT_PROTOBUF),
+            );
         }
+        request
     }
 
     /// Create and initialize a [`Client`] using the inferred configuration.
@@ -190,23 +264,44 @@ impl Client {
     /// Perform a raw HTTP request against the API and return the raw response back.
     /// This method can be used to get raw access to the API which may be used to, for example,
     /// create a proxy server or application-level gateway between localhost and the API server.
-    pub async fn send(&self, request: Request<Body>) -> Result<Response<Body>> {
+    pub async fn send(&self, mut request: Request<Body>) -> Result<Response<Body>> {
+        if let Some(options) = &self.options {
+            options.apply(&mut request)?;
+        }
         let mut svc = self.inner.clone();
-        let res = svc
-            .ready()
-            .await
-            .map_err(Error::Service)?
-            .call(request)
-            .await
-            .map_err(|err| {
-                // Error decorating request
-                err.downcast::<Error>()
-                    .map(|e| *e)
-                    // Error requesting
-                    .or_else(|err| err.downcast::<hyper::Error>().map(|err| Error::HyperError(*err)))
-                    // Error from another middleware
-                    .unwrap_or_else(Error::Service)
-            })?;
+        let send = async move {
+            svc.ready()
+                .await
+                .map_err(Error::Service)?
+                .call(request)
+                .await
+                .map_err(|err| {
+                    // Error decorating request
+                    err.downcast::<Error>()
+                        .map(|e| *e)
+                        // Error requesting
+                        .or_else(|err| err.downcast::<hyper::Error>().map(|err| Error::HyperError(*err)))
+                        // Error from another middleware
+                        .unwrap_or_else(Error::Service)
+                })
+        };
+        let res = match self.options.as_ref().and_then(|options| options.timeout) {
+            Some(timeout) => tokio::time::timeout(timeout, send)
+                .await
+                .map_err(|elapsed| Error::Service(elapsed.into()))??,
+            None => send.await?,
+        };
+        if let Some(handler) = &self.warning_handler {
+            let failed = res.status().is_client_error() || res.status().is_server_error();
+            for warning in Warning::from_headers(res.headers()) {
+                // The error of a failed request is more useful than a failing handler
+                if let Err(err) = handler.handle(&warning) {
+                    if !failed {
+                        return Err(err);
+                    }
+                }
+            }
+        }
         Ok(res)
     }
 
@@ -254,26 +349,82 @@ impl Client {
 
     /// Perform a raw HTTP request against the API and deserialize the response
     /// as JSON to some known type.
+    ///
+    /// With the `protobuf` feature, responses in the protobuf encoding are decoded as well.
+    /// These are only requested by an [`Api`](crate::Api) of the typed Pods, Nodes, Endpoints and EndpointSlices.
     pub async fn request<T>(&self, request: Request<Vec<u8>>) -> Result<T>
     where
         T: DeserializeOwned,
     {
-        let text = self.request_text(request).await?;
+        #[cfg(feature = "protobuf")]
+        let request = self.accept_protobuf(request);
+        let res = self.send(request.map(Body::from)).await?;
+        deserialize_response(res).await
+    }
 
-        serde_json::from_str(&text).map_err(|e| {
-            tracing::warn!("{}, {:?}", text, e);
-            Error::SerdeError(e)
-        })
+    /// Perform a raw HTTP request against the API and deserialize the response
+    /// as JSON to some known type, along with the warnings of the response.
+    ///
+    /// The warnings are also passed to the handler of [`Client::with_warning_handler`].
+    pub async fn request_with_warnings<T>(&self, request: Request<Vec<u8>>) -> Result<(T, Vec<Warning>)>
+    where
+        T: DeserializeOwned,
+    {
+        #[cfg(feature = "protobuf")]
+        let request = self.accept_protobuf(request);
+        let res = self.send(request.map(Body::from)).await?;
+        let warnings = Warning::from_headers(res.headers());
+        Ok((deserialize_response(res).await?, warnings))
+    }
+
+    /// Perform a list request like [`Client::request`], but get back the list metadata of the `Status`
+    /// of an HTTP 410 "Gone" response along with its error
+    ///
+    /// When the continue token of the request has expired, the apiserver can set a fresh continue
+    /// token in this metadata for the rest of an inconsistent list.
+    pub(crate) async fn request_list<T>(
+        &self,
+        request: Request<Vec<u8>>,
+    ) -> Result<Either<T, (Error, k8s_meta_v1::ListMeta)>>
+    where
+        T: DeserializeOwned,
+    {
+        #[derive(serde::Deserialize)]
+        struct GoneStatus {
+            #[serde(default)]
+            metadata: k8s_meta_v1::ListMeta,
+        }
+
+        #[cfg(feature = "protobuf")]
+        let request = self.accept_protobuf(request);
+        let res = self.send(request.map(Body::from)).await?;
+        if res.status() != http::StatusCode::GONE {
+            return deserialize_response(res).await.map(Left);
+        }
+        #[cfg(feature = "protobuf")]
+        let is_protobuf = is_protobuf(&res);
+        let (parts, body) = res.into_parts();
+        let body_bytes = body.collect().await?.to_bytes();
+        #[cfg(feature = "protobuf")]
+        let status = if is_protobuf {
+            protobuf::from_slice::<GoneStatus>(&body_bytes).ok()
+        } else {
+            serde_json::from_slice::<GoneStatus>(&body_bytes).ok()
+        };
+        #[cfg(not(feature = "protobuf"))]
+        let status = serde_json::from_slice::<GoneStatus>(&body_bytes).ok();
+        let metadata = status.map(|status| status.metadata).unwrap_or_default();
+        let Err(err) = handle_api_errors(Response::from_parts(parts, Body::from(body_bytes))).await else {
+            unreachable!("410 is a client error");
+        };
+        Ok(Right((err, metadata)))
     }
 
     /// Perform a raw HTTP request against the API and get back the response
     /// as a string
     pub async fn request_text(&self, request: Request<Vec<u8>>) -> Result<String> {
         let res = self.send(request.map(Body::from)).await?;
-        let res = handle_api_errors(res).await?;
-        let body_bytes = res.into_body().collect().await?.to_bytes();
-        let text = String::from_utf8(body_bytes.to_vec()).map_err(Error::FromUtf8)?;
-        Ok(text)
+        response_text(res).await
     }
 
     /// Perform a raw HTTP request against the API and stream the response body.
@@ -313,6 +464,8 @@ impl Client {
     }
 
     /// Perform a raw request and get back a stream of [`WatchEvent`] objects
+    ///
+    /// Like [`Client::request`], this decodes protobuf watch streams with the `protobuf` feature.
     pub async fn request_events<T>(
         &self,
         request: Request<Vec<u8>>,
@@ -320,64 +473,119 @@ impl Client {
     where
         T: Clone + DeserializeOwned,
     {
+        #[cfg(feature = "protobuf")]
+        let request = self.accept_protobuf(request);
         let res = self.send(request.map(Body::from)).await?;
         // trace!("Streaming from {} -> {}", res.url(), res.status().as_str());
         tracing::trace!("headers: {:?}", res.headers());
+        #[cfg(feature = "protobuf")]
+        let events = if is_protobuf(&res) {
+            let res = handle_api_errors(res).await?;
+            protobuf_events(res.into_body()).left_stream()
+        } else {
+            json_events(res.into_body()).right_stream()
+        };
+        #[cfg(not(feature = "protobuf"))]
+        let events = json_events(res.into_body());
+        Ok(events)
+    }
+}
 
-        let frames = FramedRead::new(
-            StreamReader::new(res.into_body().into_data_stream().map_err(|e| {
-                // Unexpected EOF from chunked decoder.
-                // Tends to happen when watching for 300+s. This will be ignored.
-                if e.to_string().contains("unexpected EOF during chunk") {
-                    return std::io::Error::new(std::io::ErrorKind::UnexpectedEof, e);
-                }
-                std::io::Error::other(e)
-            })),
-            LinesCodec::new(),
-        );
+#[cfg(feature = "protobuf")]
+fn is_protobuf<B>(res: &Response<B>) -> bool {
+    res.headers()
+        .get(http::header::CONTENT_TYPE)
+        .and_then(|ct| ct.to_str().ok())
+        .is_some_and(|ct| ct.starts_with(protobuf::PROTOBUF_MIME))
+}
 
-        Ok(frames.filter_map(|res| async {
-            match res {
-                Ok(line) => match serde_json::from_str::<WatchEvent<T>>(&line) {
-                    Ok(event) => Some(Ok(event)),
-                    Err(e) => {
-                        // Ignore EOF error that can happen for incomplete line from `decode_eof`.
-                        if e.is_eof() {
-                            return None;
-                        }
-
-                        // Got general error response
-                        if let Ok(e_resp) = serde_json::from_str::<ErrorResponse>(&line) {
-                            return Some(Err(Error::Api(e_resp)));
-                        }
-                        // Parsing error
-                        Some(Err(Error::SerdeError(e)))
-                    }
-                },
+fn body_reader(body: Body) -> impl tokio::io::AsyncRead {
+    StreamReader::new(body.into_data_stream().map_err(|e| {
+        // Unexpected EOF from chunked decoder.
+        // Tends to happen when watching for 300+s. This will be ignored.
+        if e.to_string().contains("unexpected EOF during chunk") {
+            return std::io::Error::new(std::io::ErrorKind::UnexpectedEof, e);
+        }
+        std::io::Error::other(e)
+    }))
+}
 
-                Err(LinesCodecError::Io(e)) => match e.kind() {
-                    // Client timeout
-                    std::io::ErrorKind::TimedOut => {
-                        tracing::warn!("timeout in poll: {}", e); // our client timeout
-                        None
+/// Newline separated JSON watch events
+fn json_events<T>(body: Body) -> impl TryStream<Item = Result<WatchEvent<T>>>
+where
+    T: Clone + DeserializeOwned,
+{
+    let frames = FramedRead::new(body_reader(body), LinesCodec::new());
+
+    frames.filter_map(|res| async {
+        match res {
+            Ok(line) => match serde_json::from_str::<WatchEvent<T>>(&line) {
+                Ok(event) => Some(Ok(event)),
+                Err(e) => {
+                    // Ignore EOF error that can happen for incomplete line from `decode_eof`.
+                    if e.is_eof() {
+                        return None;
                     }
-                    // Unexpected EOF from chunked decoder.
-                    // Tends to happen after 300+s of watching.
-                    std::io::ErrorKind::UnexpectedEof => {
-                        tracing::warn!("eof in poll: {}", e);
-                        None
+
+                    // Got general error response
+                    if let Ok(e_resp) = serde_json::from_str::<ErrorResponse>(&line) {
+                        return Some(Err(Error::Api(e_resp)));
                     }
-                    _ => Some(Err(Error::ReadEvents(e))),
-                },
+                    // Parsing error
+                    Some(Err(Error::SerdeError(e)))
+                }
+            },
 
-                // Reached the maximum line length without finding a newline.
-                // This should never happen because we're using the default `usize::MAX`.
-                Err(LinesCodecError::MaxLineLengthExceeded) => {
-                    Some(Err(Error::LinesCodecMaxLineLengthExceeded))
+            Err(LinesCodecError::Io(e)) => match e.kind() {
+                // Client timeout
+                std::io::ErrorKind::TimedOut => {
+                    tracing::warn!("timeout in poll: {}", e); // our client timeout
+                    None
                 }
-            }
-        }))
-    }
+                // Unexpected EOF from chunked decoder.
+                // Tends to happen after 300+s of watching.
+                std::io::ErrorKind::UnexpectedEof => {
+                    tracing::warn!("eof in poll: {}", e);
+                    None
+                }
+                _ => Some(Err(Error::ReadEvents(e))),
+            },
+
+            // Reached the maximum line length without finding a newline.
+            // This should never happen because we're using the default `usize::MAX`.
+            Err(LinesCodecError::MaxLineLengthExceeded) => Some(Err(Error::LinesCodecMaxLineLengthExceeded)),
+        }
+    })
+}
+
+/// Length prefixed protobuf watch events
+#[cfg(feature = "protobuf")]
+fn protobuf_events<T>(body: Body) -> impl TryStream<Item = Result<WatchEvent<T>>>
+where
+    T: Clone + DeserializeOwned,
+{
+    // Frames are prefixed with their length as a 4 byte big endian integer
+    let codec = LengthDelimitedCodec::builder()
+        .max_frame_length(usize::MAX)
+        .new_codec();
+    let frames = FramedRead::new(body_reader(body), codec);
+
+    frames.filter_map(|res| async {
+        match res {
+            Ok(frame) => Some(protobuf::watch_event_from_slice(&frame).map_err(Error::ProtobufDecode)),
+            Err(e) => match e.kind() {
+                std::io::ErrorKind::TimedOut => {
+                    tracing::warn!("timeout in poll: {}", e); // our client timeout
+                    None
+                }
+                std::io::ErrorKind::UnexpectedEof => {
+                    tracing::warn!("eof in poll: {}", e);
+                    None
+                }
+                _ => Some(Err(Error::ReadEvents(e))),
+            },
+        }
+    })
 }
 
 /// Low level discovery methods using `k8s_openapi` types.
@@ -472,7 +680,16 @@ async fn handle_api_errors(res: Response<Body>) -> Result<Response<Body>> {
     let status = res.status();
     if status.is_client_error() || status.is_server_error() {
         // trace!("Status = {:?} for {}", status, res.url());
+        #[cfg(feature = "protobuf")]
+        let is_protobuf = is_protobuf(&res);
         let body_bytes = res.into_body().collect().await?.to_bytes();
+        #[cfg(feature = "protobuf")]
+        if is_protobuf {
+            if let Ok(errdata) = protobuf::from_slice::<ErrorResponse>(&body_bytes) {
+                tracing::debug!("Unsuccessful: {errdata:?}");
+                return Err(Error::Api(errdata));
+            }
+        }
         let text = String::from_utf8(body_bytes.to_vec()).map_err(Error::FromUtf8)?;
         // Print better debug when things do fail
         // trace!("Parsing error: {}", text);
@@ -495,6 +712,31 @@ async fn handle_api_errors(res: Response<Body>) -> Result<Response<Body>> {
     }
 }
 
+async fn response_text(res: Response<Body>) -> Result<String> {
+    let res = handle_api_errors(res).await?;
+    let body_bytes = res.into_body().collect().await?.to_bytes();
+    String::from_utf8(body_bytes.to_vec()).map_err(Error::FromUtf8)
+}
+
+/// Deserialize a response as JSON, or as protobuf when the apiserver sent that
+async fn deserialize_response<T: DeserializeOwned>(res: Response<Body>) -> Result<T> {
+    #[cfg(feature = "protobuf")]
+    if is_protobuf(&res) {
+        let res = handle_api_errors(res).await?;
+        let body_bytes = res.into_body().collect().await?.to_bytes();
+        return protobuf::from_slice(&body_bytes).map_err(Error::ProtobufDecode);
+    }
+    let text = response_text(res).await?;
+    deserialize_text(&text)
+}
+
+fn deserialize_text<T: DeserializeOwned>(text: &str) -> Result<T> {
+    serde_json::from_str(text).map_err(|e| {
+        tracing::warn!("{}, {:?}", text, e);
+        Error::SerdeError(e)
+    })
+}
+
 impl TryFrom<Config> for Client {
     type Error = Error;
 
@@ -556,4 +798,198 @@ mod tests {
         assert_eq!(pod.metadata.annotations.unwrap().get("kube-rs").unwrap(), "test");
         spawned.await.unwrap();
     }
+
+    #[tokio::test]
+    async fn test_request_options() {
+        use crate::client::RequestOptions;
+        use std::time::Duration;
+
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.headers()["impersonate-user"], "alice");
+            let groups = request
+                .headers()
+                .get_all("impersonate-group")
+                .iter()
+                .collect::<Vec<_>>();
+            assert_eq!(groups, ["dev", "ops"]);
+            assert_eq!(request.headers()["x-tenant"], "a");
+            send.send_response(Response::builder().body(Body::from(b"{}".to_vec())).unwrap());
+            // never respond to the second request
+            let _pending = handle.next_request().await.expect("service not called");
+            std::future::pending::<()>().await;
+        });
+
+        let pods: Api<Pod> =
+            Api::default_namespaced(Client::new(mock_service, "default")).with_options(RequestOptions {
+                impersonate_user: Some("alice".into()),
+                impersonate_groups: vec!["dev".into(), "ops".into()],
+                timeout: Some(Duration::from_millis(10)),
+                headers: vec![(
+                    http::HeaderName::from_static("x-tenant"),
+                    http::HeaderValue::from_static("a"),
+                )],
+                ..RequestOptions::default()
+            });
+        pods.get_metadata_opt("test").await.unwrap();
+        assert!(matches!(pods.get("test").await, Err(crate::Error::Service(_))));
+        spawned.abort();
+    }
+
+    #[tokio::test]
+    async fn test_warning_handler() {
+        use crate::{
+            api::{Patch, PatchParams},
+            client::CollectWarnings,
+        };
+
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().query(), Some("&fieldValidation=Warn"));
+            let pod =
+                serde_json::json!({ "apiVersion": "v1", "kind": "Pod", "metadata": { "name": "test" } });
+            send.send_response(
+                Response::builder()
+                    .header(http::header::WARNING, r#"299 - "unknown field \"spec.foo\"""#)
+                    .body(Body::from(serde_json::to_vec(&pod).unwrap()))
+                    .unwrap(),
+            );
+        });
+
+        let warnings = CollectWarnings::new();
+        let client = Client::new(mock_service, "default").with_warning_handler(warnings.clone());
+        let pods: Api<Pod> = Api::default_namespaced(client);
+        let patch = serde_json::json!({ "spec": { "foo": 1 } });
+        let pp = PatchParams::default().validation_warn();
+        let (pod, returned) = pods
+            .patch_with_warnings("test", &pp, &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert_eq!(pod.metadata.name.unwrap(), "test");
+        assert_eq!(returned[0].text, r#"unknown field "spec.foo""#);
+        assert_eq!(warnings.take(), returned);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_proxy_service() {
+        use http_body_util::BodyExt;
+        use tower::ServiceExt;
+
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.method(), http::Method::POST);
+            assert_eq!(
+                request.uri().to_string(),
+                "/api/v1/namespaces/default/pods/test:8080/proxy/echo?q=1"
+            );
+            let body = request.into_body().collect().await.unwrap().to_bytes();
+            // upstream errors are passed through untouched
+            send.send_response(Response::builder().status(418).body(Body::from(body)).unwrap());
+        });
+
+        let pods: Api<Pod> = Api::default_namespaced(Client::new(mock_service, "default"));
+        let req = Request::post("/echo?q=1").body(b"hello".to_vec()).unwrap();
+        let res = pods.proxy_service("test", 8080).oneshot(req).await.unwrap();
+        assert_eq!(res.status(), 418);
+        assert_eq!(&res.into_body().collect().await.unwrap().to_bytes()[..], b"hello");
+        spawned.await.unwrap();
+    }
+
+    #[cfg(feature = "protobuf")]
+    #[tokio::test]
+    async fn test_protobuf_negotiation() {
+        use k8s_openapi::api::core::v1::ConfigMap;
+        use kube_core::protobuf;
+
+        // a length delimited field of a message
+        fn field(number: u8, value: &[u8]) -> Vec<u8> {
+            [&[number << 3 | 2, value.len() as u8], value].concat()
+        }
+
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.headers()["accept"], protobuf::ACCEPT_PROTOBUF);
+            let type_meta = [field(1, b"v1"), field(2, b"Pod")].concat();
+            let pod = field(1, &field(1, b"test"));
+            let body = [protobuf::MAGIC.as_slice(), &field(1, &type_meta), &field(2, &pod)].concat();
+            send.send_response(
+                Response::builder()
+                    .header("content-type", protobuf::PROTOBUF_MIME)
+                    .body(Body::from(body))
+                    .unwrap(),
+            );
+            // resources that cannot be decoded from protobuf are requested as json
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert!(request.headers().get("accept").is_none());
+            send.send_response(
+                Response::builder()
+                    .header("content-type", "application/json")
+                    .body(Body::from(br#"{"metadata":{"name":"config"}}"#.to_vec()))
+                    .unwrap(),
+            );
+        });
+
+        let client = Client::new(mock_service, "default");
+        let pod = Api::<Pod>::default_namespaced(client.clone())
+            .get("test")
+            .await
+            .unwrap();
+        assert_eq!(pod.metadata.name.as_deref(), Some("test"));
+        let cm = Api::<ConfigMap>::default_namespaced(client)
+            .get("config")
+            .await
+            .unwrap();
+        assert_eq!(cm.metadata.name.as_deref(), Some("config"));
+        spawned.await.unwrap();
+    }
+
+    #[cfg(feature = "protobuf")]
+    #[tokio::test]
+    async fn test_protobuf_not_negotiated_for_untyped_requests() {
+        use kube_core::{ApiResource, DynamicObject, GroupVersionKind};
+
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            for _ in 0..3 {
+                let (request, send) = handle.next_request().await.expect("service not called");
+                assert_eq!(request.uri().path(), "/api/v1/namespaces/default/pods/test");
+                assert!(request.headers().get("accept").is_none());
+                send.send_response(
+                    Response::builder()
+                        .header("content-type", "application/json")
+                        .body(Body::from(
+                            br#"{"apiVersion":"v1","kind":"Pod","metadata":{"name":"test"}}"#.to_vec(),
+                        ))
+                        .unwrap(),
+                );
+            }
+        });
+
+        let client = Client::new(mock_service, "default");
+        let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("", "v1", "Pod"));
+        let pod = Api::<DynamicObject>::default_namespaced_with(client.clone(), &ar)
+            .get("test")
+            .await
+            .unwrap();
+        assert_eq!(pod.metadata.name.as_deref(), Some("test"));
+        let request = Request::get("/api/v1/namespaces/default/pods/test")
+            .body(vec![])
+            .unwrap();
+        let pod: Pod = client.request(request.clone()).await.unwrap();
+        assert_eq!(pod.metadata.name.as_deref(), Some("test"));
+        // the client of a typed Api makes raw requests as JSON again
+        let client = Api::<Pod>::default_namespaced(client).into_client();
+        let _: Pod = client.request(request).await.unwrap();
+        spawned.await.unwrap();
+    }
 }
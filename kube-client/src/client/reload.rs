@@ -0,0 +1,117 @@
+//! Values loaded from files that are rotated on disk, like token files and certificates
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+    time::{Duration, Instant},
+};
+
+/// How long a loaded value is used before loading it again
+const RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A value that is loaded again at most once per [`RELOAD_INTERVAL`]
+///
+/// The last value is kept when loading fails. Only one caller loads at a time, without holding the lock,
+/// so that other callers keep using the current value in the meantime instead of waiting.
+pub(crate) struct Reloading<T> {
+    current: RwLock<(T, Instant)>,
+    loading: AtomicBool,
+    load: Box<dyn Fn() -> Option<T> + Send + Sync>,
+}
+
+impl<T: Clone> Reloading<T> {
+    /// Load the initial value, which fails if it cannot be loaded
+    pub(crate) fn new<E>(load: impl Fn() -> Result<T, E> + Send + Sync + 'static) -> Result<Self, E>
+    where
+        E: std::error::Error + 'static,
+    {
+        let value = load()?;
+        Ok(Self {
+            current: RwLock::new((value, Instant::now() + RELOAD_INTERVAL)),
+            loading: AtomicBool::new(false),
+            load: Box::new(move || {
+                load()
+                    .map_err(|err| tracing::warn!(error = &err as &dyn std::error::Error, "failed to reload"))
+                    .ok()
+            }),
+        })
+    }
+
+    /// Get the current value, loading it again first if it is due
+    pub(crate) fn get(&self) -> T {
+        let now = Instant::now();
+        let (value, due) = {
+            let current = self.current.read().unwrap_or_else(|err| err.into_inner());
+            (current.0.clone(), current.1 <= now)
+        };
+        if !due || self.loading.swap(true, Ordering::Acquire) {
+            return value;
+        }
+        let loaded = (self.load)();
+        let mut current = self.current.write().unwrap_or_else(|err| err.into_inner());
+        if let Some(value) = loaded {
+            current.0 = value;
+        }
+        // a failed load is not retried until the next interval either
+        current.1 = Instant::now() + RELOAD_INTERVAL;
+        let value = current.0.clone();
+        drop(current);
+        self.loading.store(false, Ordering::Release);
+        value
+    }
+
+    #[cfg(test)]
+    pub(crate) fn expire(&self) {
+        self.current.write().unwrap().1 = Instant::now();
+    }
+}
+
+impl<T> std::fmt::Debug for Reloading<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Reloading").finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        fmt,
+        sync::{atomic::AtomicUsize, Arc},
+    };
+
+    #[derive(Debug)]
+    struct LoadError;
+
+    impl fmt::Display for LoadError {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            f.write_str("load error")
+        }
+    }
+
+    impl std::error::Error for LoadError {}
+
+    #[test]
+    fn reloading_keeps_last_value_on_errors() {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let counter = loads.clone();
+        let reloading = Reloading::new(move || match counter.fetch_add(1, Ordering::SeqCst) {
+            1 => Err(LoadError),
+            n => Ok(n),
+        })
+        .unwrap();
+
+        // Not reloaded before the interval
+        assert_eq!(reloading.get(), 0);
+        assert_eq!(loads.load(Ordering::SeqCst), 1);
+
+        reloading.expire();
+        assert_eq!(reloading.get(), 0, "failed reload keeps the last value");
+        assert_eq!(reloading.get(), 0, "failed reload is not retried immediately");
+        assert_eq!(loads.load(Ordering::SeqCst), 2);
+
+        reloading.expire();
+        assert_eq!(reloading.get(), 2);
+    }
+}
@@ -0,0 +1,72 @@
+use std::time::Duration;
+
+use http::{header::HeaderName, HeaderValue, Request};
+
+use crate::{client::Body, Error, Result};
+
+/// Options applied to every request of a scoped [`Client`](crate::Client) or [`Api`](crate::Api)
+///
+/// Created with [`Client::with_options`](crate::Client::with_options) or [`Api::with_options`](crate::Api::with_options),
+/// and unlike the [`Config`](crate::Config) these only apply to the scoped client, not the clients it was created from.
+///
+/// ```no_run
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// # use k8s_openapi::api::core::v1::Pod;
+/// # use kube::{Api, Client, client::RequestOptions};
+/// # use std::time::Duration;
+/// # let client: Client = todo!();
+/// let pods: Api<Pod> = Api::namespaced(client, "tenant-a").with_options(RequestOptions {
+///     impersonate_user: Some("system:serviceaccount:tenant-a:app".into()),
+///     timeout: Some(Duration::from_secs(5)),
+///     ..RequestOptions::default()
+/// });
+/// let pod = pods.get("blog").await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RequestOptions {
+    /// The user to impersonate
+    ///
+    /// Impersonation set here replaces the impersonation of the [`Config`](crate::Config), including its groups.
+    pub impersonate_user: Option<String>,
+    /// The groups to impersonate
+    pub impersonate_groups: Vec<String>,
+    /// The uid of the user to impersonate
+    pub impersonate_uid: Option<String>,
+    /// The maximum time to wait for the response of a request
+    ///
+    /// This covers sending the request and receiving the response headers, so it does not
+    /// cut off the streaming responses of watches and logs. A request that times out fails with
+    /// [`Error::Service`].
+    pub timeout: Option<Duration>,
+    /// Extra headers to add to each request
+    pub headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl RequestOptions {
+    pub(crate) fn apply(&self, request: &mut Request<Body>) -> Result<()> {
+        let headers = request.headers_mut();
+        let users = self
+            .impersonate_user
+            .iter()
+            .map(|user| ("impersonate-user", user));
+        let groups = self
+            .impersonate_groups
+            .iter()
+            .map(|group| ("impersonate-group", group));
+        let uids = self.impersonate_uid.iter().map(|uid| ("impersonate-uid", uid));
+        for (name, value) in users.chain(groups).chain(uids) {
+            headers.append(
+                HeaderName::from_static(name),
+                HeaderValue::from_str(value)
+                    .map_err(http::Error::from)
+                    .map_err(Error::HttpError)?,
+            );
+        }
+        for (name, value) in &self.headers {
+            headers.append(name.clone(), value.clone());
+        }
+        Ok(())
+    }
+}
@@ -1,14 +1,26 @@
 #[cfg(feature = "rustls-tls")]
 pub mod rustls_tls {
+    use std::{
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+
     use hyper_rustls::ConfigBuilderExt;
     use rustls::{
         self,
-        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        client::{
+            danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+            ResolvesClientCert, WebPkiServerVerifier,
+        },
+        crypto::CryptoProvider,
         pki_types::{CertificateDer, InvalidDnsNameError, PrivateKeyDer, ServerName},
-        ClientConfig, DigitallySignedStruct,
+        sign::CertifiedKey,
+        ClientConfig, DigitallySignedStruct, InconsistentKeys,
     };
     use thiserror::Error;
 
+    use crate::{client::reload::Reloading, config::TlsReloadConfig};
+
     /// Errors from Rustls
     #[derive(Debug, Error)]
     pub enum Error {
@@ -44,15 +56,35 @@ pub mod rustls_tls {
         /// Invalid server name
         #[error("invalid server name: {0}")]
         InvalidServerName(#[source] InvalidDnsNameError),
+
+        /// Failed to read a certificate file
+        #[error("failed to read certificate file {}: {source}", path.display())]
+        ReadCertificateFile {
+            /// The certificate file
+            path: PathBuf,
+            /// The underlying error
+            #[source]
+            source: std::io::Error,
+        },
     }
 
     /// Create `rustls::ClientConfig`.
+    ///
+    /// Certificates from the files of `reload` take precedence over `identity_pem` and `root_certs`.
     pub fn rustls_client_config(
         identity_pem: Option<&[u8]>,
         root_certs: Option<&[Vec<u8>]>,
         accept_invalid: bool,
+        reload: Option<&TlsReloadConfig>,
     ) -> Result<ClientConfig, Error> {
-        let config_builder = if let Some(certs) = root_certs {
+        let reload_root_cert = reload.and_then(|reload| reload.root_cert.as_ref());
+        let config_builder = if let Some(path) = reload_root_cert.filter(|_| !accept_invalid) {
+            let provider = ClientConfig::builder().crypto_provider().clone();
+            let verifier = ReloadingVerifier::new(path.clone(), provider)?;
+            ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(verifier))
+        } else if let Some(certs) = root_certs {
             ClientConfig::builder().with_root_certificates(root_store(certs)?)
         } else {
             #[cfg(feature = "webpki-roots")]
@@ -69,7 +101,16 @@ pub mod rustls_tls {
             }
         };
 
-        let mut client_config = if let Some((chain, pkey)) = identity_pem.map(client_auth).transpose()? {
+        let reload_identity = reload.and_then(|reload| {
+            let cert = reload.client_certificate.clone()?;
+            let key = reload.client_key.clone().unwrap_or_else(|| cert.clone());
+            Some((cert, key))
+        });
+        let mut client_config = if let Some((cert, key)) = reload_identity {
+            let provider = config_builder.crypto_provider().clone();
+            let resolver = ReloadingClientCert::new(cert, key, provider)?;
+            config_builder.with_client_cert_resolver(Arc::new(resolver))
+        } else if let Some((chain, pkey)) = identity_pem.map(client_auth).transpose()? {
             config_builder
                 .with_client_auth_cert(chain, pkey)
                 .map_err(Error::InvalidPrivateKey)?
@@ -123,6 +164,120 @@ pub mod rustls_tls {
         Ok((cert_chain, private_key))
     }
 
+    fn read_file(path: &Path) -> Result<Vec<u8>, Error> {
+        std::fs::read(path).map_err(|source| Error::ReadCertificateFile {
+            path: path.to_path_buf(),
+            source,
+        })
+    }
+
+    /// Resolves the client certificate from files
+    #[derive(Debug)]
+    struct ReloadingClientCert(Reloading<Arc<CertifiedKey>>);
+
+    impl ReloadingClientCert {
+        fn new(cert: PathBuf, key: PathBuf, provider: Arc<CryptoProvider>) -> Result<Self, Error> {
+            Reloading::new(move || {
+                let mut pem = read_file(&cert)?;
+                if key != cert {
+                    pem.push(b'\n');
+                    pem.extend(read_file(&key)?);
+                }
+                let (chain, key) = client_auth(&pem)?;
+                let key = provider
+                    .key_provider
+                    .load_private_key(key)
+                    .map_err(Error::InvalidPrivateKey)?;
+                let certified = CertifiedKey::new(chain, key);
+                // files read while they are being rotated can pair the new certificate with the old key
+                match certified.keys_match() {
+                    Err(err @ rustls::Error::InconsistentKeys(InconsistentKeys::KeyMismatch)) => {
+                        Err(Error::InvalidPrivateKey(err))
+                    }
+                    // providers that cannot tell the public key of a private key are trusted
+                    _ => Ok(Arc::new(certified)),
+                }
+            })
+            .map(Self)
+        }
+    }
+
+    impl ResolvesClientCert for ReloadingClientCert {
+        fn resolve(
+            &self,
+            _root_hint_subjects: &[&[u8]],
+            _sigschemes: &[rustls::SignatureScheme],
+        ) -> Option<Arc<CertifiedKey>> {
+            Some(self.0.get())
+        }
+
+        fn has_certs(&self) -> bool {
+            true
+        }
+    }
+
+    /// Verifies server certificates with root certificates from a file
+    #[derive(Debug)]
+    struct ReloadingVerifier(Reloading<Arc<WebPkiServerVerifier>>);
+
+    impl ReloadingVerifier {
+        fn new(path: PathBuf, provider: Arc<CryptoProvider>) -> Result<Self, Error> {
+            Reloading::new(move || {
+                use rustls::pki_types::pem::{self, SectionKind};
+
+                let data = read_file(&path)?;
+                let mut roots = Vec::new();
+                let mut reader = std::io::Cursor::new(data);
+                while let Some((kind, der)) = pem::from_buf(&mut reader).map_err(Error::InvalidIdentityPem)? {
+                    if kind == SectionKind::Certificate {
+                        roots.push(der);
+                    }
+                }
+                WebPkiServerVerifier::builder_with_provider(Arc::new(root_store(&roots)?), provider.clone())
+                    .build()
+                    .map_err(|e| Error::AddRootCertificate(Box::new(e)))
+            })
+            .map(Self)
+        }
+    }
+
+    impl ServerCertVerifier for ReloadingVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer,
+            intermediates: &[CertificateDer],
+            server_name: &ServerName,
+            ocsp_response: &[u8],
+            now: rustls::pki_types::UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            self.0
+                .get()
+                .verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.0.get().verify_tls12_signature(message, cert, dss)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.0.get().verify_tls13_signature(message, cert, dss)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+            self.0.get().supported_verify_schemes()
+        }
+    }
+
     #[derive(Debug)]
     struct NoCertificateVerification {}
 
@@ -176,6 +331,149 @@ pub mod rustls_tls {
             ]
         }
     }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use rustls::{pki_types::UnixTime, ClientConnection, ServerConfig, ServerConnection};
+
+        const CA_1: &[u8] = include_bytes!("./test_data/ca1.pem");
+        // signed the server certificate
+        const CA_2: &[u8] = include_bytes!("./test_data/ca2.pem");
+        const CLIENT_1: &[u8] = include_bytes!("./test_data/client1.pem");
+        const CLIENT_1_KEY: &[u8] = include_bytes!("./test_data/client1.key");
+        const CLIENT_2: &[u8] = include_bytes!("./test_data/client2.pem");
+        const CLIENT_2_KEY: &[u8] = include_bytes!("./test_data/client2.key");
+        const SERVER: &[u8] = include_bytes!("./test_data/server.pem");
+        const SERVER_KEY: &[u8] = include_bytes!("./test_data/server.key");
+        const SERVER_NAME: &str = "kubernetes.default";
+
+        struct Files(tempfile::TempDir);
+
+        impl Files {
+            fn new(ca: &[u8], cert: &[u8], key: &[u8]) -> Self {
+                let files = Self(tempfile::tempdir().unwrap());
+                files.write(ca, cert, key);
+                files
+            }
+
+            fn write(&self, ca: &[u8], cert: &[u8], key: &[u8]) {
+                std::fs::write(self.ca(), ca).unwrap();
+                std::fs::write(self.cert(), cert).unwrap();
+                std::fs::write(self.key(), key).unwrap();
+            }
+
+            fn ca(&self) -> PathBuf {
+                self.0.path().join("ca.pem")
+            }
+
+            fn cert(&self) -> PathBuf {
+                self.0.path().join("client.pem")
+            }
+
+            fn key(&self) -> PathBuf {
+                self.0.path().join("client.key")
+            }
+        }
+
+        fn provider() -> Arc<CryptoProvider> {
+            ClientConfig::builder().crypto_provider().clone()
+        }
+
+        fn chain(pem: &[u8]) -> Vec<CertificateDer<'static>> {
+            client_auth(&[pem, CLIENT_1_KEY].concat()).unwrap().0
+        }
+
+        fn verify_server(verifier: &ReloadingVerifier) -> Result<ServerCertVerified, rustls::Error> {
+            let server_name = ServerName::try_from(SERVER_NAME).unwrap();
+            verifier.verify_server_cert(&chain(SERVER)[0], &[], &server_name, &[], UnixTime::now())
+        }
+
+        /// Complete a handshake in memory with a server that uses the certificate signed by `CA_2`
+        fn handshake(config: ClientConfig) -> Result<(), rustls::Error> {
+            let (server_chain, server_key) = client_auth(&[SERVER, SERVER_KEY].concat()).unwrap();
+            let server_config = ServerConfig::builder()
+                .with_no_client_auth()
+                .with_single_cert(server_chain, server_key)
+                .unwrap();
+            let server_name = ServerName::try_from(SERVER_NAME).unwrap();
+            let mut client = ClientConnection::new(Arc::new(config), server_name)?;
+            let mut server = ServerConnection::new(Arc::new(server_config))?;
+            while client.is_handshaking() || server.is_handshaking() {
+                let mut buf = Vec::new();
+                client.write_tls(&mut buf).unwrap();
+                server.read_tls(&mut buf.as_slice()).unwrap();
+                server.process_new_packets()?;
+                buf.clear();
+                server.write_tls(&mut buf).unwrap();
+                client.read_tls(&mut buf.as_slice()).unwrap();
+                client.process_new_packets()?;
+            }
+            Ok(())
+        }
+
+        #[test]
+        fn client_config_uses_reloaded_files() {
+            let files = Files::new(CA_2, CLIENT_1, CLIENT_1_KEY);
+            let reload = TlsReloadConfig {
+                root_cert: Some(files.ca()),
+                client_certificate: Some(files.cert()),
+                client_key: Some(files.key()),
+            };
+            // the files take precedence over the certificates of the kubeconfig
+            let identity = [CLIENT_2, CLIENT_2_KEY].concat();
+            let root_certs = [chain(CA_1)[0].to_vec()];
+            let config =
+                rustls_client_config(Some(&identity), Some(&root_certs), false, Some(&reload)).unwrap();
+
+            let resolved = config.client_auth_cert_resolver.resolve(&[], &[]).unwrap();
+            assert_eq!(resolved.cert, chain(CLIENT_1));
+            handshake(config).unwrap();
+
+            files.write(CA_1, CLIENT_1, CLIENT_1_KEY);
+            let config = rustls_client_config(None, None, false, Some(&reload)).unwrap();
+            assert!(
+                handshake(config).is_err(),
+                "server is not trusted by the new roots"
+            );
+        }
+
+        #[test]
+        fn reloads_rotated_certificates() {
+            let files = Files::new(CA_1, CLIENT_1, CLIENT_1_KEY);
+            let resolver = ReloadingClientCert::new(files.cert(), files.key(), provider()).unwrap();
+            let verifier = ReloadingVerifier::new(files.ca(), provider()).unwrap();
+            assert_eq!(resolver.resolve(&[], &[]).unwrap().cert, chain(CLIENT_1));
+            assert!(verify_server(&verifier).is_err());
+
+            files.write(CA_2, CLIENT_2, CLIENT_2_KEY);
+            // not reloaded before the interval
+            assert_eq!(resolver.resolve(&[], &[]).unwrap().cert, chain(CLIENT_1));
+            assert!(verify_server(&verifier).is_err());
+
+            resolver.0.expire();
+            verifier.0.expire();
+            assert_eq!(resolver.resolve(&[], &[]).unwrap().cert, chain(CLIENT_2));
+            verify_server(&verifier).unwrap();
+        }
+
+        #[test]
+        fn reloading_keeps_identity_when_key_does_not_match() {
+            let files = Files::new(CA_1, CLIENT_1, CLIENT_1_KEY);
+            let resolver = ReloadingClientCert::new(files.cert(), files.key(), provider()).unwrap();
+
+            // the certificate was rotated, but the key was not yet
+            files.write(CA_1, CLIENT_2, CLIENT_1_KEY);
+            resolver.0.expire();
+            let resolved = resolver.resolve(&[], &[]).unwrap();
+            assert_eq!(resolved.cert, chain(CLIENT_1));
+            resolved.keys_match().unwrap();
+
+            files.write(CA_1, CLIENT_2, CLIENT_2_KEY);
+            resolver.0.expire();
+            assert_eq!(resolver.resolve(&[], &[]).unwrap().cert, chain(CLIENT_2));
+        }
+    }
 }
 
 #[cfg(feature = "openssl-tls")]
@@ -197,6 +495,10 @@ pub mod openssl_tls {
         /// Failed to create OpenSSL SSL connector
         #[error("failed to create OpenSSL SSL connector: {0}")]
         CreateSslConnector(#[source] SslConnectorError),
+
+        /// Reloading certificates with `Config::tls_reload` is only supported with rustls
+        #[error("reloading TLS certificates is not supported with OpenSSL, use the rustls-tls feature")]
+        TlsReloadUnsupported,
     }
 
     /// Errors from creating a `SslConnectorBuilder`
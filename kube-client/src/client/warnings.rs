@@ -0,0 +1,214 @@
+use std::{
+    collections::HashSet,
+    fmt,
+    sync::{Arc, Mutex},
+};
+
+use http::{header::WARNING, HeaderMap};
+
+use crate::{Error, Result};
+
+/// A warning sent by the apiserver in a `Warning` header
+///
+/// The apiserver warns about the use of deprecated APIs, and about unknown or duplicate fields
+/// when using [`PatchParams::validation_warn`](crate::api::PatchParams::validation_warn).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Warning {
+    /// The warning code, `299` for warnings from the apiserver
+    pub code: u16,
+    /// The agent that added the warning, usually `-`
+    pub agent: String,
+    /// The text of the warning
+    pub text: String,
+}
+
+impl fmt::Display for Warning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+impl Warning {
+    /// Parse the warnings in the `Warning` headers of a response
+    ///
+    /// Warnings that are not in the `<code> <agent> "<text>"` format are skipped.
+    pub fn from_headers(headers: &HeaderMap) -> Vec<Warning> {
+        headers
+            .get_all(WARNING)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(parse_warnings)
+            .collect()
+    }
+}
+
+/// Parse a header value, which can contain several comma separated warnings
+fn parse_warnings(mut value: &str) -> Vec<Warning> {
+    let mut warnings = vec![];
+    while let Some((warning, rest)) = parse_warning(value.trim_start()) {
+        warnings.push(warning);
+        match rest.trim_start().strip_prefix(',') {
+            Some(rest) => value = rest,
+            None => break,
+        }
+    }
+    warnings
+}
+
+/// Parse one `warn-code SP warn-agent SP warn-text [SP warn-date]` warning
+fn parse_warning(value: &str) -> Option<(Warning, &str)> {
+    let (code, rest) = value.split_once(' ')?;
+    let code = code.parse().ok().filter(|_| code.len() == 3)?;
+    let (agent, rest) = rest.split_once(' ')?;
+    let (text, mut rest) = parse_quoted(rest)?;
+    // The date is optional, and not used
+    if let Some((_, after_date)) = rest.strip_prefix(' ').and_then(parse_quoted) {
+        rest = after_date;
+    }
+    let warning = Warning {
+        code,
+        agent: agent.to_owned(),
+        text,
+    };
+    Some((warning, rest))
+}
+
+/// Parse a quoted string, returning its unescaped contents and the remaining input
+fn parse_quoted(value: &str) -> Option<(String, &str)> {
+    let mut chars = value.strip_prefix('"')?.char_indices();
+    let mut text = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '"' => return Some((text, &value[i + 2..])),
+            '\\' => text.push(chars.next()?.1),
+            c => text.push(c),
+        }
+    }
+    None
+}
+
+/// Handles the warnings that the apiserver sends with responses
+///
+/// Set on a [`Client`](crate::Client) with [`Client::with_warning_handler`](crate::Client::with_warning_handler).
+/// Closures taking a `&Warning` and returning a [`Result`] are also handlers.
+pub trait WarningHandler: Send + Sync {
+    /// Handle a warning of a response
+    ///
+    /// Returning an error fails the request, even though the apiserver handled it successfully.
+    fn handle(&self, warning: &Warning) -> Result<()>;
+}
+
+impl<F> WarningHandler for F
+where
+    F: Fn(&Warning) -> Result<()> + Send + Sync,
+{
+    fn handle(&self, warning: &Warning) -> Result<()> {
+        self(warning)
+    }
+}
+
+/// A [`WarningHandler`] that logs warnings
+#[derive(Debug, Default)]
+pub struct LogWarnings {
+    seen: Option<Mutex<HashSet<String>>>,
+}
+
+impl LogWarnings {
+    /// Log every warning
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Log each distinct warning once, like `kubectl` does
+    pub fn deduplicated() -> Self {
+        Self {
+            seen: Some(Mutex::default()),
+        }
+    }
+}
+
+impl WarningHandler for LogWarnings {
+    fn handle(&self, warning: &Warning) -> Result<()> {
+        if let Some(seen) = &self.seen {
+            if !seen.lock().unwrap().insert(warning.text.clone()) {
+                return Ok(());
+            }
+        }
+        tracing::warn!("apiserver warning: {}", warning.text);
+        Ok(())
+    }
+}
+
+/// A [`WarningHandler`] that collects warnings
+///
+/// Clones share the collected warnings, so a clone can be kept to read the warnings
+/// that the client receives.
+#[derive(Clone, Debug, Default)]
+pub struct CollectWarnings {
+    warnings: Arc<Mutex<Vec<Warning>>>,
+}
+
+impl CollectWarnings {
+    /// Create an empty collection of warnings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the warnings collected so far
+    pub fn take(&self) -> Vec<Warning> {
+        std::mem::take(&mut *self.warnings.lock().unwrap())
+    }
+}
+
+impl WarningHandler for CollectWarnings {
+    fn handle(&self, warning: &Warning) -> Result<()> {
+        self.warnings.lock().unwrap().push(warning.clone());
+        Ok(())
+    }
+}
+
+/// A [`WarningHandler`] that fails requests with warnings, with [`Error::Warning`]
+///
+/// The apiserver has already handled the request when its warnings are received,
+/// so changes from requests that fail this way have still been made.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StrictWarnings;
+
+impl WarningHandler for StrictWarnings {
+    fn handle(&self, warning: &Warning) -> Result<()> {
+        Err(Error::Warning(warning.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_warning_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            WARNING,
+            r#"299 - "batch/v1beta1 CronJob is deprecated in v1.21+, unavailable in v1.25+; use batch/v1 CronJob""#
+                .parse()
+                .unwrap(),
+        );
+        headers.append(
+            WARNING,
+            r#"299 - "unknown field \"spec.foo\"" "Tue, 15 Nov 1994 08:12:31 GMT", 299 kube "second""#
+                .parse()
+                .unwrap(),
+        );
+        headers.append(WARNING, "not a warning".parse().unwrap());
+
+        let warnings = Warning::from_headers(&headers);
+        let texts = warnings.iter().map(|w| w.text.as_str()).collect::<Vec<_>>();
+        assert_eq!(texts, [
+            "batch/v1beta1 CronJob is deprecated in v1.21+, unavailable in v1.25+; use batch/v1 CronJob",
+            r#"unknown field "spec.foo""#,
+            "second",
+        ]);
+        assert_eq!(warnings[2].code, 299);
+        assert_eq!(warnings[2].agent, "kube");
+    }
+}
@@ -304,6 +304,26 @@ pub struct ExecConfig {
     /// Should be used only when `provide_cluster_info` is True.
     #[serde(skip)]
     pub cluster: Option<ExecAuthCluster>,
+
+    /// Cache credentials returned by the plugin on disk, to reuse them across clients and processes.
+    ///
+    /// This does not exist upstream and cannot be specified on disk.
+    /// See [`Config::cache_exec_credentials`](crate::Config::cache_exec_credentials).
+    #[serde(skip)]
+    pub credential_cache: Option<ExecCredentialCache>,
+}
+
+/// On-disk cache for credentials of an exec plugin
+///
+/// Credentials are stored in `dir`, in a file named after the exec config, the cluster and the inherited environment
+/// of the plugin, and reused until shortly before their `expirationTimestamp`.
+/// Credentials without an expiration are never cached.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExecCredentialCache {
+    /// Directory to store credentials in
+    pub dir: PathBuf,
+    /// Identifies the cluster that the credentials are for, usually its url
+    pub cluster: String,
 }
 
 /// ExecInteractiveMode define the interactity of the child process
@@ -358,34 +378,7 @@ impl Kubeconfig {
         let mut merged_docs = None;
         for mut config in kubeconfig_from_yaml(&data)? {
             if let Some(dir) = path.as_ref().parent() {
-                for named in config.clusters.iter_mut() {
-                    if let Some(cluster) = &mut named.cluster {
-                        if let Some(path) = &cluster.certificate_authority {
-                            if let Some(abs_path) = to_absolute(dir, path) {
-                                cluster.certificate_authority = Some(abs_path);
-                            }
-                        }
-                    }
-                }
-                for named in config.auth_infos.iter_mut() {
-                    if let Some(auth_info) = &mut named.auth_info {
-                        if let Some(path) = &auth_info.client_certificate {
-                            if let Some(abs_path) = to_absolute(dir, path) {
-                                auth_info.client_certificate = Some(abs_path);
-                            }
-                        }
-                        if let Some(path) = &auth_info.client_key {
-                            if let Some(abs_path) = to_absolute(dir, path) {
-                                auth_info.client_key = Some(abs_path);
-                            }
-                        }
-                        if let Some(path) = &auth_info.token_file {
-                            if let Some(abs_path) = to_absolute(dir, path) {
-                                auth_info.token_file = Some(abs_path);
-                            }
-                        }
-                    }
-                }
+                config.absolutize_paths(dir);
             }
             if let Some(c) = merged_docs {
                 merged_docs = Some(Kubeconfig::merge(c, config)?);
@@ -422,15 +415,8 @@ impl Kubeconfig {
     ///
     /// Panics if `KUBECONFIG` value contains the NUL character.
     pub fn from_env() -> Result<Option<Self>, KubeconfigError> {
-        match std::env::var_os(KUBECONFIG) {
-            Some(value) => {
-                let paths = std::env::split_paths(&value)
-                    .filter(|p| !p.as_os_str().is_empty())
-                    .collect::<Vec<_>>();
-                if paths.is_empty() {
-                    return Ok(None);
-                }
-
+        match env_paths() {
+            Some(paths) => {
                 let merged = paths.iter().try_fold(Kubeconfig::default(), |m, p| {
                     Kubeconfig::read_from(p).and_then(|c| m.merge(c))
                 })?;
@@ -471,6 +457,128 @@ impl Kubeconfig {
         self.extensions = self.extensions.or(next.extensions);
         Ok(self)
     }
+
+    /// Add or replace the cluster called `name`
+    ///
+    /// The equivalent of `kubectl config set-cluster`.
+    pub fn set_cluster(&mut self, name: impl Into<String>, cluster: Cluster) {
+        let named = NamedCluster {
+            name: name.into(),
+            cluster: Some(cluster),
+        };
+        set_named(&mut self.clusters, named, |x| &x.name);
+    }
+
+    /// Add or replace the user called `name`
+    ///
+    /// The equivalent of `kubectl config set-credentials`.
+    pub fn set_credentials(&mut self, name: impl Into<String>, auth_info: AuthInfo) {
+        let named = NamedAuthInfo {
+            name: name.into(),
+            auth_info: Some(auth_info),
+        };
+        set_named(&mut self.auth_infos, named, |x| &x.name);
+    }
+
+    /// Add or replace the context called `name`
+    ///
+    /// The equivalent of `kubectl config set-context`.
+    pub fn set_context(&mut self, name: impl Into<String>, context: Context) {
+        let named = NamedContext {
+            name: name.into(),
+            context: Some(context),
+        };
+        set_named(&mut self.contexts, named, |x| &x.name);
+    }
+
+    /// Set the current context to an existing context
+    ///
+    /// The equivalent of `kubectl config use-context`.
+    pub fn use_context(&mut self, name: &str) -> Result<(), KubeconfigError> {
+        if !self.contexts.iter().any(|x| x.name == name) {
+            return Err(KubeconfigError::ContextNotFound(name.to_owned()));
+        }
+        self.current_context = Some(name.to_owned());
+        Ok(())
+    }
+
+    /// Rename a context, and the current context if it refers to it
+    ///
+    /// The equivalent of `kubectl config rename-context`.
+    pub fn rename_context(&mut self, name: &str, new_name: impl Into<String>) -> Result<(), KubeconfigError> {
+        let new_name = new_name.into();
+        if self.contexts.iter().any(|x| x.name == new_name) {
+            return Err(KubeconfigError::ContextExists(new_name));
+        }
+        let named = self
+            .contexts
+            .iter_mut()
+            .find(|x| x.name == name)
+            .ok_or_else(|| KubeconfigError::ContextNotFound(name.to_owned()))?;
+        named.name.clone_from(&new_name);
+        if self.current_context.as_deref() == Some(name) {
+            self.current_context = Some(new_name);
+        }
+        Ok(())
+    }
+
+    /// Remove the cluster called `name`, returning it if it existed
+    pub fn delete_cluster(&mut self, name: &str) -> Option<NamedCluster> {
+        remove_named(&mut self.clusters, name, |x| &x.name)
+    }
+
+    /// Remove the user called `name`, returning it if it existed
+    pub fn delete_credentials(&mut self, name: &str) -> Option<NamedAuthInfo> {
+        remove_named(&mut self.auth_infos, name, |x| &x.name)
+    }
+
+    /// Remove the context called `name`, returning it if it existed
+    ///
+    /// Like `kubectl config delete-context`, this leaves the current context unchanged.
+    pub fn delete_context(&mut self, name: &str) -> Option<NamedContext> {
+        remove_named(&mut self.contexts, name, |x| &x.name)
+    }
+
+    /// Remap the relative file paths in the config to absolute paths in `dir`
+    pub(super) fn absolutize_paths(&mut self, dir: &Path) {
+        for named in self.clusters.iter_mut() {
+            if let Some(cluster) = &mut named.cluster {
+                if let Some(path) = &cluster.certificate_authority {
+                    if let Some(abs_path) = to_absolute(dir, path) {
+                        cluster.certificate_authority = Some(abs_path);
+                    }
+                }
+            }
+        }
+        for named in self.auth_infos.iter_mut() {
+            if let Some(auth_info) = &mut named.auth_info {
+                if let Some(path) = &auth_info.client_certificate {
+                    if let Some(abs_path) = to_absolute(dir, path) {
+                        auth_info.client_certificate = Some(abs_path);
+                    }
+                }
+                if let Some(path) = &auth_info.client_key {
+                    if let Some(abs_path) = to_absolute(dir, path) {
+                        auth_info.client_key = Some(abs_path);
+                    }
+                }
+                if let Some(path) = &auth_info.token_file {
+                    if let Some(abs_path) = to_absolute(dir, path) {
+                        auth_info.token_file = Some(abs_path);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Returns the non-empty paths listed in `KUBECONFIG`, in order of precedence.
+pub(super) fn env_paths() -> Option<Vec<PathBuf>> {
+    let value = std::env::var_os(KUBECONFIG)?;
+    let paths = std::env::split_paths(&value)
+        .filter(|p| !p.as_os_str().is_empty())
+        .collect::<Vec<_>>();
+    (!paths.is_empty()).then_some(paths)
 }
 
 fn kubeconfig_from_yaml(text: &str) -> Result<Vec<Kubeconfig>, KubeconfigError> {
@@ -497,7 +605,25 @@ where
     });
 }
 
-fn read_path<P: AsRef<Path>>(path: P) -> io::Result<String> {
+fn set_named<T, F>(base: &mut Vec<T>, named: T, f: F)
+where
+    F: Fn(&T) -> &String,
+{
+    match base.iter_mut().find(|x| f(x) == f(&named)) {
+        Some(existing) => *existing = named,
+        None => base.push(named),
+    }
+}
+
+fn remove_named<T, F>(base: &mut Vec<T>, name: &str, f: F) -> Option<T>
+where
+    F: Fn(&T) -> &String,
+{
+    let index = base.iter().position(|x| f(x) == name)?;
+    Some(base.remove(index))
+}
+
+pub(super) fn read_path<P: AsRef<Path>>(path: P) -> io::Result<String> {
     let bytes = fs::read(&path)?;
     match bytes.as_slice() {
         [0xFF, 0xFE, ..] => {
@@ -659,7 +785,7 @@ fn ensure_trailing_newline(mut data: Vec<u8>) -> Vec<u8> {
 }
 
 /// Returns kubeconfig path from `$HOME/.kube/config`.
-fn default_kube_path() -> Option<PathBuf> {
+pub(super) fn default_kube_path() -> Option<PathBuf> {
     home::home_dir().map(|h| h.join(".kube").join("config"))
 }
 
@@ -913,6 +1039,35 @@ users:
         Ok(())
     }
 
+    #[test]
+    fn kubeconfig_edit_contexts() {
+        let mut config = Kubeconfig::default();
+        config.set_context("dev", Context {
+            cluster: "dev".into(),
+            ..Context::default()
+        });
+        assert!(matches!(
+            config.use_context("prod"),
+            Err(KubeconfigError::ContextNotFound(_))
+        ));
+        config.use_context("dev").unwrap();
+
+        config.rename_context("dev", "staging").unwrap();
+        assert_eq!(config.current_context.as_deref(), Some("staging"));
+        assert_eq!(config.contexts[0].name, "staging");
+
+        config.set_context("staging", Context {
+            cluster: "staging".into(),
+            ..Context::default()
+        });
+        assert_eq!(config.contexts.len(), 1);
+        assert_eq!(config.contexts[0].context.as_ref().unwrap().cluster, "staging");
+
+        assert!(config.delete_context("staging").is_some());
+        assert!(config.contexts.is_empty());
+        assert_eq!(config.current_context.as_deref(), Some("staging"));
+    }
+
     #[test]
     fn kubeconfig_from_empty_string() {
         let cfg = Kubeconfig::from_yaml("").unwrap();
@@ -4,7 +4,7 @@ use super::{
 };
 
 /// KubeConfigOptions stores options used when loading kubeconfig file.
-#[derive(Default, Clone)]
+#[derive(Default, Clone, Debug, PartialEq, Eq, Hash)]
 pub struct KubeConfigOptions {
     /// The named context to load
     pub context: Option<String>,
@@ -0,0 +1,502 @@
+use std::{
+    collections::HashSet,
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+use serde_yaml::{Mapping, Value};
+
+use super::{
+    file_config::{default_kube_path, env_paths, read_path},
+    Kubeconfig, KubeconfigError,
+};
+
+/// Sections of named entries, which are saved to the file that defines each entry
+const NAMED_SECTIONS: [&str; 3] = ["clusters", "users", "contexts"];
+/// Top level values, which are saved to the first file that sets them
+const TOP_LEVEL_KEYS: [&str; 3] = ["current-context", "preferences", "extensions"];
+
+/// Writing an edited config back to the files it was read from
+impl Kubeconfig {
+    /// Save the config to the files in `KUBECONFIG`, or the default location
+    ///
+    /// This is the counterpart of [`Kubeconfig::read`], and follows the rules of `kubectl config`
+    /// for deciding where each change is written. See [`Kubeconfig::save_to_files`].
+    pub fn save(&self) -> Result<(), KubeconfigError> {
+        match env_paths() {
+            Some(paths) => self.save_to_files(&paths),
+            None => self.save_to_files(&[default_kube_path().ok_or(KubeconfigError::FindPath)?]),
+        }
+    }
+
+    /// Save the config to a list of kubeconfig files, given in order of precedence
+    ///
+    /// The config is compared to the merge of the files, and only the differences are written:
+    ///
+    /// - Changed and deleted clusters, users and contexts are written to the first file that defines them.
+    /// - `current-context`, `preferences` and `extensions` are written to the first file that sets them.
+    /// - Anything else is written to the only file, the first file that exists, or the last file, in that order.
+    ///
+    /// Files are only rewritten when something in them changed. Fields of the files that
+    /// [`Kubeconfig`] does not know about, and relative paths that were not changed, are kept as is.
+    pub fn save_to_files<P: AsRef<Path>>(&self, paths: &[P]) -> Result<(), KubeconfigError> {
+        let mut files = paths
+            .iter()
+            .map(|path| KubeconfigFile::load(path.as_ref()))
+            .collect::<Result<Vec<_>, _>>()?;
+        if files.is_empty() {
+            return Ok(());
+        }
+        let config = serde_yaml::to_value(self).map_err(KubeconfigError::Serialize)?;
+        let default = default_file(&files);
+
+        for section in NAMED_SECTIONS {
+            let entries = config
+                .get(section)
+                .and_then(Value::as_sequence)
+                .map(Vec::as_slice)
+                .unwrap_or_default();
+            let names = entries.iter().filter_map(entry_name).collect::<HashSet<_>>();
+            remove_deleted(&mut files, section, &names);
+
+            let mut saved = HashSet::new();
+            for entry in entries {
+                let Some(name) = entry_name(entry) else { continue };
+                if !saved.insert(name) {
+                    // Like when merging, only the first entry with a name is used
+                    continue;
+                }
+                let origin = files.iter().enumerate().find_map(|(f, file)| {
+                    file.docs
+                        .iter()
+                        .enumerate()
+                        .find_map(|(d, doc)| doc.find(section, name).map(|index| (f, d, index)))
+                });
+                match origin {
+                    Some((f, d, index)) => {
+                        let doc = &mut files[f].docs[d];
+                        let (raw, abs) = (&doc.raw[section][index], &doc.abs[section][index]);
+                        if abs != entry {
+                            let patched = patch(raw, abs, entry);
+                            doc.raw[section][index] = patched;
+                            doc.abs[section][index] = entry.clone();
+                            files[f].changed = true;
+                        }
+                    }
+                    None => {
+                        let doc = files[default].first_doc();
+                        doc.push(section, entry.clone());
+                        files[default].changed = true;
+                    }
+                }
+            }
+        }
+
+        for key in TOP_LEVEL_KEYS {
+            let value = config.get(key).filter(|value| !value.is_null());
+            let origin = files.iter().enumerate().find_map(|(f, file)| {
+                file.docs
+                    .iter()
+                    .position(|doc| doc.abs.get(key).is_some_and(|value| !value.is_null()))
+                    .map(|d| (f, d))
+            });
+            match (origin, value) {
+                (Some((f, d)), value) => {
+                    let doc = &mut files[f].docs[d];
+                    let abs = &doc.abs[key];
+                    if Some(abs) == value {
+                        continue;
+                    }
+                    let raw = doc
+                        .raw
+                        .as_mapping_mut()
+                        .expect("documents with values are mappings");
+                    match value {
+                        Some(value) => {
+                            let patched = patch(&raw[key], abs, value);
+                            raw.insert(key.into(), patched);
+                        }
+                        None => {
+                            raw.remove(key);
+                        }
+                    }
+                    files[f].changed = true;
+                }
+                (None, Some(value)) => {
+                    let doc = files[default].first_doc();
+                    doc.raw[key] = value.clone();
+                    files[default].changed = true;
+                }
+                (None, None) => {}
+            }
+        }
+
+        for file in files.iter().filter(|file| file.changed) {
+            file.write()?;
+        }
+        Ok(())
+    }
+}
+
+/// A kubeconfig file, with each of its YAML documents
+struct KubeconfigFile {
+    path: PathBuf,
+    exists: bool,
+    docs: Vec<Document>,
+    changed: bool,
+}
+
+/// A YAML document of a kubeconfig file
+struct Document {
+    /// The document as it is written in the file
+    raw: Value,
+    /// The document as it is read into a [`Kubeconfig`], with absolute paths
+    abs: Value,
+}
+
+impl KubeconfigFile {
+    fn load(path: &Path) -> Result<Self, KubeconfigError> {
+        let (exists, data) = match read_path(path) {
+            Ok(data) => (true, data),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => (false, String::new()),
+            Err(err) => return Err(KubeconfigError::ReadConfig(err, path.into())),
+        };
+        let mut docs = vec![];
+        for doc in serde_yaml::Deserializer::from_str(&data) {
+            let raw = Value::deserialize(doc).map_err(KubeconfigError::Parse)?;
+            let mut config: Kubeconfig =
+                serde_yaml::from_value(raw.clone()).map_err(KubeconfigError::InvalidStructure)?;
+            if let Some(dir) = path.parent() {
+                config.absolutize_paths(dir);
+            }
+            let abs = serde_yaml::to_value(config).map_err(KubeconfigError::Serialize)?;
+            docs.push(Document { raw, abs });
+        }
+        Ok(Self {
+            path: path.into(),
+            exists,
+            docs,
+            changed: false,
+        })
+    }
+
+    /// The document that new values are added to
+    fn first_doc(&mut self) -> &mut Document {
+        if self.docs.is_empty() || self.docs[0].raw.is_null() {
+            let mut raw = Mapping::new();
+            raw.insert("apiVersion".into(), "v1".into());
+            raw.insert("kind".into(), "Config".into());
+            self.docs.truncate(0);
+            self.docs.push(Document {
+                raw: Value::Mapping(raw),
+                abs: Value::Mapping(Mapping::new()),
+            });
+        }
+        &mut self.docs[0]
+    }
+
+    fn write(&self) -> Result<(), KubeconfigError> {
+        use std::io::Write;
+
+        let mut data = String::new();
+        for (i, doc) in self.docs.iter().enumerate() {
+            if i > 0 {
+                data.push_str("---\n");
+            }
+            data.push_str(&serde_yaml::to_string(&doc.raw).map_err(KubeconfigError::Serialize)?);
+        }
+        let write = || {
+            // Replace the target of a symlinked kubeconfig rather than the link
+            let path = match fs::canonicalize(&self.path) {
+                Ok(path) => path,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => self.path.clone(),
+                Err(err) => return Err(err),
+            };
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir)?;
+            }
+            let permissions = match fs::metadata(&path) {
+                Ok(meta) => Some(meta.permissions()),
+                Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+                Err(err) => return Err(err),
+            };
+            // Write to a temporary file and rename it, so that readers never see a partial file
+            let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+            let mut options = fs::OpenOptions::new();
+            options.write(true).create(true).truncate(true);
+            // Kubeconfigs often contain credentials, so new files are only readable by the owner
+            #[cfg(unix)]
+            std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+            let written = options.open(&tmp).and_then(|mut file| {
+                file.write_all(data.as_bytes())?;
+                if let Some(permissions) = permissions {
+                    file.set_permissions(permissions)?;
+                }
+                file.sync_all()
+            });
+            let renamed = written.and_then(|()| fs::rename(&tmp, &path));
+            if renamed.is_err() {
+                let _ = fs::remove_file(&tmp);
+            }
+            renamed
+        };
+        write().map_err(|err| KubeconfigError::WriteConfig(err, self.path.clone()))
+    }
+}
+
+impl Document {
+    /// Find the index of the entry called `name` in a section
+    fn find(&self, section: &str, name: &str) -> Option<usize> {
+        self.abs
+            .get(section)?
+            .as_sequence()?
+            .iter()
+            .position(|entry| entry_name(entry) == Some(name))
+    }
+
+    fn push(&mut self, section: &str, entry: Value) {
+        for doc in [&mut self.raw, &mut self.abs] {
+            if !doc[section].is_sequence() {
+                doc[section] = Value::Sequence(vec![]);
+            }
+            if let Value::Sequence(entries) = &mut doc[section] {
+                entries.push(entry.clone());
+            }
+        }
+    }
+
+    fn remove(&mut self, section: &str, index: usize) {
+        for doc in [&mut self.raw, &mut self.abs] {
+            if let Value::Sequence(entries) = &mut doc[section] {
+                entries.remove(index);
+            }
+        }
+    }
+}
+
+fn entry_name(entry: &Value) -> Option<&str> {
+    entry.get("name")?.as_str()
+}
+
+/// Remove entries from the first file that defines them, when they are no longer in the config
+fn remove_deleted(files: &mut [KubeconfigFile], section: &str, names: &HashSet<&str>) {
+    let mut seen = HashSet::new();
+    for file in files {
+        for doc in &mut file.docs {
+            let Some(entries) = doc.abs.get(section).and_then(Value::as_sequence) else {
+                continue;
+            };
+            let deleted = entries
+                .iter()
+                .enumerate()
+                .filter_map(|(index, entry)| {
+                    let name = entry_name(entry)?.to_owned();
+                    let first = seen.insert(name.clone());
+                    (first && !names.contains(name.as_str())).then_some(index)
+                })
+                .collect::<Vec<_>>();
+            for index in deleted.into_iter().rev() {
+                doc.remove(section, index);
+                file.changed = true;
+            }
+        }
+    }
+}
+
+/// Pick the file that new entries are written to, following `kubectl config`
+fn default_file(files: &[KubeconfigFile]) -> usize {
+    files
+        .iter()
+        .position(|file| file.exists)
+        .unwrap_or(files.len() - 1)
+}
+
+/// Apply the changes from `abs` to `new` to the `raw` value from a file
+///
+/// Unchanged values are kept as written, so that relative paths stay relative,
+/// and fields that are unknown to [`Kubeconfig`] are kept.
+fn patch(raw: &Value, abs: &Value, new: &Value) -> Value {
+    if abs == new {
+        return raw.clone();
+    }
+    match (raw, abs, new) {
+        (Value::Mapping(raw), Value::Mapping(abs), Value::Mapping(new)) => {
+            let mut patched = Mapping::new();
+            for (key, raw_value) in raw {
+                match (abs.get(key), new.get(key)) {
+                    (Some(abs_value), Some(new_value)) => {
+                        patched.insert(key.clone(), patch(raw_value, abs_value, new_value));
+                    }
+                    (None, Some(new_value)) => {
+                        patched.insert(key.clone(), new_value.clone());
+                    }
+                    // Not known to `Kubeconfig`, so it can't have been changed
+                    (None, None) => {
+                        patched.insert(key.clone(), raw_value.clone());
+                    }
+                    // Removed from the config
+                    (Some(_), None) => {}
+                }
+            }
+            for (key, new_value) in new {
+                if !raw.contains_key(key) {
+                    patched.insert(key.clone(), new_value.clone());
+                }
+            }
+            Value::Mapping(patched)
+        }
+        _ => new.clone(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{AuthInfo, Cluster, Context};
+    use secrecy::SecretString;
+
+    fn write(dir: &Path, name: &str, data: &str) -> PathBuf {
+        let path = dir.join(name);
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn read(path: &Path) -> Value {
+        serde_yaml::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn save_edits_to_defining_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let first = write(
+            dir.path(),
+            "first",
+            r#"
+apiVersion: v1
+kind: Config
+clusters:
+- name: dev
+  cluster:
+    server: https://dev.example.com
+    certificate-authority: certs/dev.crt
+    extensions:
+    - name: vendor
+      extension:
+        region: eu
+    x-unknown-cluster-field: kept
+contexts:
+- name: dev
+  context:
+    cluster: dev
+    user: dev
+x-unknown-top-level: kept
+"#,
+        );
+        let second = write(
+            dir.path(),
+            "second",
+            r#"
+apiVersion: v1
+kind: Config
+current-context: dev
+users:
+- name: dev
+  user:
+    token: old
+    x-unknown-user-field: kept
+- name: stale
+  user:
+    token: stale
+"#,
+        );
+        let paths = [first.clone(), second.clone()];
+        let mut config = paths
+            .iter()
+            .try_fold(Kubeconfig::default(), |m, p| {
+                Kubeconfig::read_from(p).and_then(|c| m.merge(c))
+            })
+            .unwrap();
+
+        config.set_credentials("dev", AuthInfo {
+            token: Some(SecretString::new("new".into())),
+            ..AuthInfo::default()
+        });
+        config.delete_credentials("stale");
+        config.set_cluster("prod", Cluster {
+            server: Some("https://prod.example.com".into()),
+            ..Cluster::default()
+        });
+        config.set_context("prod", Context {
+            cluster: "prod".into(),
+            user: Some("dev".into()),
+            ..Context::default()
+        });
+        config.use_context("prod").unwrap();
+        config.save_to_files(&paths).unwrap();
+
+        let first = read(&first);
+        assert_eq!(first["x-unknown-top-level"], "kept");
+        let dev = &first["clusters"][0]["cluster"];
+        assert_eq!(dev["certificate-authority"], "certs/dev.crt");
+        assert_eq!(dev["x-unknown-cluster-field"], "kept");
+        assert_eq!(dev["extensions"][0]["extension"]["region"], "eu");
+        assert_eq!(first["clusters"][1]["name"], "prod");
+        assert_eq!(first["contexts"][1]["name"], "prod");
+        assert!(first.get("current-context").is_none());
+
+        let second = read(&second);
+        assert_eq!(second["current-context"], "prod");
+        let users = second["users"].as_sequence().unwrap();
+        assert_eq!(users.len(), 1);
+        assert_eq!(users[0]["user"]["token"], "new");
+        assert_eq!(users[0]["user"]["x-unknown-user-field"], "kept");
+        assert!(second.get("clusters").is_none());
+    }
+
+    #[test]
+    fn save_creates_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let paths = [
+            dir.path().join("missing"),
+            dir.path().join("nested").join("config"),
+        ];
+        let mut config = Kubeconfig::default();
+        config.set_cluster("dev", Cluster {
+            server: Some("https://dev.example.com".into()),
+            ..Cluster::default()
+        });
+        config.save_to_files(&paths).unwrap();
+
+        assert!(!paths[0].exists());
+        let saved = Kubeconfig::read_from(&paths[1]).unwrap();
+        assert_eq!(saved.clusters, config.clusters);
+        assert_eq!(read(&paths[1])["kind"], "Config");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn save_keeps_file_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let existing = write(dir.path(), "config", "kind: Config\napiVersion: v1\n");
+        fs::set_permissions(&existing, fs::Permissions::from_mode(0o640)).unwrap();
+        let created = dir.path().join("created");
+
+        let mut config = Kubeconfig::read_from(&existing).unwrap();
+        config.set_cluster("dev", Cluster {
+            server: Some("https://dev.example.com".into()),
+            ..Cluster::default()
+        });
+        config.save_to_files(&[&existing]).unwrap();
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&existing), 0o640);
+        assert_eq!(read(&existing)["clusters"][0]["name"], "dev");
+
+        config.save_to_files(&[&created]).unwrap();
+        assert_eq!(mode(&created), 0o600);
+        // No temporary files are left behind
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+}
@@ -14,6 +14,7 @@ use thiserror::Error;
 
 mod file_config;
 mod file_loader;
+mod file_writer;
 mod incluster_config;
 
 use file_loader::ConfigLoader;
@@ -53,6 +54,14 @@ pub enum KubeconfigError {
     #[error("failed to load the cluster of context: {0}")]
     LoadClusterOfContext(String),
 
+    /// No context exists with the given name
+    #[error("no context exists with the name: {0}")]
+    ContextNotFound(String),
+
+    /// A context with the given name already exists
+    #[error("a context with the name {0} already exists")]
+    ContextExists(String),
+
     /// Failed to find the path of kubeconfig
     #[error("failed to find the path of kubeconfig")]
     FindPath,
@@ -69,6 +78,14 @@ pub enum KubeconfigError {
     #[error("the structure of the parsed kubeconfig is invalid: {0}")]
     InvalidStructure(#[source] serde_yaml::Error),
 
+    /// Failed to serialize kubeconfig to YAML
+    #[error("failed to serialize kubeconfig YAML: {0}")]
+    Serialize(#[source] serde_yaml::Error),
+
+    /// Failed to write kubeconfig
+    #[error("failed to write kubeconfig to '{1:?}': {0}")]
+    WriteConfig(#[source] std::io::Error, PathBuf),
+
     /// Cluster url is missing on selected cluster
     #[error("cluster url is missing on selected cluster")]
     MissingClusterUrl,
@@ -161,6 +178,100 @@ pub struct Config {
     pub tls_server_name: Option<String>,
     /// Headers to pass with every request.
     pub headers: Vec<(HeaderName, HeaderValue)>,
+    /// Retry transient failures (throttling, unavailable apiserver, connection errors)
+    ///
+    /// A value of `None` means requests are never retried.
+    pub retry: Option<RetryConfig>,
+    /// Client-side throttling of requests
+    ///
+    /// A value of `None` means requests are only throttled by the apiserver.
+    pub rate_limit: Option<RateLimitConfig>,
+    /// Reload TLS certificates from files when they are rotated on disk
+    ///
+    /// A value of `None` means certificates are only loaded when the client is built.
+    pub tls_reload: Option<TlsReloadConfig>,
+}
+
+/// Files to reload TLS certificates from
+///
+/// New connections use the latest certificates, so certificates rotated by e.g. cert-manager are used
+/// without rebuilding the client. Like token files, the files are read again at most once per minute,
+/// and the previous certificates are kept if the files cannot be loaded.
+///
+/// Reloading is only supported with the `rustls-tls` feature. Building an OpenSSL connector fails
+/// with [`OpensslTlsError::TlsReloadUnsupported`](crate::client::OpensslTlsError::TlsReloadUnsupported) when it is set.
+#[derive(Debug, Clone, Default)]
+pub struct TlsReloadConfig {
+    /// PEM file with the root certificates, which replace [`Config::root_cert`]
+    pub root_cert: Option<PathBuf>,
+    /// PEM file with the client certificate chain, which replaces the client certificate of [`Config::auth_info`]
+    pub client_certificate: Option<PathBuf>,
+    /// PEM file with the private key of the client certificate
+    ///
+    /// Defaults to the `client_certificate` file.
+    pub client_key: Option<PathBuf>,
+}
+
+/// Settings for client-side throttling with a token bucket
+///
+/// Matches the `QPS` and `Burst` settings of client-go.
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Sustained number of requests per second
+    pub qps: f32,
+    /// Number of requests that can be made at once before throttling kicks in
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        // client-go defaults
+        Self { qps: 5.0, burst: 10 }
+    }
+}
+
+/// Settings for retrying transient request failures
+///
+/// Requests are retried on `429 Too Many Requests`, `500`, `502`, `503` and `504` responses,
+/// and on connection errors. A `Retry-After` header sent by the apiserver takes precedence over the backoff,
+/// but is also capped at `max_backoff`.
+///
+/// Only idempotent verbs (`GET`, `HEAD`, `OPTIONS`, `PUT` and `DELETE`) are retried by default,
+/// except when the apiserver could not have processed the request (throttled or never connected).
+#[derive(Debug, Clone)]
+pub struct RetryConfig {
+    /// Maximum number of retries for a single request
+    pub max_retries: u32,
+    /// Backoff before the first retry, doubled for every further retry
+    pub initial_backoff: Duration,
+    /// Upper bound for the backoff between retries, including delays requested with `Retry-After`
+    pub max_backoff: Duration,
+    /// Whether to also retry `POST` and `PATCH` requests
+    ///
+    /// Only enable this if your requests are safe to repeat, e.g. when using server-side apply.
+    pub retry_non_idempotent: bool,
+    /// The number of retries that are always allowed per second, regardless of the retry budget
+    pub min_retries_per_sec: u32,
+    /// The fraction of requests that can be retried on top of `min_retries_per_sec`
+    ///
+    /// For example, `0.2` allows one retry for every five requests. Must be between 0 and 1000.
+    pub retry_percent: f32,
+    /// How long requests are remembered for the retry budget
+    pub budget_ttl: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(10),
+            retry_non_idempotent: false,
+            min_retries_per_sec: 10,
+            retry_percent: 0.2,
+            budget_ttl: Duration::from_secs(10),
+        }
+    }
 }
 
 impl Config {
@@ -183,6 +294,9 @@ impl Config {
             proxy_url: None,
             tls_server_name: None,
             headers: Vec::new(),
+            retry: None,
+            rate_limit: None,
+            tls_reload: None,
         }
     }
 
@@ -266,6 +380,9 @@ impl Config {
             proxy_url: None,
             tls_server_name: None,
             headers: Vec::new(),
+            retry: None,
+            rate_limit: None,
+            tls_reload: None,
         })
     }
 
@@ -327,9 +444,46 @@ impl Config {
             auth_info: loader.user,
             tls_server_name: loader.cluster.tls_server_name,
             headers: Vec::new(),
+            retry: None,
+            rate_limit: None,
+            tls_reload: None,
         })
     }
 
+    /// Cache credentials of the exec plugin in `dir`
+    ///
+    /// Plugins like `aws eks get-token` or `gke-gcloud-auth-plugin` are slow to run,
+    /// which is noticeable in CLIs that build a new client for every invocation.
+    /// With the cache, their credentials are reused until shortly before they expire.
+    /// The cache is keyed by the exec config, the [`Config::cluster_url`], and the inherited environment variables
+    /// that select the identity of common plugins, like `AWS_PROFILE`, `CLOUDSDK_CONFIG` or `KUBECONFIG`.
+    /// One directory can be shared between clusters and users, but plugins that pick an identity from other state,
+    /// like a file changed by `gcloud config set account`, keep returning the cached credentials until they expire.
+    /// The directory is created readable only by the current user, and the cache is not used when the directory
+    /// is owned by another user or writable by other users, so it should not be shared like the temporary directory.
+    ///
+    /// Does nothing when the user is not authenticated with an exec plugin.
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// use kube::{Client, Config};
+    /// let mut config = Config::infer().await?;
+    /// let home = std::env::var_os("HOME").ok_or("HOME is not set")?;
+    /// config.cache_exec_credentials(std::path::Path::new(&home).join(".kube/cache/my-cli"));
+    /// let client = Client::try_from(config)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn cache_exec_credentials(&mut self, dir: impl Into<PathBuf>) {
+        let cluster = self.cluster_url.to_string();
+        if let Some(exec) = &mut self.auth_info.exec {
+            exec.credential_cache = Some(ExecCredentialCache {
+                dir: dir.into(),
+                cluster,
+            });
+        }
+    }
+
     /// Override configuration based on environment variables
     ///
     /// This is only intended for use as a debugging aid, and the specific variables and their behaviour
@@ -394,8 +548,8 @@ const DEFAULT_WRITE_TIMEOUT: Duration = Duration::from_secs(295);
 
 // Expose raw config structs
 pub use file_config::{
-    AuthInfo, AuthProviderConfig, Cluster, Context, ExecAuthCluster, ExecConfig, ExecInteractiveMode,
-    Kubeconfig, NamedAuthInfo, NamedCluster, NamedContext, NamedExtension, Preferences,
+    AuthInfo, AuthProviderConfig, Cluster, Context, ExecAuthCluster, ExecConfig, ExecCredentialCache,
+    ExecInteractiveMode, Kubeconfig, NamedAuthInfo, NamedCluster, NamedContext, NamedExtension, Preferences,
 };
 
 #[cfg(test)]
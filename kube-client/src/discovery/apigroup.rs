@@ -1,9 +1,12 @@
 use super::parse::{self, GroupVersionData};
 use crate::{error::DiscoveryError, Client, Error, Result};
-use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIGroup, APIVersions};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{
+    APIGroup, APIResourceList, APIVersions, GroupVersionForDiscovery,
+};
 pub use kube_core::discovery::{ApiCapabilities, ApiResource};
 use kube_core::{
-    gvk::{GroupVersion, GroupVersionKind, ParseGroupVersionError},
+    discovery::v2::{APIGroupDiscovery, FRESHNESS_STALE},
+    gvk::{GroupVersion, GroupVersionKind},
     Version,
 };
 use std::{cmp::Reverse, collections::HashMap, iter::Iterator};
@@ -124,6 +127,72 @@ impl ApiGroup {
         Ok(group)
     }
 
+    /// Convert a group from aggregated discovery, which already contains all of its resources
+    ///
+    /// Versions whose resources are stale are skipped, and `None` is returned if no version is left.
+    pub(crate) fn from_aggregated(g: APIGroupDiscovery) -> Option<Self> {
+        let name = g.metadata.name.unwrap_or_default();
+        let mut data = vec![];
+        for ver in g.versions {
+            if ver.freshness.as_deref() == Some(FRESHNESS_STALE) {
+                tracing::warn!(
+                    group = name.as_str(),
+                    version = ver.version.as_str(),
+                    "Skipping stale group version"
+                );
+                continue;
+            }
+            data.push(GroupVersionData::from_aggregated(&name, ver));
+        }
+        // versions are listed in order of preference
+        let preferred = data.first()?.version.clone();
+        let mut group = ApiGroup {
+            name,
+            data,
+            preferred: Some(preferred),
+        };
+        group.sort_versions();
+        Some(group)
+    }
+
+    /// Rebuild a group from legacy discovery documents, with one resource list per version of the group
+    pub(crate) fn from_resource_lists(g: APIGroup, lists: Vec<APIResourceList>) -> Result<Self> {
+        let mut data = vec![];
+        for (vers, list) in g.versions.iter().zip(lists) {
+            data.push(GroupVersionData::new(vers.version.clone(), list)?);
+        }
+        if data.is_empty() {
+            return Err(Error::Discovery(DiscoveryError::EmptyApiGroup(g.name)));
+        }
+        let mut group = ApiGroup {
+            name: g.name,
+            data,
+            preferred: g.preferred_version.map(|v| v.version),
+        };
+        group.sort_versions();
+        Ok(group)
+    }
+
+    /// Convert to legacy discovery documents, inverse of [`ApiGroup::from_resource_lists`]
+    pub(crate) fn to_resource_lists(&self) -> (APIGroup, Vec<APIResourceList>) {
+        let to_discovery = |version: &str| GroupVersionForDiscovery {
+            group_version: GroupVersion::gv(&self.name, version).api_version(),
+            version: version.to_string(),
+        };
+        let group = APIGroup {
+            name: self.name.clone(),
+            versions: self.versions().map(to_discovery).collect(),
+            preferred_version: self.preferred.as_deref().map(to_discovery),
+            server_address_by_client_cidrs: None,
+        };
+        let lists = self
+            .data
+            .iter()
+            .map(|gvd| gvd.to_resource_list(&self.name))
+            .collect();
+        (group, lists)
+    }
+
     fn sort_versions(&mut self) {
         self.data
             .sort_by_cached_key(|gvd| Reverse(Version::parse(gvd.version.as_str()).priority()))
@@ -140,16 +209,8 @@ impl ApiGroup {
         } else {
             client.list_api_group_resources(&apiver).await?
         };
-        for res in &list.resources {
-            if res.kind == gvk.kind && !res.name.contains('/') {
-                let ar = parse::parse_apiresource(res, &list.group_version).map_err(
-                    |ParseGroupVersionError(s)| Error::Discovery(DiscoveryError::InvalidGroupVersion(s)),
-                )?;
-                let caps = parse::parse_apicapabilities(&list, &res.name)?;
-                return Ok((ar, caps));
-            }
-        }
-        Err(Error::Discovery(DiscoveryError::MissingKind(format!("{gvk:?}"))))
+        parse::find_kind(&list, &gvk.kind)?
+            .ok_or_else(|| Error::Discovery(DiscoveryError::MissingKind(format!("{gvk:?}"))))
     }
 
     // shortcut method to give cheapest return for a pinned group
@@ -0,0 +1,309 @@
+//! On-disk cache of discovery results, laid out like the cache kept by kubectl
+use super::{parse, ApiGroup};
+use crate::{error::DiscoveryError, Client, Error, Result};
+use http::Uri;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIGroupList, APIResourceList};
+use kube_core::{
+    discovery::{ApiCapabilities, ApiResource},
+    gvk::GroupVersionKind,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+const SERVER_GROUPS: &str = "servergroups.json";
+const SERVER_RESOURCES: &str = "serverresources.json";
+
+/// A cache of discovery results for one cluster, stored on disk in the same layout as kubectl
+///
+/// The cache contains a `servergroups.json` file with an `APIGroupList`, and a
+/// `<group>/<version>/serverresources.json` file with an `APIResourceList` for every group version.
+/// Files are considered fresh until they are older than the [ttl](DiscoveryCache::ttl).
+///
+/// The lists only contain the fields that kube uses, so the cache must not be shared with kubectl,
+/// which would read them as complete discovery results.
+///
+/// Use it with [`Discovery::cache`](crate::discovery::Discovery::cache) to skip full discovery
+/// on startup while the cache is fresh, or with [`DiscoveryCache::pinned_kind`] for a single kind.
+///
+/// ```no_run
+/// use kube::{Client, Config, discovery::{Discovery, DiscoveryCache}};
+/// #[tokio::main]
+/// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+///     let config = Config::infer().await?;
+///     let cache = DiscoveryCache::for_cluster(&config.cluster_url).expect("home directory");
+///     let client = Client::try_from(config)?;
+///     let discovery = Discovery::new(client).cache(cache).run().await?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DiscoveryCache {
+    dir: PathBuf,
+    ttl: Duration,
+}
+
+impl DiscoveryCache {
+    /// Default time to live of cached files, matching kubectl
+    pub const DEFAULT_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+    /// Use a cache stored in the given directory
+    ///
+    /// The directory is specific to a cluster, see [`DiscoveryCache::for_cluster`] for the default directory.
+    /// It must not be kubectl's discovery cache directory.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            ttl: Self::DEFAULT_TTL,
+        }
+    }
+
+    /// Use the default cache directory for the cluster at `cluster_url`
+    ///
+    /// This is `$KUBECACHEDIR/kube-rs/discovery/<host>`, where `KUBECACHEDIR` defaults to `~/.kube/cache`,
+    /// next to kubectl's `discovery` directory.
+    /// Returns `None` if `KUBECACHEDIR` is unset and the home directory cannot be determined.
+    pub fn for_cluster(cluster_url: &Uri) -> Option<Self> {
+        let parent = match std::env::var_os("KUBECACHEDIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => home::home_dir()?.join(".kube").join("cache"),
+        };
+        Some(Self::new(
+            parent
+                .join("kube-rs")
+                .join("discovery")
+                .join(cache_dir_name(cluster_url)),
+        ))
+    }
+
+    /// Set how long cached files are considered fresh
+    #[must_use]
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Returns the directory of this cache
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Removes all cached files, so that the next lookup queries the apiserver
+    pub fn invalidate(&self) -> io::Result<()> {
+        match fs::remove_dir_all(&self.dir) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Finds an [`ApiResource`] and its [`ApiCapabilities`] for a single kind, using the cache when fresh
+    ///
+    /// This is the cached equivalent of [`oneshot::pinned_kind`](crate::discovery::pinned_kind).
+    /// If the kind is missing from the cached group version, the cache is invalidated
+    /// and the group version is queried again, so that newly installed CRDs are found.
+    pub async fn pinned_kind(
+        &self,
+        client: &Client,
+        gvk: &GroupVersionKind,
+    ) -> Result<(ApiResource, ApiCapabilities)> {
+        let apiver = gvk.api_version();
+        let path = self.resources_path(&apiver);
+        let cached = self
+            .blocking({
+                let path = path.clone();
+                move |cache| cache.read::<APIResourceList>(&path)
+            })
+            .await
+            .flatten();
+        if let Some(list) = cached {
+            if let Some(found) = parse::find_kind(&list, &gvk.kind)? {
+                return Ok(found);
+            }
+            tracing::debug!(?gvk, "Kind missing from discovery cache, invalidating");
+            self.blocking(Self::invalidate_or_warn).await;
+        }
+        let list = if gvk.group.is_empty() {
+            client.list_core_api_resources(&apiver).await?
+        } else {
+            client.list_api_group_resources(&apiver).await?
+        };
+        let found = parse::find_kind(&list, &gvk.kind)?;
+        self.blocking(move |cache| cache.write_or_warn(&path, &list))
+            .await;
+        found.ok_or_else(|| Error::Discovery(DiscoveryError::MissingKind(format!("{gvk:?}"))))
+    }
+
+    /// Load all groups if every cached file is fresh
+    pub(crate) async fn load(&self) -> Option<HashMap<String, ApiGroup>> {
+        self.blocking(Self::load_groups).await.flatten()
+    }
+
+    fn load_groups(&self) -> Option<HashMap<String, ApiGroup>> {
+        let list = self.read::<APIGroupList>(&self.dir.join(SERVER_GROUPS))?;
+        let mut groups = HashMap::new();
+        for g in list.groups {
+            let mut lists = vec![];
+            for vers in &g.versions {
+                lists.push(self.read::<APIResourceList>(&self.resources_path(&vers.group_version))?);
+            }
+            let key = g.name.clone();
+            match ApiGroup::from_resource_lists(g, lists) {
+                Ok(group) => groups.insert(key, group),
+                Err(err) => {
+                    tracing::debug!(%err, "Ignoring invalid discovery cache");
+                    return None;
+                }
+            };
+        }
+        Some(groups)
+    }
+
+    /// Store all groups, overwriting previously cached files
+    pub(crate) async fn store(&self, groups: &HashMap<String, ApiGroup>) {
+        let mut list = APIGroupList { groups: vec![] };
+        let mut resource_lists = vec![];
+        let mut keys: Vec<_> = groups.keys().collect();
+        // core group first, as in the apiserver response
+        keys.sort();
+        for key in keys {
+            let (group, lists) = groups[key].to_resource_lists();
+            resource_lists.extend(lists);
+            list.groups.push(group);
+        }
+        self.blocking(move |cache| {
+            for resource_list in &resource_lists {
+                cache.write_or_warn(&cache.resources_path(&resource_list.group_version), resource_list);
+            }
+            cache.write_or_warn(&cache.dir.join(SERVER_GROUPS), &list);
+        })
+        .await;
+    }
+
+    /// Run blocking file operations on the cache without blocking the async runtime
+    async fn blocking<T, F>(&self, f: F) -> Option<T>
+    where
+        F: FnOnce(&Self) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let cache = self.clone();
+        tokio::task::spawn_blocking(move || f(&cache))
+            .await
+            .map_err(|err| tracing::warn!(%err, "Discovery cache task failed"))
+            .ok()
+    }
+
+    pub(crate) fn invalidate_or_warn(&self) {
+        if let Err(err) = self.invalidate() {
+            tracing::warn!(%err, dir = %self.dir.display(), "Failed to invalidate discovery cache");
+        }
+    }
+
+    fn resources_path(&self, group_version: &str) -> PathBuf {
+        self.dir.join(group_version).join(SERVER_RESOURCES)
+    }
+
+    /// Read a cached file if it exists and is fresh
+    fn read<T: DeserializeOwned>(&self, path: &Path) -> Option<T> {
+        let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+        let age = SystemTime::now().duration_since(modified).unwrap_or_default();
+        if age >= self.ttl {
+            return None;
+        }
+        let data = fs::read(path).ok()?;
+        serde_json::from_slice(&data)
+            .map_err(
+                |err| tracing::debug!(%err, path = %path.display(), "Ignoring invalid discovery cache file"),
+            )
+            .ok()
+    }
+
+    fn write_or_warn<T: Serialize>(&self, path: &Path, value: &T) {
+        if let Err(err) = write_atomic(path, value) {
+            tracing::warn!(%err, path = %path.display(), "Failed to write discovery cache");
+        }
+    }
+}
+
+// Write to a temporary file first, so that concurrent readers never see partial files
+fn write_atomic<T: Serialize>(path: &Path, value: &T) -> io::Result<()> {
+    let data = serde_json::to_vec(value)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)
+}
+
+/// Directory name of a cluster in the cache, computed like kubectl does
+///
+/// The scheme is stripped, and every character except alphanumerics, `_`, `/`, `.`, `(` and `)` is replaced with `_`.
+fn cache_dir_name(cluster_url: &Uri) -> String {
+    let url = cluster_url.to_string();
+    let host = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(&url)
+        .trim_end_matches('/');
+    host.chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '_' | '/' | '.' | '(' | ')' => c,
+            _ => '_',
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kube_core::discovery::Scope;
+
+    #[test]
+    fn cache_dir_matches_kubectl() {
+        let url = "https://127.0.0.1:6443".parse().unwrap();
+        assert_eq!(cache_dir_name(&url), "127.0.0.1_6443");
+        let url = "https://rancher.example.com/k8s/clusters/c-m-1".parse().unwrap();
+        assert_eq!(cache_dir_name(&url), "rancher.example.com/k8s/clusters/c_m_1");
+    }
+
+    #[tokio::test]
+    async fn groups_roundtrip_through_cache() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = DiscoveryCache::new(dir.path());
+        assert!(cache.load().await.is_none());
+
+        let list: APIResourceList = serde_json::from_value(serde_json::json!({
+            "groupVersion": "apps/v1",
+            "resources": [
+                { "name": "deployments", "singularName": "deployment", "namespaced": true, "kind": "Deployment", "verbs": ["get", "list"] },
+                { "name": "deployments/scale", "singularName": "", "namespaced": true, "group": "autoscaling", "version": "v1", "kind": "Scale", "verbs": ["get"] }
+            ]
+        }))
+        .unwrap();
+        let apigroup = serde_json::from_value(serde_json::json!({
+            "name": "apps",
+            "versions": [{ "groupVersion": "apps/v1", "version": "v1" }],
+            "preferredVersion": { "groupVersion": "apps/v1", "version": "v1" }
+        }))
+        .unwrap();
+        let group = ApiGroup::from_resource_lists(apigroup, vec![list]).unwrap();
+        cache.store(&HashMap::from([("apps".to_string(), group)])).await;
+        assert!(dir.path().join("apps/v1/serverresources.json").exists());
+
+        let groups = cache.load().await.unwrap();
+        let (ar, caps) = groups["apps"].recommended_kind("Deployment").unwrap();
+        assert_eq!(ar.api_version, "apps/v1");
+        assert_eq!(caps.scope, Scope::Namespaced);
+        assert_eq!(caps.subresources[0].0.plural, "scale");
+        assert_eq!(caps.subresources[0].0.group, "autoscaling");
+
+        // expired files are ignored
+        assert!(cache.clone().ttl(Duration::ZERO).load().await.is_none());
+        cache.invalidate().unwrap();
+        assert!(cache.load().await.is_none());
+    }
+}
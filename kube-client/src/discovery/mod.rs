@@ -1,12 +1,20 @@
 //! High-level utilities for runtime API discovery.
 
-use crate::{Client, Result};
+use crate::{Client, Error, Result};
+use http::{header::ACCEPT, Request};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIGroupList, APIVersions};
 pub use kube_core::discovery::{verbs, ApiCapabilities, ApiResource, Scope};
-use kube_core::gvk::GroupVersionKind;
+use kube_core::{
+    discovery::v2::{APIGroupDiscoveryList, ACCEPT_AGGREGATED_DISCOVERY, APIGROUP_DISCOVERY_LIST_KIND},
+    gvk::GroupVersionKind,
+};
+use serde::de::DeserializeOwned;
 use std::collections::HashMap;
 mod apigroup;
+mod cache;
 pub mod oneshot;
 pub use apigroup::ApiGroup;
+pub use cache::DiscoveryCache;
 mod parse;
 
 // re-export one-shots
@@ -21,6 +29,10 @@ enum DiscoveryMode {
 }
 
 impl DiscoveryMode {
+    fn is_unfiltered(&self) -> bool {
+        matches!(self, Self::Block(blocked) if blocked.is_empty())
+    }
+
     fn is_queryable(&self, group: &String) -> bool {
         match &self {
             Self::Allow(allowed) => allowed.contains(group),
@@ -29,6 +41,35 @@ impl DiscoveryMode {
     }
 }
 
+/// Response to a discovery request, depending on whether the apiserver supports aggregated discovery
+enum DiscoveryResponse<T> {
+    /// All groups, versions and resources in one document
+    Aggregated(APIGroupDiscoveryList),
+    /// The legacy document, requiring one more request per group version
+    Legacy(T),
+}
+
+impl<T: DeserializeOwned> DiscoveryResponse<T> {
+    /// Request the discovery document at `/api` or `/apis`, preferring aggregated discovery
+    async fn fetch(client: &Client, path: &str) -> Result<Self> {
+        let req = Request::builder()
+            .uri(path)
+            .header(ACCEPT, ACCEPT_AGGREGATED_DISCOVERY)
+            .body(vec![])
+            .map_err(Error::HttpError)?;
+        let value: serde_json::Value = client.request(req).await?;
+        if value["kind"] == APIGROUP_DISCOVERY_LIST_KIND {
+            serde_json::from_value(value)
+                .map(Self::Aggregated)
+                .map_err(Error::SerdeError)
+        } else {
+            serde_json::from_value(value)
+                .map(Self::Legacy)
+                .map_err(Error::SerdeError)
+        }
+    }
+}
+
 /// A caching client for running API discovery against the Kubernetes API.
 ///
 /// This simplifies the required querying and type matching, and stores the responses
@@ -53,6 +94,7 @@ pub struct Discovery {
     client: Client,
     groups: HashMap<String, ApiGroup>,
     mode: DiscoveryMode,
+    cache: Option<DiscoveryCache>,
 }
 
 /// Caching discovery interface
@@ -64,7 +106,12 @@ impl Discovery {
     pub fn new(client: Client) -> Self {
         let groups = HashMap::new();
         let mode = DiscoveryMode::Block(vec![]);
-        Self { client, groups, mode }
+        Self {
+            client,
+            groups,
+            mode,
+            cache: None,
+        }
     }
 
     /// Configure the discovery client to only look for the listed apigroups
@@ -81,10 +128,24 @@ impl Discovery {
         self
     }
 
+    /// Configure the discovery client to use an on-disk cache
+    ///
+    /// When all cached files are fresh, [`Discovery::run`] reads them instead of querying the apiserver.
+    /// Otherwise the results of an unfiltered discovery are written back to the cache.
+    /// The cache is invalidated when [`Discovery::resolve_gvk`] does not find a kind.
+    #[must_use]
+    pub fn cache(mut self, cache: DiscoveryCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
     /// Runs or re-runs the configured discovery algorithm and updates/populates the cache
     ///
-    /// The cache is empty cleared when this is started. By default, every api group found is checked,
-    /// causing `N+2` queries to the api server (where `N` is number of api groups).
+    /// The cache is empty cleared when this is started. By default, every api group found is checked.
+    ///
+    /// Apiservers supporting aggregated discovery (`apidiscovery.k8s.io`) return every group in 2 queries.
+    /// Otherwise this falls back to querying each group version,
+    /// causing `N+2` queries to the api server (where `N` is number of api group versions).
     ///
     /// ```no_run
     /// use kube::{Client, api::{Api, DynamicObject}, discovery::{Discovery, verbs, Scope}, ResourceExt};
@@ -110,24 +171,61 @@ impl Discovery {
     /// See a bigger example in [examples/dynamic.api](https://github.com/kube-rs/kube/blob/main/examples/dynamic_api.rs)
     pub async fn run(mut self) -> Result<Self> {
         self.groups.clear();
-        let api_groups = self.client.list_api_groups().await?;
+        let cached = match &self.cache {
+            Some(cache) => cache.load().await,
+            None => None,
+        };
+        if let Some(groups) = cached {
+            tracing::debug!("Using cached discovery");
+            self.groups = groups
+                .into_iter()
+                .filter(|(key, _)| self.mode.is_queryable(key))
+                .collect();
+            return Ok(self);
+        }
         // query regular groups + crds under /apis
-        for g in api_groups.groups {
-            let key = g.name.clone();
-            if self.mode.is_queryable(&key) {
-                let apigroup = ApiGroup::query_apis(&self.client, g).await?;
-                self.groups.insert(key, apigroup);
+        match DiscoveryResponse::<APIGroupList>::fetch(&self.client, "/apis").await? {
+            DiscoveryResponse::Aggregated(list) => self.insert_aggregated(list),
+            DiscoveryResponse::Legacy(api_groups) => {
+                for g in api_groups.groups {
+                    let key = g.name.clone();
+                    if self.mode.is_queryable(&key) {
+                        let apigroup = ApiGroup::query_apis(&self.client, g).await?;
+                        self.groups.insert(key, apigroup);
+                    }
+                }
             }
         }
         // query core versions under /api
         let corekey = ApiGroup::CORE_GROUP.to_string();
         if self.mode.is_queryable(&corekey) {
-            let coreapis = self.client.list_core_api_versions().await?;
-            let apigroup = ApiGroup::query_core(&self.client, coreapis).await?;
-            self.groups.insert(corekey, apigroup);
+            match DiscoveryResponse::<APIVersions>::fetch(&self.client, "/api").await? {
+                DiscoveryResponse::Aggregated(list) => self.insert_aggregated(list),
+                DiscoveryResponse::Legacy(coreapis) => {
+                    let apigroup = ApiGroup::query_core(&self.client, coreapis).await?;
+                    self.groups.insert(corekey, apigroup);
+                }
+            }
+        }
+        if let Some(cache) = &self.cache {
+            // a filtered discovery would hide groups from later unfiltered runs
+            if self.mode.is_unfiltered() {
+                cache.store(&self.groups).await;
+            }
         }
         Ok(self)
     }
+
+    fn insert_aggregated(&mut self, list: APIGroupDiscoveryList) {
+        for g in list.items {
+            let key = g.metadata.name.clone().unwrap_or_default();
+            if self.mode.is_queryable(&key) {
+                if let Some(apigroup) = ApiGroup::from_aggregated(g) {
+                    self.groups.insert(key, apigroup);
+                }
+            }
+        }
+    }
 }
 
 /// Interface to the Discovery cache
@@ -161,10 +259,21 @@ impl Discovery {
     ///
     /// This is for quick extraction after having done a complete discovery.
     /// If you are only interested in a single kind, consider [`oneshot::pinned_kind`](crate::discovery::pinned_kind).
+    ///
+    /// If a [cache](Discovery::cache) is used and the kind is not found, the cache is invalidated
+    /// so that the next [`Discovery::run`] picks up newly installed kinds.
     pub fn resolve_gvk(&self, gvk: &GroupVersionKind) -> Option<(ApiResource, ApiCapabilities)> {
-        self.get(&gvk.group)?
-            .versioned_resources(&gvk.version)
-            .into_iter()
-            .find(|res| res.0.kind == gvk.kind)
+        let found = self.get(&gvk.group).and_then(|g| {
+            g.versioned_resources(&gvk.version)
+                .into_iter()
+                .find(|res| res.0.kind == gvk.kind)
+        });
+        if found.is_none() {
+            if let Some(cache) = &self.cache {
+                tracing::debug!(?gvk, "Kind missing from discovery cache, invalidating");
+                cache.invalidate_or_warn();
+            }
+        }
+        found
     }
 }
@@ -2,8 +2,11 @@
 use crate::{error::DiscoveryError, Error, Result};
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIResource, APIResourceList};
 use kube_core::{
-    discovery::{ApiCapabilities, ApiResource, Scope},
-    gvk::{GroupVersion, ParseGroupVersionError},
+    discovery::{
+        v2::{APIResourceDiscovery, APIVersionDiscovery},
+        ApiCapabilities, ApiResource, Scope,
+    },
+    gvk::{GroupVersion, GroupVersionKind, ParseGroupVersionError},
 };
 
 /// Creates an `ApiResource` from a `meta::v1::APIResource` instance + its groupversion.
@@ -59,6 +62,25 @@ pub(crate) fn parse_apicapabilities(list: &APIResourceList, name: &str) -> Resul
     })
 }
 
+/// Finds a kind in a `meta::v1::APIResourceList` and extracts its `ApiResource` and `ApiCapabilities`.
+///
+/// Returns `None` if the list does not contain the kind.
+pub(crate) fn find_kind(
+    list: &APIResourceList,
+    kind: &str,
+) -> Result<Option<(ApiResource, ApiCapabilities)>> {
+    for res in &list.resources {
+        if res.kind == kind && !res.name.contains('/') {
+            let ar = parse_apiresource(res, &list.group_version).map_err(|ParseGroupVersionError(s)| {
+                Error::Discovery(DiscoveryError::InvalidGroupVersion(s))
+            })?;
+            let caps = parse_apicapabilities(list, &res.name)?;
+            return Ok(Some((ar, caps)));
+        }
+    }
+    Ok(None)
+}
+
 /// Internal resource information and capabilities for a particular ApiGroup at a particular version
 pub(crate) struct GroupVersionData {
     /// Pinned api version
@@ -85,4 +107,149 @@ impl GroupVersionData {
         }
         Ok(GroupVersionData { version, resources })
     }
+
+    /// Convert back to an APIResourceList for the given group, inverse of [`GroupVersionData::new`]
+    pub(crate) fn to_resource_list(&self, group: &str) -> APIResourceList {
+        let to_apiresource = |name: String, ar: &ApiResource, caps: &ApiCapabilities| APIResource {
+            name,
+            kind: ar.kind.clone(),
+            group: Some(ar.group.clone()),
+            version: Some(ar.version.clone()),
+            namespaced: caps.scope == Scope::Namespaced,
+            verbs: caps.operations.clone(),
+            ..APIResource::default()
+        };
+        let mut resources = vec![];
+        for (ar, caps) in &self.resources {
+            resources.push(to_apiresource(ar.plural.clone(), ar, caps));
+            for (sub, subcaps) in &caps.subresources {
+                let name = format!("{}/{}", ar.plural, sub.plural);
+                resources.push(to_apiresource(name, sub, subcaps));
+            }
+        }
+        APIResourceList {
+            group_version: GroupVersion::gv(group, &self.version).api_version(),
+            resources,
+        }
+    }
+}
+
+/// Creates an `ApiResource` from the kind an aggregated discovery resource responds with.
+///
+/// Group and version of the kind default to those of the listed group version.
+fn parse_aggregated_apiresource(
+    kind: Option<&GroupVersionKind>,
+    gv: &GroupVersion,
+    plural: &str,
+) -> ApiResource {
+    let non_empty =
+        |s: Option<&String>, default: &String| s.filter(|s| !s.is_empty()).unwrap_or(default).clone();
+    ApiResource {
+        group: non_empty(kind.map(|k| &k.group), &gv.group),
+        version: non_empty(kind.map(|k| &k.version), &gv.version),
+        api_version: gv.api_version(),
+        kind: kind.map(|k| k.kind.clone()).unwrap_or_default(),
+        plural: plural.to_string(),
+    }
+}
+
+/// Creates an `ApiResource` and its `ApiCapabilities` from an aggregated discovery resource.
+pub(crate) fn parse_aggregated_resource(
+    res: &APIResourceDiscovery,
+    gv: &GroupVersion,
+) -> (ApiResource, ApiCapabilities) {
+    let scope = if res.scope == "Namespaced" {
+        Scope::Namespaced
+    } else {
+        Scope::Cluster
+    };
+    let subresources = res
+        .subresources
+        .iter()
+        .map(|sub| {
+            let ar = parse_aggregated_apiresource(sub.response_kind.as_ref(), gv, &sub.subresource);
+            let caps = ApiCapabilities {
+                scope: scope.clone(),
+                subresources: vec![],
+                operations: sub.verbs.clone(),
+            };
+            (ar, caps)
+        })
+        .collect();
+    let ar = parse_aggregated_apiresource(res.response_kind.as_ref(), gv, &res.resource);
+    let caps = ApiCapabilities {
+        scope,
+        subresources,
+        operations: res.verbs.clone(),
+    };
+    (ar, caps)
+}
+
+impl GroupVersionData {
+    /// Given an aggregated discovery version of a group, extract all information for that version
+    pub(crate) fn from_aggregated(group: &str, ver: APIVersionDiscovery) -> Self {
+        let gv = GroupVersion::gv(group, &ver.version);
+        let resources = ver
+            .resources
+            .iter()
+            .map(|res| parse_aggregated_resource(res, &gv))
+            .collect();
+        GroupVersionData {
+            version: ver.version,
+            resources,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use kube_core::discovery::v2::APIGroupDiscoveryList;
+
+    #[test]
+    fn aggregated_resources_match_legacy_discovery() {
+        let list: APIGroupDiscoveryList = serde_json::from_value(serde_json::json!({
+            "kind": "APIGroupDiscoveryList",
+            "apiVersion": "apidiscovery.k8s.io/v2",
+            "metadata": {},
+            "items": [{
+                "metadata": { "name": "apps" },
+                "versions": [{
+                    "version": "v1",
+                    "resources": [{
+                        "resource": "deployments",
+                        "responseKind": { "group": "", "version": "", "kind": "Deployment" },
+                        "scope": "Namespaced",
+                        "singularResource": "deployment",
+                        "verbs": ["get", "list"],
+                        "subresources": [{
+                            "subresource": "scale",
+                            "responseKind": { "group": "autoscaling", "version": "v1", "kind": "Scale" },
+                            "verbs": ["get", "patch"]
+                        }]
+                    }]
+                }]
+            }]
+        }))
+        .unwrap();
+        let ver = list.items[0].versions[0].clone();
+        let gvd = GroupVersionData::from_aggregated("apps", ver);
+        assert_eq!(gvd.version, "v1");
+        let (ar, caps) = &gvd.resources[0];
+        assert_eq!(ar, &ApiResource {
+            group: "apps".into(),
+            version: "v1".into(),
+            api_version: "apps/v1".into(),
+            kind: "Deployment".into(),
+            plural: "deployments".into(),
+        });
+        assert_eq!(caps.scope, Scope::Namespaced);
+        assert_eq!(caps.operations, ["get", "list"]);
+        let (sub, subcaps) = &caps.subresources[0];
+        assert_eq!(sub.plural, "scale");
+        assert_eq!(sub.kind, "Scale");
+        assert_eq!(sub.group, "autoscaling");
+        assert_eq!(sub.api_version, "apps/v1");
+        assert_eq!(subcaps.operations, ["get", "patch"]);
+    }
 }
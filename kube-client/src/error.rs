@@ -63,6 +63,12 @@ pub enum Error {
     #[error("Error deserializing response: {0}")]
     SerdeError(#[source] serde_json::Error),
 
+    /// Failed to decode a protobuf response
+    #[cfg(feature = "protobuf")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "protobuf")))]
+    #[error("Error decoding protobuf response: {0}")]
+    ProtobufDecode(#[source] kube_core::protobuf::DecodeError),
+
     /// Failed to build request
     #[error("Failed to build request: {0}")]
     BuildRequest(#[source] kube_core::request::Error),
@@ -71,6 +77,10 @@ pub enum Error {
     #[error("Failed to infer configuration: {0}")]
     InferConfig(#[source] crate::config::InferConfigError),
 
+    /// Failed to load a kubeconfig
+    #[error("Failed to load kubeconfig: {0}")]
+    Kubeconfig(#[source] crate::config::KubeconfigError),
+
     /// Discovery errors
     #[error("Error from discovery: {0}")]
     Discovery(#[source] DiscoveryError),
@@ -97,6 +107,30 @@ pub enum Error {
     #[error("failed to upgrade to a WebSocket connection: {0}")]
     UpgradeConnection(#[source] crate::client::UpgradeConnectionError),
 
+    /// Failed to copy files to or from a pod
+    #[cfg(feature = "ws")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+    #[error("failed to copy files: {0}")]
+    Copy(#[source] crate::api::CopyError),
+
+    /// Failed to collect the output of a command
+    #[cfg(feature = "ws")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+    #[error("failed to run command: {0}")]
+    Exec(#[source] crate::api::ExecError),
+
+    /// Failed to set up a port-forward session
+    #[cfg(feature = "ws")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+    #[error("failed to set up port forwarding: {0}")]
+    PortForwardSession(#[source] crate::api::PortForwardSessionError),
+
+    /// A warning from the apiserver, failed by [`StrictWarnings`](crate::client::StrictWarnings)
+    #[cfg(feature = "client")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "client")))]
+    #[error("apiserver warning: {0}")]
+    Warning(crate::client::Warning),
+
     /// Errors related to client auth
     #[cfg(feature = "client")]
     #[cfg_attr(docsrs, doc(cfg(feature = "client")))]
@@ -0,0 +1,109 @@
+//! Generates the protobuf messages of kube-core, and the table of their JSON field names
+//!
+//! Run from the root of the repository with `just protobuf`.
+use std::{fmt::Write, fs, path::Path};
+
+use heck::{ToSnakeCase, ToUpperCamelCase};
+use prost_types::{field_descriptor_proto::Type, DescriptorProto, FieldDescriptorProto};
+
+const PROTOS: &str = "kube-core/protos";
+const OUT_DIR: &str = "kube-core/src/protobuf/generated";
+
+const FILES: &[&str] = &[
+    "k8s.io/apimachinery/pkg/api/resource/generated.proto",
+    "k8s.io/apimachinery/pkg/apis/meta/v1/generated.proto",
+    "k8s.io/apimachinery/pkg/runtime/generated.proto",
+    "k8s.io/apimachinery/pkg/util/intstr/generated.proto",
+    "k8s.io/api/core/v1/generated.proto",
+    "k8s.io/api/discovery/v1/generated.proto",
+];
+
+/// Messages that do not deserialize as a JSON object, these are implemented by hand
+const CUSTOM: &[&str] = &[
+    ".k8s.io.apimachinery.pkg.api.resource.Quantity",
+    ".k8s.io.apimachinery.pkg.apis.meta.v1.FieldsV1",
+    ".k8s.io.apimachinery.pkg.apis.meta.v1.Time",
+    ".k8s.io.apimachinery.pkg.apis.meta.v1.WatchEvent",
+    ".k8s.io.apimachinery.pkg.runtime.RawExtension",
+    ".k8s.io.apimachinery.pkg.runtime.TypeMeta",
+    ".k8s.io.apimachinery.pkg.runtime.Unknown",
+    ".k8s.io.apimachinery.pkg.util.intstr.IntOrString",
+];
+
+/// Fields that are embedded in Go, their fields are part of the JSON object of the outer message
+const INLINE: &[&str] = &[
+    "ephemeralContainerCommon",
+    "handler",
+    "localObjectReference",
+    "volumeSource",
+];
+
+/// Optional strings that are set when empty in JSON, other empty strings are left out like in JSON
+const PRESENT: &[&str] = &[".k8s.io.api.core.v1.PersistentVolumeClaimSpec.storageClassName"];
+
+fn main() {
+    let protoc = protoc_bin_vendored::protoc_bin_path().unwrap();
+    let mut config = prost_build::Config::new();
+    config
+        .protoc_executable(protoc)
+        .btree_map(["."])
+        .include_file("mod.rs")
+        .out_dir(OUT_DIR);
+    let fds = config.load_fds(FILES, &[PROTOS]).unwrap();
+    config.compile_fds(fds.clone()).unwrap();
+
+    let mut table =
+        String::from("// This file is @generated by kube-core/protos/generate.rs.\nmessages! {\n");
+    for file in &fds.file {
+        let package = file.package();
+        for message in &file.message_type {
+            let name = format!(".{package}.{}", message.name());
+            if !CUSTOM.contains(&name.as_str()) {
+                write_message(&mut table, package, &name, message);
+            }
+        }
+    }
+    table.push_str("}\n");
+    fs::write(Path::new(OUT_DIR).join("json.rs"), table).unwrap();
+}
+
+fn write_message(table: &mut String, package: &str, name: &str, message: &DescriptorProto) {
+    writeln!(table, "    {} {{", rust_path(package, message.name())).unwrap();
+    for field in &message.field {
+        let json = field.name();
+        let spec = if INLINE.contains(&json) {
+            format!("(inline {})", rust_type(field))
+        } else if PRESENT.contains(&format!("{name}.{json}").as_str()) {
+            format!("(present {json:?})")
+        } else {
+            format!("{json:?}")
+        };
+        writeln!(table, "        {}: {spec},", rust_ident(json)).unwrap();
+    }
+    table.push_str("    }\n");
+}
+
+/// The path of a message in the module tree of the `mod.rs` include file
+fn rust_path(package: &str, message: &str) -> String {
+    let modules = package.split('.').map(rust_ident).collect::<Vec<_>>();
+    format!("{}::{}", modules.join("::"), message.to_upper_camel_case())
+}
+
+fn rust_type(field: &FieldDescriptorProto) -> String {
+    assert_eq!(field.r#type(), Type::Message, "only messages can be inlined");
+    let (package, message) = field
+        .type_name()
+        .trim_start_matches('.')
+        .rsplit_once('.')
+        .unwrap();
+    rust_path(package, message)
+}
+
+/// The identifier of a field or module, as named by prost-build
+fn rust_ident(name: &str) -> String {
+    let ident = name.to_snake_case();
+    match ident.as_str() {
+        "continue" | "type" => format!("r#{ident}"),
+        _ => ident,
+    }
+}
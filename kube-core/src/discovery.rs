@@ -2,6 +2,8 @@
 use crate::{gvk::GroupVersionKind, resource::Resource};
 use serde::{Deserialize, Serialize};
 
+pub mod v2;
+
 /// Information about a Kubernetes API resource
 ///
 /// Enough information to use it like a `Resource` by passing it to the dynamic `Api`
@@ -0,0 +1,164 @@
+//! Types for aggregated discovery from `apidiscovery.k8s.io/v2`
+//!
+//! Aggregated discovery returns every group, version and resource served under
+//! `/api` or `/apis` in a single response when requested with the [`ACCEPT_AGGREGATED_DISCOVERY`] media type.
+//! Apiservers that do not support it return the legacy `APIVersions` or `APIGroupList` instead.
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    gvk::GroupVersionKind,
+    metadata::{ListMeta, ObjectMeta},
+};
+
+/// Accept header requesting aggregated discovery, with a fallback to the legacy discovery documents
+///
+/// `v2beta1` is served by Kubernetes 1.26 to 1.29, and has the same shape as `v2`.
+pub const ACCEPT_AGGREGATED_DISCOVERY: &str = "application/json;g=apidiscovery.k8s.io;v=v2;as=APIGroupDiscoveryList,application/json;g=apidiscovery.k8s.io;v=v2beta1;as=APIGroupDiscoveryList,application/json";
+
+/// Kind of the aggregated discovery document
+pub const APIGROUP_DISCOVERY_LIST_KIND: &str = "APIGroupDiscoveryList";
+
+/// Freshness of a version whose resources could not be fetched from an aggregated apiserver
+pub const FRESHNESS_STALE: &str = "Stale";
+
+/// All groups served under either `/api` or `/apis`
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct APIGroupDiscoveryList {
+    /// Standard list metadata
+    #[serde(default)]
+    pub metadata: ListMeta,
+
+    /// The served groups
+    #[serde(default)]
+    pub items: Vec<APIGroupDiscovery>,
+}
+
+/// A group and its served versions
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct APIGroupDiscovery {
+    /// Metadata of the group, where the name is the group name, empty for the core group
+    #[serde(default)]
+    pub metadata: ObjectMeta,
+
+    /// Versions of the group, ordered by preference with the preferred version first
+    #[serde(default)]
+    pub versions: Vec<APIVersionDiscovery>,
+}
+
+/// A served version of a group and its resources
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct APIVersionDiscovery {
+    /// Name of the version, e.g. `v1`
+    pub version: String,
+
+    /// Resources served in this version
+    #[serde(default)]
+    pub resources: Vec<APIResourceDiscovery>,
+
+    /// Whether the resources are `Current` or `Stale`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub freshness: Option<String>,
+}
+
+/// A resource served in a group version
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct APIResourceDiscovery {
+    /// Plural name of the resource
+    pub resource: String,
+
+    /// Kind returned when getting the resource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_kind: Option<GroupVersionKind>,
+
+    /// Either `Cluster` or `Namespaced`
+    pub scope: String,
+
+    /// Singular name of the resource
+    #[serde(default)]
+    pub singular_resource: String,
+
+    /// Supported verbs
+    #[serde(default)]
+    pub verbs: Vec<String>,
+
+    /// Short names of the resource
+    #[serde(default)]
+    pub short_names: Vec<String>,
+
+    /// Categories the resource belongs to, e.g. `all`
+    #[serde(default)]
+    pub categories: Vec<String>,
+
+    /// Subresources of the resource
+    #[serde(default)]
+    pub subresources: Vec<APISubresourceDiscovery>,
+}
+
+/// A subresource of a resource
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct APISubresourceDiscovery {
+    /// Name of the subresource, e.g. `status`
+    pub subresource: String,
+
+    /// Kind returned when getting the subresource
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub response_kind: Option<GroupVersionKind>,
+
+    /// Kinds accepted when writing to the subresource
+    #[serde(default)]
+    pub accepted_types: Vec<GroupVersionKind>,
+
+    /// Supported verbs
+    #[serde(default)]
+    pub verbs: Vec<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::APIGroupDiscoveryList;
+
+    #[test]
+    fn deserializes_aggregated_discovery() {
+        let list: APIGroupDiscoveryList = serde_json::from_value(serde_json::json!({
+            "kind": "APIGroupDiscoveryList",
+            "apiVersion": "apidiscovery.k8s.io/v2",
+            "metadata": {},
+            "items": [{
+                "metadata": { "name": "apps", "creationTimestamp": null },
+                "versions": [{
+                    "version": "v1",
+                    "resources": [{
+                        "resource": "deployments",
+                        "responseKind": { "group": "", "version": "", "kind": "Deployment" },
+                        "scope": "Namespaced",
+                        "singularResource": "deployment",
+                        "verbs": ["create", "get", "list"],
+                        "shortNames": ["deploy"],
+                        "categories": ["all"],
+                        "subresources": [{
+                            "subresource": "scale",
+                            "responseKind": { "group": "autoscaling", "version": "v1", "kind": "Scale" },
+                            "verbs": ["get", "patch", "update"]
+                        }]
+                    }],
+                    "freshness": "Current"
+                }]
+            }]
+        }))
+        .unwrap();
+        let group = &list.items[0];
+        assert_eq!(group.metadata.name.as_deref(), Some("apps"));
+        let deploy = &group.versions[0].resources[0];
+        assert_eq!(deploy.response_kind.as_ref().unwrap().kind, "Deployment");
+        assert_eq!(deploy.short_names, ["deploy"]);
+        assert_eq!(
+            deploy.subresources[0].response_kind.as_ref().unwrap().group,
+            "autoscaling"
+        );
+    }
+}
@@ -0,0 +1,652 @@
+//! Type safe field selector logic
+use crate::{labels::ParseExpressionError, request::Error, DynamicObject, Resource};
+use k8s_openapi::{
+    api::{
+        admissionregistration::v1::{MutatingWebhookConfiguration, ValidatingWebhookConfiguration},
+        apps::v1::{ControllerRevision, DaemonSet, Deployment, ReplicaSet, StatefulSet},
+        autoscaling::v2::HorizontalPodAutoscaler,
+        batch::v1::{CronJob, Job},
+        certificates::v1::CertificateSigningRequest,
+        coordination::v1::Lease,
+        core::v1::{
+            ConfigMap, Endpoints, Event, LimitRange, Namespace, Node, PersistentVolume,
+            PersistentVolumeClaim, Pod, PodTemplate, ReplicationController, ResourceQuota, Secret, Service,
+            ServiceAccount,
+        },
+        discovery::v1::EndpointSlice,
+        networking::v1::{Ingress, IngressClass, NetworkPolicy},
+        policy::v1::PodDisruptionBudget,
+        rbac::v1::{ClusterRole, ClusterRoleBinding, Role, RoleBinding},
+        scheduling::v1::PriorityClass,
+        storage::v1::{StorageClass, VolumeAttachment},
+    },
+    apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Display},
+    iter::FromIterator,
+    str::FromStr,
+};
+
+/// A field selector expression
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub enum FieldExpression {
+    /// Field is equal:
+    ///
+    /// ```
+    /// # use kube_core::FieldExpression;
+    /// let exp = FieldExpression::Equal("status.phase".into(), "Running".into());
+    /// assert_eq!(exp.to_string(), "status.phase=Running")
+    /// ```
+    Equal(String, String),
+
+    /// Field is not equal:
+    ///
+    /// ```
+    /// # use kube_core::FieldExpression;
+    /// let exp = FieldExpression::NotEqual("metadata.namespace".into(), "default".into());
+    /// assert_eq!(exp.to_string(), "metadata.namespace!=default")
+    /// ```
+    NotEqual(String, String),
+}
+
+/// Perform selection on a list of field expressions
+///
+/// Can be injected into [`WatchParams`](crate::params::WatchParams::fields_from) or [`ListParams`](crate::params::ListParams::fields_from),
+/// and evaluated against objects on the client with [`FieldSelector::matches`].
+///
+/// ```
+/// use k8s_openapi::api::core::v1::Pod;
+/// use kube_core::FieldSelector;
+///
+/// let selector: FieldSelector = "status.phase=Running,spec.nodeName!=node-1".parse()?;
+/// assert_eq!(selector.to_string(), "status.phase=Running,spec.nodeName!=node-1");
+/// assert!(!selector.matches(&Pod::default()));
+/// # Ok::<(), kube_core::ParseExpressionError>(())
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq, Default, Deserialize, Serialize)]
+pub struct FieldSelector(Vec<FieldExpression>);
+
+impl FieldSelector {
+    /// Indicates whether this field selector matches everything
+    pub fn selects_all(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Extend the list of expressions for the selector
+    pub fn extend(&mut self, exprs: impl IntoIterator<Item = FieldExpression>) -> &mut Self {
+        self.0.extend(exprs);
+        self
+    }
+
+    /// Perform a match check on an object
+    ///
+    /// Fields are read with [`SelectableFields`]. Fields that are not selectable for the kind never match,
+    /// whereas the apiserver rejects the whole request.
+    pub fn matches<K: SelectableFields>(&self, obj: &K) -> bool {
+        self.0.iter().all(|expr| expr.matches(obj))
+    }
+
+    /// Check that every field in the selector is selectable for the kind
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube_core::FieldSelector;
+    ///
+    /// let selector: FieldSelector = "spec.nodeName=node-1".parse()?;
+    /// assert!(selector.validate::<Pod>().is_ok());
+    /// let selector: FieldSelector = "spec.containers=app".parse()?;
+    /// assert!(selector.validate::<Pod>().is_err());
+    /// # Ok::<(), kube_core::ParseExpressionError>(())
+    /// ```
+    pub fn validate<K: SelectableFields>(&self) -> Result<(), Error> {
+        for expr in &self.0 {
+            let (FieldExpression::Equal(field, _) | FieldExpression::NotEqual(field, _)) = expr;
+            if !K::is_selectable(field) {
+                let supported = ["metadata.name", "metadata.namespace"]
+                    .iter()
+                    .chain(K::FIELDS)
+                    .copied()
+                    .collect::<Vec<_>>();
+                return Err(Error::Validation(format!(
+                    "field selector on {field:?} is not supported, expected one of: {}",
+                    supported.join(", ")
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FieldExpression {
+    /// Perform a match check on an object
+    pub fn matches<K: SelectableFields>(&self, obj: &K) -> bool {
+        let (field, value, equal) = match self {
+            FieldExpression::Equal(field, value) => (field, value, true),
+            FieldExpression::NotEqual(field, value) => (field, value, false),
+        };
+        match field_value(obj, field) {
+            Some(actual) => (&actual == value) == equal,
+            None => false,
+        }
+    }
+}
+
+fn field_value<K: SelectableFields>(obj: &K, field: &str) -> Option<String> {
+    match field {
+        "metadata.name" => Some(obj.meta().name.clone().unwrap_or_default()),
+        "metadata.namespace" => Some(obj.meta().namespace.clone().unwrap_or_default()),
+        _ => obj.field(field),
+    }
+}
+
+/// Access to the fields of a kind that can be used in field selectors
+///
+/// `metadata.name` and `metadata.namespace` are selectable for every kind.
+/// Implementations list the other fields supported by the apiserver for the kind in [`FIELDS`](Self::FIELDS),
+/// and return their value from [`field`](Self::field), or `None` for fields that cannot be selected.
+/// Unset fields are selectable as the empty string.
+///
+/// Custom resources can implement this for the fields listed in `selectableFields` of their definition,
+/// by listing their paths and matching on the path to return the value of the field.
+pub trait SelectableFields: Resource {
+    /// The paths of the selectable fields besides `metadata.name` and `metadata.namespace`
+    const FIELDS: &'static [&'static str] = &[];
+
+    /// Get the value of a selectable field by its path, e.g. `status.phase`
+    fn field(&self, path: &str) -> Option<String> {
+        let _ = path;
+        None
+    }
+
+    /// Whether the apiserver accepts a field selector on the path for the kind
+    fn is_selectable(path: &str) -> bool {
+        matches!(path, "metadata.name" | "metadata.namespace") || Self::FIELDS.contains(&path)
+    }
+}
+
+macro_rules! metadata_fields {
+    ($($kind:ty),*) => {
+        $(impl SelectableFields for $kind {})*
+    };
+}
+
+metadata_fields!(
+    ClusterRole,
+    ClusterRoleBinding,
+    ConfigMap,
+    ControllerRevision,
+    CronJob,
+    CustomResourceDefinition,
+    DaemonSet,
+    Deployment,
+    EndpointSlice,
+    Endpoints,
+    HorizontalPodAutoscaler,
+    Ingress,
+    IngressClass,
+    Lease,
+    LimitRange,
+    MutatingWebhookConfiguration,
+    NetworkPolicy,
+    PersistentVolume,
+    PersistentVolumeClaim,
+    PodDisruptionBudget,
+    PodTemplate,
+    PriorityClass,
+    ResourceQuota,
+    Role,
+    RoleBinding,
+    ServiceAccount,
+    StatefulSet,
+    StorageClass,
+    ValidatingWebhookConfiguration,
+    VolumeAttachment
+);
+
+impl SelectableFields for Pod {
+    const FIELDS: &'static [&'static str] = &[
+        "spec.nodeName",
+        "spec.restartPolicy",
+        "spec.schedulerName",
+        "spec.serviceAccountName",
+        "spec.hostNetwork",
+        "status.phase",
+        "status.podIP",
+        "status.nominatedNodeName",
+    ];
+
+    fn field(&self, path: &str) -> Option<String> {
+        let spec = self.spec.as_ref();
+        let status = self.status.as_ref();
+        let value = match path {
+            "spec.nodeName" => spec.and_then(|s| s.node_name.clone()),
+            "spec.restartPolicy" => spec.and_then(|s| s.restart_policy.clone()),
+            "spec.schedulerName" => spec.and_then(|s| s.scheduler_name.clone()),
+            "spec.serviceAccountName" => spec.and_then(|s| s.service_account_name.clone()),
+            "spec.hostNetwork" => Some(spec.and_then(|s| s.host_network).unwrap_or_default().to_string()),
+            "status.phase" => status.and_then(|s| s.phase.clone()),
+            "status.podIP" => status.and_then(|s| s.pod_ip.clone()),
+            "status.nominatedNodeName" => status.and_then(|s| s.nominated_node_name.clone()),
+            _ => return None,
+        };
+        Some(value.unwrap_or_default())
+    }
+}
+
+/// `spec.clusterIP` and `spec.type` are selectable since Kubernetes 1.31
+impl SelectableFields for Service {
+    const FIELDS: &'static [&'static str] = &["spec.clusterIP", "spec.type"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        let spec = self.spec.as_ref();
+        let value = match path {
+            "spec.clusterIP" => spec.and_then(|s| s.cluster_ip.clone()),
+            "spec.type" => spec.and_then(|s| s.type_.clone()),
+            _ => return None,
+        };
+        Some(value.unwrap_or_default())
+    }
+}
+
+impl SelectableFields for Node {
+    const FIELDS: &'static [&'static str] = &["spec.unschedulable"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "spec.unschedulable" => Some(
+                self.spec
+                    .as_ref()
+                    .and_then(|s| s.unschedulable)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl SelectableFields for Namespace {
+    const FIELDS: &'static [&'static str] = &["status.phase"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "status.phase" => Some(
+                self.status
+                    .as_ref()
+                    .and_then(|s| s.phase.clone())
+                    .unwrap_or_default(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl SelectableFields for Secret {
+    const FIELDS: &'static [&'static str] = &["type"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "type" => Some(self.type_.clone().unwrap_or_default()),
+            _ => None,
+        }
+    }
+}
+
+impl SelectableFields for Event {
+    const FIELDS: &'static [&'static str] = &[
+        "involvedObject.kind",
+        "involvedObject.namespace",
+        "involvedObject.name",
+        "involvedObject.uid",
+        "involvedObject.apiVersion",
+        "involvedObject.resourceVersion",
+        "involvedObject.fieldPath",
+        "reason",
+        "reportingComponent",
+        "source",
+        "type",
+    ];
+
+    fn field(&self, path: &str) -> Option<String> {
+        let involved = &self.involved_object;
+        let value = match path {
+            "involvedObject.kind" => involved.kind.clone(),
+            "involvedObject.namespace" => involved.namespace.clone(),
+            "involvedObject.name" => involved.name.clone(),
+            "involvedObject.uid" => involved.uid.clone(),
+            "involvedObject.apiVersion" => involved.api_version.clone(),
+            "involvedObject.resourceVersion" => involved.resource_version.clone(),
+            "involvedObject.fieldPath" => involved.field_path.clone(),
+            "reason" => self.reason.clone(),
+            "reportingComponent" => self.reporting_component.clone(),
+            "source" => self.source.as_ref().and_then(|s| s.component.clone()),
+            "type" => self.type_.clone(),
+            _ => return None,
+        };
+        Some(value.unwrap_or_default())
+    }
+}
+
+impl SelectableFields for ReplicaSet {
+    const FIELDS: &'static [&'static str] = &["status.replicas"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "status.replicas" => Some(
+                self.status
+                    .as_ref()
+                    .map(|s| s.replicas)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl SelectableFields for ReplicationController {
+    const FIELDS: &'static [&'static str] = &["status.replicas"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "status.replicas" => Some(
+                self.status
+                    .as_ref()
+                    .map(|s| s.replicas)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl SelectableFields for Job {
+    const FIELDS: &'static [&'static str] = &["status.successful"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "status.successful" => Some(
+                self.status
+                    .as_ref()
+                    .and_then(|s| s.succeeded)
+                    .unwrap_or_default()
+                    .to_string(),
+            ),
+            _ => None,
+        }
+    }
+}
+
+impl SelectableFields for CertificateSigningRequest {
+    const FIELDS: &'static [&'static str] = &["spec.signerName"];
+
+    fn field(&self, path: &str) -> Option<String> {
+        match path {
+            "spec.signerName" => Some(self.spec.signer_name.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Fields of dynamic objects are looked up by path, since the selectable fields of the kind are unknown
+impl SelectableFields for DynamicObject {
+    fn is_selectable(_path: &str) -> bool {
+        true
+    }
+
+    fn field(&self, path: &str) -> Option<String> {
+        let value = path.split('.').try_fold(&self.data, |v, key| v.get(key));
+        match value {
+            None | Some(serde_json::Value::Null) => Some(String::new()),
+            Some(serde_json::Value::String(s)) => Some(s.clone()),
+            Some(v @ (serde_json::Value::Bool(_) | serde_json::Value::Number(_))) => Some(v.to_string()),
+            Some(_) => None,
+        }
+    }
+}
+
+/// Escape the characters with a meaning in field selectors, as `fields.EscapeValue` in apimachinery
+fn escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ',' | '=') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+fn unescape(value: &str) -> Result<String, ParseExpressionError> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c @ ('\\' | ',' | '=')) => unescaped.push(c),
+                _ => {
+                    return Err(ParseExpressionError(format!(
+                        "invalid escape sequence in field selector value {value:?}"
+                    )))
+                }
+            },
+            ',' | '=' => {
+                return Err(ParseExpressionError(format!(
+                    "unescaped {c:?} in field selector value {value:?}"
+                )))
+            }
+            c => unescaped.push(c),
+        }
+    }
+    Ok(unescaped)
+}
+
+/// Split a string on every unescaped `sep`, keeping the escapes
+fn split_unescaped(s: &str, sep: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == sep {
+            parts.push(&s[start..i]);
+            start = i + 1;
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+impl Display for FieldExpression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FieldExpression::Equal(field, value) => write!(f, "{field}={}", escape(value)),
+            FieldExpression::NotEqual(field, value) => write!(f, "{field}!={}", escape(value)),
+        }
+    }
+}
+
+impl Display for FieldSelector {
+    /// Convert a selector to a string for the API
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let selectors: Vec<String> = self.0.iter().map(|e| e.to_string()).collect();
+        write!(f, "{}", selectors.join(","))
+    }
+}
+
+impl FromStr for FieldExpression {
+    type Err = ParseExpressionError;
+
+    /// Parse a single term such as `status.phase=Running`, with `=`, `==` or `!=`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the first unescaped operator splits the field from the value
+        let mut escaped = false;
+        for (i, c) in s.char_indices() {
+            if escaped {
+                escaped = false;
+                continue;
+            }
+            let (op_len, equal) = match c {
+                '\\' => {
+                    escaped = true;
+                    continue;
+                }
+                '!' if s[i..].starts_with("!=") => (2, false),
+                '=' if s[i..].starts_with("==") => (2, true),
+                '=' => (1, true),
+                _ => continue,
+            };
+            let field = &s[..i];
+            if field.is_empty() {
+                return Err(ParseExpressionError(format!(
+                    "missing field in field selector {s:?}"
+                )));
+            }
+            let value = unescape(&s[i + op_len..])?;
+            return Ok(if equal {
+                FieldExpression::Equal(field.to_string(), value)
+            } else {
+                FieldExpression::NotEqual(field.to_string(), value)
+            });
+        }
+        Err(ParseExpressionError(format!(
+            "invalid field selector {s:?}: expected '=', '==' or '!='"
+        )))
+    }
+}
+
+impl FromStr for FieldSelector {
+    type Err = ParseExpressionError;
+
+    /// Parse a comma separated field selector, with the same syntax as the apiserver
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        split_unescaped(s, ',')
+            .into_iter()
+            .filter(|term| !term.is_empty())
+            .map(str::parse)
+            .collect()
+    }
+}
+
+// convenience conversions for FieldSelector and FieldExpression
+
+impl IntoIterator for FieldSelector {
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+    type Item = FieldExpression;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl FromIterator<FieldExpression> for FieldSelector {
+    fn from_iter<T: IntoIterator<Item = FieldExpression>>(iter: T) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl From<FieldExpression> for FieldSelector {
+    fn from(value: FieldExpression) -> Self {
+        Self(vec![value])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k8s_openapi::api::core::v1::{PodSpec, PodStatus};
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for input in [
+            "",
+            "metadata.name=foo",
+            "status.phase!=Running,spec.nodeName=",
+            r"metadata.name=a\,b\=c\\d",
+        ] {
+            let selector: FieldSelector = input.parse().unwrap();
+            assert_eq!(selector.to_string(), input);
+        }
+        let selector: FieldSelector = "metadata.name==foo,,type!=a".parse().unwrap();
+        assert_eq!(selector.to_string(), "metadata.name=foo,type!=a");
+        let selector: FieldSelector = r"metadata.name=a\,b".parse().unwrap();
+        assert_eq!(
+            selector,
+            FieldExpression::Equal("metadata.name".into(), "a,b".into()).into()
+        );
+
+        for input in ["foo", "=bar", "foo=a=b", r"foo=a\b", r"foo=a\", "foo<1"] {
+            assert!(input.parse::<FieldSelector>().is_err(), "{input}");
+        }
+    }
+
+    #[test]
+    fn test_matches() {
+        let pod = Pod {
+            metadata: crate::ObjectMeta {
+                name: Some("blog".into()),
+                namespace: Some("apps".into()),
+                ..Default::default()
+            },
+            spec: Some(PodSpec {
+                node_name: Some("node-1".into()),
+                ..Default::default()
+            }),
+            status: Some(PodStatus {
+                phase: Some("Running".into()),
+                ..Default::default()
+            }),
+        };
+        for (selector, matches) in [
+            ("", true),
+            ("metadata.name=blog,metadata.namespace=apps", true),
+            ("metadata.namespace!=apps", false),
+            ("status.phase=Running,spec.nodeName!=node-2", true),
+            ("spec.hostNetwork=false", true),
+            ("status.podIP=", true),
+            ("spec.containers=", false),
+        ] {
+            let selector: FieldSelector = selector.parse().unwrap();
+            assert_eq!(selector.matches(&pod), matches, "{selector}");
+        }
+
+        let obj: DynamicObject = serde_json::from_value(serde_json::json!({
+            "apiVersion": "clux.dev/v1",
+            "kind": "Foo",
+            "metadata": { "name": "foo" },
+            "spec": { "color": "blue", "replicas": 2 }
+        }))
+        .unwrap();
+        let selector: FieldSelector = "spec.color=blue,spec.replicas=2,spec.size=".parse().unwrap();
+        assert!(selector.matches(&obj));
+    }
+
+    #[test]
+    fn test_validate() {
+        use k8s_openapi::api::{batch::v1::CronJob, core::v1::Node};
+
+        let selector: FieldSelector = "metadata.name=blog,status.phase=Running".parse().unwrap();
+        assert!(selector.validate::<Pod>().is_ok());
+        assert!(selector.validate::<DynamicObject>().is_ok());
+        let err = selector.validate::<Node>().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "failed to validate request: field selector on \"status.phase\" is not supported, \
+             expected one of: metadata.name, metadata.namespace, spec.unschedulable"
+        );
+        assert!(selector.validate::<CronJob>().is_err());
+
+        let lp = crate::params::ListParams::default()
+            .fields_from::<Node>(&"spec.unschedulable=true".parse().unwrap());
+        assert_eq!(
+            lp.unwrap().field_selector.as_deref(),
+            Some("spec.unschedulable=true")
+        );
+    }
+}
@@ -6,8 +6,9 @@ use std::{
     cmp::PartialEq,
     collections::{BTreeMap, BTreeSet},
     fmt::Display,
-    iter::FromIterator,
+    iter::{FromIterator, Peekable},
     option::IntoIter,
+    str::{CharIndices, FromStr},
 };
 use thiserror::Error;
 
@@ -220,6 +221,284 @@ impl Display for Selector {
         write!(f, "{}", selectors.join(","))
     }
 }
+
+impl FromStr for Selector {
+    type Err = ParseExpressionError;
+
+    /// Parse a selector string, with the same syntax and validation as the apiserver
+    ///
+    /// ```
+    /// use kube_core::{Expression, Selector};
+    ///
+    /// let selector: Selector = "app in (a,b),!canary,tier=web".parse()?;
+    /// assert_eq!(selector.to_string(), "app in (a,b),!canary,tier=web");
+    /// # Ok::<(), kube_core::ParseExpressionError>(())
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let mut exprs = vec![];
+        if parser.peek() == Token::End {
+            return Ok(Self::default());
+        }
+        loop {
+            exprs.push(parser.expression()?);
+            match parser.next() {
+                Token::End => return Ok(Self(exprs)),
+                Token::Comma => {}
+                token => return Err(parser.unexpected(token, "',' or end of selector")),
+            }
+        }
+    }
+}
+
+impl FromStr for Expression {
+    type Err = ParseExpressionError;
+
+    /// Parse a single selector requirement, such as `tier=web` or `app in (a,b)`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s);
+        let expr = parser.expression()?;
+        match parser.next() {
+            Token::End => Ok(expr),
+            token => Err(parser.unexpected(token, "end of expression")),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token<'a> {
+    Identifier(&'a str),
+    Not,
+    Equals,
+    DoubleEquals,
+    NotEquals,
+    OpenParen,
+    CloseParen,
+    Comma,
+    In,
+    NotIn,
+    /// The `<` and `>` (`lt` and `gt`) operators, which apimachinery accepts in label selectors,
+    /// but are rejected here because an [`Expression`] cannot represent them
+    Invalid(&'a str),
+    End,
+}
+
+impl Display for Token<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Token::Identifier(s) | Token::Invalid(s) => write!(f, "'{s}'"),
+            Token::Not => f.write_str("'!'"),
+            Token::Equals => f.write_str("'='"),
+            Token::DoubleEquals => f.write_str("'=='"),
+            Token::NotEquals => f.write_str("'!='"),
+            Token::OpenParen => f.write_str("'('"),
+            Token::CloseParen => f.write_str("')'"),
+            Token::Comma => f.write_str("','"),
+            Token::In => f.write_str("'in'"),
+            Token::NotIn => f.write_str("'notin'"),
+            Token::End => f.write_str("end of selector"),
+        }
+    }
+}
+
+/// Recursive descent parser for label selectors, following the lexer in apimachinery
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    peeked: Option<Token<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.char_indices().peekable(),
+            peeked: None,
+        }
+    }
+
+    fn is_special(c: char) -> bool {
+        matches!(c, '!' | '=' | '(' | ')' | ',' | '<' | '>')
+    }
+
+    fn lex(&mut self) -> Token<'a> {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+        let Some((start, c)) = self.chars.next() else {
+            return Token::End;
+        };
+        match c {
+            '(' => Token::OpenParen,
+            ')' => Token::CloseParen,
+            ',' => Token::Comma,
+            '!' if self.chars.next_if(|(_, c)| *c == '=').is_some() => Token::NotEquals,
+            '!' => Token::Not,
+            '=' if self.chars.next_if(|(_, c)| *c == '=').is_some() => Token::DoubleEquals,
+            '=' => Token::Equals,
+            '<' | '>' => Token::Invalid(&self.input[start..start + 1]),
+            _ => {
+                let mut end = start + c.len_utf8();
+                while let Some((i, c)) = self
+                    .chars
+                    .next_if(|(_, c)| !c.is_whitespace() && !Self::is_special(*c))
+                {
+                    end = i + c.len_utf8();
+                }
+                match &self.input[start..end] {
+                    "in" => Token::In,
+                    "notin" => Token::NotIn,
+                    ident => Token::Identifier(ident),
+                }
+            }
+        }
+    }
+
+    fn next(&mut self) -> Token<'a> {
+        self.peeked.take().unwrap_or_else(|| self.lex())
+    }
+
+    fn peek(&mut self) -> Token<'a> {
+        let token = self.next();
+        self.peeked = Some(token);
+        token
+    }
+
+    fn unexpected(&self, found: Token<'_>, expected: &str) -> ParseExpressionError {
+        ParseExpressionError(format!(
+            "found {found}, expected {expected} in selector {:?}",
+            self.input
+        ))
+    }
+
+    fn expression(&mut self) -> Result<Expression, ParseExpressionError> {
+        let not = self.peek() == Token::Not;
+        if not {
+            self.next();
+        }
+        let key = match self.next() {
+            Token::Identifier(key) => validate_key(key)?,
+            token => return Err(self.unexpected(token, "a label key")),
+        };
+        if not {
+            return Ok(Expression::DoesNotExist(key));
+        }
+        match self.peek() {
+            Token::End | Token::Comma => Ok(Expression::Exists(key)),
+            Token::Equals | Token::DoubleEquals => {
+                self.next();
+                Ok(Expression::Equal(key, self.value()?))
+            }
+            Token::NotEquals => {
+                self.next();
+                Ok(Expression::NotEqual(key, self.value()?))
+            }
+            Token::In => {
+                self.next();
+                Ok(Expression::In(key, self.values()?))
+            }
+            Token::NotIn => {
+                self.next();
+                Ok(Expression::NotIn(key, self.values()?))
+            }
+            token => Err(self.unexpected(token, "an operator")),
+        }
+    }
+
+    /// Value after an equality operator, which may be empty
+    fn value(&mut self) -> Result<String, ParseExpressionError> {
+        match self.peek() {
+            Token::End | Token::Comma => Ok(String::new()),
+            Token::Identifier(value) => {
+                self.next();
+                validate_value(value)
+            }
+            token => Err(self.unexpected(token, "a label value")),
+        }
+    }
+
+    /// Parenthesized list of values after a set operator
+    ///
+    /// As in apimachinery, an empty list `()` is a set containing the empty value.
+    fn values(&mut self) -> Result<BTreeSet<String>, ParseExpressionError> {
+        match self.next() {
+            Token::OpenParen => {}
+            token => return Err(self.unexpected(token, "'('")),
+        }
+        let mut values = BTreeSet::new();
+        loop {
+            match self.next() {
+                Token::Identifier(value) => {
+                    values.insert(validate_value(value)?);
+                    match self.next() {
+                        Token::Comma => {}
+                        Token::CloseParen => return Ok(values),
+                        token => return Err(self.unexpected(token, "',' or ')'")),
+                    }
+                }
+                // empty values, as in `(a,)` or `()`
+                Token::Comma => {
+                    values.insert(String::new());
+                }
+                Token::CloseParen => {
+                    values.insert(String::new());
+                    return Ok(values);
+                }
+                token => return Err(self.unexpected(token, "a label value")),
+            }
+        }
+    }
+}
+
+/// Validate a label key, an optional DNS subdomain prefix followed by `/` and a name
+fn validate_key(key: &str) -> Result<String, ParseExpressionError> {
+    let (prefix, name) = match key.split_once('/') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, key),
+    };
+    if let Some(prefix) = prefix {
+        let valid_label = |l: &str| {
+            !l.is_empty()
+                && l.len() <= 63
+                && l.bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-')
+                && !l.starts_with('-')
+                && !l.ends_with('-')
+        };
+        if prefix.len() > 253 || !prefix.split('.').all(valid_label) {
+            return Err(ParseExpressionError(format!(
+                "invalid label key {key:?}: prefix must be a lowercase DNS subdomain of at most 253 characters"
+            )));
+        }
+    }
+    if name.is_empty() || !is_valid_name(name) {
+        return Err(ParseExpressionError(format!(
+            "invalid label key {key:?}: name must be 63 characters or less, \
+             consist of alphanumerics, '-', '_' or '.', and start and end with an alphanumeric"
+        )));
+    }
+    Ok(key.to_string())
+}
+
+/// Validate a label value, which is either empty or a valid name
+fn validate_value(value: &str) -> Result<String, ParseExpressionError> {
+    if !value.is_empty() && !is_valid_name(value) {
+        return Err(ParseExpressionError(format!(
+            "invalid label value {value:?}: must be 63 characters or less, \
+             consist of alphanumerics, '-', '_' or '.', and start and end with an alphanumeric"
+        )));
+    }
+    Ok(value.to_string())
+}
+
+fn is_valid_name(name: &str) -> bool {
+    let bytes = name.as_bytes();
+    name.len() <= 63
+        && bytes.first().is_some_and(u8::is_ascii_alphanumeric)
+        && bytes.last().is_some_and(u8::is_ascii_alphanumeric)
+        && bytes
+            .iter()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
 // convenience conversions for Selector and Expression
 
 impl IntoIterator for Expression {
@@ -627,4 +906,62 @@ mod tests {
             "foo in (bar,baz),foo notin (bar,baz),foo=bar,foo!=bar,foo,!foo"
         )
     }
+
+    #[test]
+    fn test_parse_roundtrip() {
+        for input in [
+            "",
+            "foo",
+            "!foo",
+            "foo=bar",
+            "foo!=bar",
+            "foo=",
+            "foo in (bar,baz),foo notin (bar,baz),foo=bar,foo!=bar,foo,!foo",
+            "app in (a,b),!canary,tier=web",
+            "example.com/tier=web,app.kubernetes.io/name in (),k8s.io/x_y.z",
+        ] {
+            let selector: Selector = input.parse().unwrap();
+            assert_eq!(selector.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_parse_normalizes() {
+        for (input, expected) in [
+            ("  foo == bar ,  baz  ", "foo=bar,baz"),
+            ("foo in ( b , a )", "foo in (a,b)"),
+            ("foo in (a,)", "foo in (,a)"),
+            ("foo notin()", "foo notin ()"),
+        ] {
+            let selector: Selector = input.parse().unwrap();
+            assert_eq!(selector.to_string(), expected, "{input}");
+        }
+        let expr: Expression = "! foo".parse().unwrap();
+        assert_eq!(expr, Expression::DoesNotExist("foo".into()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for input in [
+            ",",
+            "foo,",
+            "foo=bar=baz",
+            "foo in bar",
+            "foo in (bar",
+            "foo in (bar baz)",
+            "foo>1",
+            "!foo=bar",
+            "in",
+            "-foo",
+            "Example.com/foo",
+            "/foo",
+            "foo/",
+            "foo=-bar",
+            &"a".repeat(64),
+            &format!("foo={}", "a".repeat(64)),
+        ] {
+            assert!(input.parse::<Selector>().is_err(), "{input}");
+        }
+        assert!("foo,bar".parse::<Expression>().is_err());
+    }
 }
@@ -37,6 +37,9 @@ pub use gvk::{GroupVersion, GroupVersionKind, GroupVersionResource};
 pub mod metadata;
 pub use metadata::{ListMeta, ObjectMeta, PartialObjectMeta, PartialObjectMetaExt, TypeMeta};
 
+pub mod fields;
+pub use fields::{FieldExpression, FieldSelector, SelectableFields};
+
 pub mod labels;
 
 #[cfg(feature = "kubelet-debug")] pub mod kubelet_debug;
@@ -46,6 +49,10 @@ pub use object::{NotUsed, Object, ObjectList};
 
 pub mod params;
 
+#[cfg_attr(docsrs, doc(cfg(feature = "protobuf")))]
+#[cfg(feature = "protobuf")]
+pub mod protobuf;
+
 pub mod request;
 pub use request::Request;
 
@@ -66,6 +73,9 @@ pub mod schema;
 
 pub mod subresource;
 
+pub mod table;
+pub use table::{Table, TableColumnDefinition, TableRow, TableRowCondition};
+
 pub mod util;
 
 pub mod watch;
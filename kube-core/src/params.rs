@@ -1,5 +1,5 @@
 //! A port of request parameter *Optionals from apimachinery/types.go
-use crate::{request::Error, Selector};
+use crate::{request::Error, FieldSelector, SelectableFields, Selector};
 use serde::Serialize;
 
 /// Controls how the resource version parameter is applied for list calls
@@ -156,6 +156,24 @@ impl ListParams {
         self
     }
 
+    /// Configure a typed field selector
+    ///
+    /// The fields are validated against the [`SelectableFields`] of the kind `K`.
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::core::{FieldExpression, FieldSelector};
+    /// # use kube::core::params::ListParams;
+    /// let selector: FieldSelector = FieldExpression::Equal("status.phase".into(), "Running".into()).into();
+    /// let lp = ListParams::default().fields_from::<Pod>(&selector)?;
+    /// # Ok::<(), kube::core::request::Error>(())
+    /// ```
+    pub fn fields_from<K: SelectableFields>(mut self, selector: &FieldSelector) -> Result<Self, Error> {
+        selector.validate::<K>()?;
+        self.field_selector = Some(selector.to_string());
+        Ok(self)
+    }
+
     /// Configure the selector to restrict the list of returned objects by their labels.
     ///
     /// Defaults to everything.
@@ -444,6 +462,24 @@ impl WatchParams {
         self
     }
 
+    /// Configure a typed field selector
+    ///
+    /// The fields are validated against the [`SelectableFields`] of the kind `K`.
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::core::{FieldExpression, FieldSelector};
+    /// # use kube::core::params::WatchParams;
+    /// let selector: FieldSelector = FieldExpression::Equal("status.phase".into(), "Running".into()).into();
+    /// let wp = WatchParams::default().fields_from::<Pod>(&selector)?;
+    /// # Ok::<(), kube::core::request::Error>(())
+    /// ```
+    pub fn fields_from<K: SelectableFields>(mut self, selector: &FieldSelector) -> Result<Self, Error> {
+        selector.validate::<K>()?;
+        self.field_selector = Some(selector.to_string());
+        Ok(self)
+    }
+
     /// Configure the selector to restrict the list of returned objects by their labels.
     ///
     /// Defaults to everything.
@@ -0,0 +1,502 @@
+//! Deserialization of decoded messages as if they were the JSON encoding of their objects
+//!
+//! Messages are passed to visitors with the field names, nesting and scalar encodings of their JSON objects,
+//! so that any type that deserializes from JSON (e.g. the `k8s-openapi` structs) also deserializes from them.
+use std::collections::BTreeMap;
+
+use prost::Message as _;
+use serde::de::{
+    self,
+    value::{MapDeserializer, SeqDeserializer, StrDeserializer, StringDeserializer},
+    DeserializeSeed, IntoDeserializer, MapAccess, Visitor,
+};
+
+use super::{
+    generated::k8s::{
+        self,
+        io::{
+            api::{core, discovery},
+            apimachinery::pkg::{api::resource, apis::meta, runtime, util::intstr},
+        },
+    },
+    DecodeError, MAGIC, PROTOBUF_MIME,
+};
+
+/// A decoded value that deserializes like its JSON encoding
+pub(super) trait Value: Default {
+    /// Whether the value is set, unset fields are left out of objects like in JSON
+    fn is_set(&self) -> bool {
+        true
+    }
+
+    /// Pass the value to a visitor
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError>;
+
+    /// Pass the value to a visitor of an enum, which only strings can be in JSON
+    fn visit_enum<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        self.visit(visitor)
+    }
+}
+
+/// A decoded message that deserializes like a JSON object
+pub(super) trait JsonObject: Value {
+    /// The number of fields in the JSON object, including the fields of inlined messages
+    const FIELDS: usize;
+
+    /// The JSON name of a field
+    fn name(index: usize) -> &'static str;
+
+    /// Whether a field is set
+    fn has(&self, index: usize) -> bool;
+
+    /// Deserialize a field, leaving it unset
+    fn take<'de, S: DeserializeSeed<'de>>(&mut self, index: usize, seed: S) -> Result<S::Value, DecodeError>;
+}
+
+/// Implements [`JsonObject`] for messages, from the table of their JSON field names
+///
+/// Fields are either the JSON name, `(present "name")` for optional strings that are set even when empty,
+/// or `(inline Message)` for Go embedded structs whose fields are part of the outer object.
+macro_rules! messages {
+    ($($message:path { $($field:ident: $spec:tt,)* })*) => {$(
+        impl Value for $message {
+            fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+                visitor.visit_map(FieldAccess::new(self))
+            }
+        }
+
+        impl JsonObject for $message {
+            const FIELDS: usize = 0 $(+ field!(len $spec))*;
+
+            #[allow(unused_assignments)]
+            fn name(mut index: usize) -> &'static str {
+                $(field!(name index, $spec);)*
+                unreachable!("no field {index} in {}", stringify!($message))
+            }
+
+            #[allow(unused_assignments)]
+            fn has(&self, mut index: usize) -> bool {
+                $(field!(has self.$field, index, $spec);)*
+                false
+            }
+
+            #[allow(unused_assignments)]
+            fn take<'de, S: DeserializeSeed<'de>>(&mut self, mut index: usize, seed: S) -> Result<S::Value, DecodeError> {
+                $(field!(take self.$field, index, seed, $spec);)*
+                Err(de::Error::custom(format_args!("no field {index} in {}", stringify!($message))))
+            }
+        }
+    )*};
+}
+
+/// The parts of [`JsonObject`] implementations for each kind of field
+macro_rules! field {
+    (len (inline $inner:path)) => { <$inner as JsonObject>::FIELDS };
+    (len $spec:tt) => { 1 };
+
+    (name $index:ident, (inline $inner:path)) => {
+        if $index < <$inner as JsonObject>::FIELDS {
+            return <$inner as JsonObject>::name($index);
+        }
+        $index -= <$inner as JsonObject>::FIELDS;
+    };
+    (name $index:ident, (present $name:literal)) => { field!(name $index, $name) };
+    (name $index:ident, $name:literal) => {
+        if $index == 0 {
+            return $name;
+        }
+        $index -= 1;
+    };
+
+    (has $this:ident.$field:ident, $index:ident, (inline $inner:path)) => {
+        if $index < <$inner as JsonObject>::FIELDS {
+            return $this.$field.as_ref().is_some_and(|inner| inner.has($index));
+        }
+        $index -= <$inner as JsonObject>::FIELDS;
+    };
+    (has $this:ident.$field:ident, $index:ident, (present $name:literal)) => {
+        if $index == 0 {
+            return $this.$field.is_some();
+        }
+        $index -= 1;
+    };
+    (has $this:ident.$field:ident, $index:ident, $name:literal) => {
+        if $index == 0 {
+            return Value::is_set(&$this.$field);
+        }
+        $index -= 1;
+    };
+
+    (take $this:ident.$field:ident, $index:ident, $seed:ident, (inline $inner:path)) => {
+        if $index < <$inner as JsonObject>::FIELDS {
+            return $this.$field.get_or_insert_with(Default::default).take($index, $seed);
+        }
+        $index -= <$inner as JsonObject>::FIELDS;
+    };
+    (take $this:ident.$field:ident, $index:ident, $seed:ident, $spec:tt) => {
+        if $index == 0 {
+            return $seed.deserialize(ValueDeserializer(std::mem::take(&mut $this.$field)));
+        }
+        $index -= 1;
+    };
+}
+
+include!("generated/json.rs");
+
+impl Value for String {
+    /// Empty strings are left out of JSON objects
+    fn is_set(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_string(self)
+    }
+
+    fn visit_enum<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_enum(StringDeserializer::new(self))
+    }
+}
+
+impl Value for bool {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_bool(self)
+    }
+}
+
+impl Value for i32 {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_i32(self)
+    }
+}
+
+impl Value for i64 {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_i64(self)
+    }
+}
+
+impl<T: Value> Value for Option<T> {
+    fn is_set(&self) -> bool {
+        self.as_ref().is_some_and(Value::is_set)
+    }
+
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        match self {
+            Some(value) => value.visit(visitor),
+            None => visitor.visit_none(),
+        }
+    }
+
+    fn visit_enum<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        match self {
+            Some(value) => value.visit_enum(visitor),
+            None => visitor.visit_none(),
+        }
+    }
+}
+
+impl<T: Value> Value for Vec<T> {
+    fn is_set(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        de::Deserializer::deserialize_any(
+            SeqDeserializer::new(self.into_iter().map(ValueDeserializer)),
+            visitor,
+        )
+    }
+}
+
+impl<T: Value> Value for BTreeMap<String, T> {
+    fn is_set(&self) -> bool {
+        !self.is_empty()
+    }
+
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        let entries = self
+            .into_iter()
+            .map(|(key, value)| (key, ValueDeserializer(value)));
+        de::Deserializer::deserialize_any(MapDeserializer::new(entries), visitor)
+    }
+}
+
+/// Quantities are strings in JSON
+impl Value for resource::Quantity {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visitor.visit_string(self.string.unwrap_or_default())
+    }
+}
+
+/// Either an integer or a string in JSON
+impl Value for intstr::IntOrString {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        match self.r#type {
+            Some(1) => visitor.visit_string(self.str_val.unwrap_or_default()),
+            _ => visitor.visit_i32(self.int_val.unwrap_or_default()),
+        }
+    }
+}
+
+/// Times are RFC 3339 strings with second precision in JSON
+impl Value for meta::v1::Time {
+    /// The zero time is encoded as an empty message, and as `null` in JSON
+    fn is_set(&self) -> bool {
+        self.seconds.is_some() || self.nanos.is_some()
+    }
+
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        let seconds = self.seconds.unwrap_or_default();
+        let time = chrono::DateTime::from_timestamp(seconds, 0)
+            .ok_or_else(|| de::Error::custom(format_args!("invalid timestamp {seconds}")))?;
+        visitor.visit_string(time.to_rfc3339_opts(chrono::SecondsFormat::Secs, true))
+    }
+}
+
+/// Managed fields hold their JSON encoding
+impl Value for meta::v1::FieldsV1 {
+    fn visit<'de, V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        visit_json(self.raw.as_deref().unwrap_or(b"{}"), visitor)
+    }
+}
+
+fn visit_json<'de, V: Visitor<'de>>(raw: &[u8], visitor: V) -> Result<V::Value, DecodeError> {
+    let value = serde_json::from_slice::<serde_json::Value>(raw).map_err(DecodeError::Json)?;
+    de::Deserializer::deserialize_any(value, visitor).map_err(DecodeError::Json)
+}
+
+/// Deserializes a decoded value
+pub(super) struct ValueDeserializer<T>(pub(super) T);
+
+impl<'de, T: Value> de::Deserializer<'de> for ValueDeserializer<T> {
+    type Error = DecodeError;
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        self.0.visit(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        if self.0.is_set() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, DecodeError> {
+        self.0.visit_enum(visitor)
+    }
+}
+
+impl<'de, T: Value> IntoDeserializer<'de, DecodeError> for ValueDeserializer<T> {
+    type Deserializer = Self;
+
+    fn into_deserializer(self) -> Self {
+        self
+    }
+}
+
+/// The fields of a message that are set
+struct FieldAccess<M> {
+    message: M,
+    index: usize,
+}
+
+impl<M> FieldAccess<M> {
+    fn new(message: M) -> Self {
+        Self { message, index: 0 }
+    }
+}
+
+impl<'de, M: JsonObject> MapAccess<'de> for FieldAccess<M> {
+    type Error = DecodeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, DecodeError> {
+        while self.index < M::FIELDS && !self.message.has(self.index) {
+            self.index += 1;
+        }
+        if self.index == M::FIELDS {
+            return Ok(None);
+        }
+        seed.deserialize(StrDeserializer::new(M::name(self.index)))
+            .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DecodeError> {
+        let value = self.message.take(self.index, seed);
+        self.index += 1;
+        value
+    }
+}
+
+/// The fields of a top level object, after the `apiVersion` and `kind` of its envelope
+///
+/// The messages of objects leave out their type, which is only part of the envelope.
+struct ObjectAccess<M> {
+    types: std::vec::IntoIter<(&'static str, String)>,
+    value: Option<String>,
+    fields: FieldAccess<M>,
+}
+
+impl<'de, M: JsonObject> MapAccess<'de> for ObjectAccess<M> {
+    type Error = DecodeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, DecodeError> {
+        match self.types.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(StrDeserializer::new(key)).map(Some)
+            }
+            None => self.fields.next_key_seed(seed),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DecodeError> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(ValueDeserializer(value)),
+            None => self.fields.next_value_seed(seed),
+        }
+    }
+}
+
+/// Deserializes the object in a `runtime.Unknown` envelope
+pub(super) struct Envelope(runtime::Unknown);
+
+impl Envelope {
+    /// Decode an envelope, including its `k8s\0` prefix
+    pub(super) fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
+        let buf = buf.strip_prefix(MAGIC).ok_or(DecodeError::MissingMagic)?;
+        runtime::Unknown::decode(buf)
+            .map(Self)
+            .map_err(DecodeError::Invalid)
+    }
+}
+
+impl<'de> de::Deserializer<'de> for Envelope {
+    type Error = DecodeError;
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map struct enum identifier ignored_any
+    }
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, DecodeError> {
+        let runtime::Unknown {
+            type_meta,
+            raw,
+            content_encoding,
+            content_type,
+        } = self.0;
+        if let Some(encoding) = content_encoding.filter(|encoding| !encoding.is_empty()) {
+            return Err(DecodeError::UnsupportedEncoding(encoding));
+        }
+        let raw = raw.unwrap_or_default();
+        match content_type.as_deref() {
+            None | Some("" | PROTOBUF_MIME) => {}
+            Some(content_type) if content_type.starts_with("application/json") => {
+                return visit_json(&raw, visitor);
+            }
+            Some(content_type) => return Err(DecodeError::UnsupportedContentType(content_type.to_owned())),
+        }
+
+        let type_meta = type_meta.unwrap_or_default();
+        let api_version = type_meta.api_version.unwrap_or_default();
+        let kind = type_meta.kind.unwrap_or_default();
+        match (api_version.as_str(), kind.as_str()) {
+            ("v1", "Pod") => visit_object::<core::v1::Pod, V>(api_version, kind, &raw, visitor),
+            ("v1", "PodList") => visit_object::<core::v1::PodList, V>(api_version, kind, &raw, visitor),
+            ("v1", "Node") => visit_object::<core::v1::Node, V>(api_version, kind, &raw, visitor),
+            ("v1", "NodeList") => visit_object::<core::v1::NodeList, V>(api_version, kind, &raw, visitor),
+            ("v1", "Endpoints") => visit_object::<core::v1::Endpoints, V>(api_version, kind, &raw, visitor),
+            ("v1", "EndpointsList") => {
+                visit_object::<core::v1::EndpointsList, V>(api_version, kind, &raw, visitor)
+            }
+            ("discovery.k8s.io/v1", "EndpointSlice") => {
+                visit_object::<discovery::v1::EndpointSlice, V>(api_version, kind, &raw, visitor)
+            }
+            ("discovery.k8s.io/v1", "EndpointSliceList") => {
+                visit_object::<discovery::v1::EndpointSliceList, V>(api_version, kind, &raw, visitor)
+            }
+            ("v1", "Status") => visit_object::<meta::v1::Status, V>(api_version, kind, &raw, visitor),
+            _ => Err(DecodeError::UnsupportedKind { api_version, kind }),
+        }
+    }
+}
+
+fn visit_object<'de, M, V>(
+    api_version: String,
+    kind: String,
+    raw: &[u8],
+    visitor: V,
+) -> Result<V::Value, DecodeError>
+where
+    M: JsonObject + prost::Message,
+    V: Visitor<'de>,
+{
+    let message = M::decode(raw).map_err(DecodeError::Invalid)?;
+    visitor.visit_map(ObjectAccess {
+        types: vec![("apiVersion", api_version), ("kind", kind)].into_iter(),
+        value: None,
+        fields: FieldAccess::new(message),
+    })
+}
+
+/// Deserializes a `meta.v1.WatchEvent` like its JSON encoding, with the `type` and enveloped `object`
+pub(super) struct WatchEventAccess {
+    event_type: Option<String>,
+    object: Option<Vec<u8>>,
+}
+
+impl WatchEventAccess {
+    /// Decode a watch event, without its length prefix
+    pub(super) fn decode(buf: &[u8]) -> Result<Self, DecodeError> {
+        let event = meta::v1::WatchEvent::decode(buf).map_err(DecodeError::Invalid)?;
+        Ok(Self {
+            event_type: Some(event.r#type.unwrap_or_default()),
+            object: Some(event.object.and_then(|object| object.raw).unwrap_or_default()),
+        })
+    }
+}
+
+impl<'de> MapAccess<'de> for WatchEventAccess {
+    type Error = DecodeError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>, DecodeError> {
+        let key = if self.event_type.is_some() {
+            "type"
+        } else if self.object.is_some() {
+            "object"
+        } else {
+            return Ok(None);
+        };
+        seed.deserialize(StrDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, DecodeError> {
+        if let Some(event_type) = self.event_type.take() {
+            return seed.deserialize(ValueDeserializer(event_type));
+        }
+        let object = self.object.take().unwrap_or_default();
+        seed.deserialize(Envelope::decode(&object)?)
+    }
+}
@@ -0,0 +1,1022 @@
+// This file is @generated by kube-core/protos/generate.rs.
+messages! {
+    k8s::io::apimachinery::pkg::apis::meta::v1::LabelSelector {
+        match_labels: "matchLabels",
+        match_expressions: "matchExpressions",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::LabelSelectorRequirement {
+        key: "key",
+        operator: "operator",
+        values: "values",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::ListMeta {
+        self_link: "selfLink",
+        resource_version: "resourceVersion",
+        r#continue: "continue",
+        remaining_item_count: "remainingItemCount",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::ManagedFieldsEntry {
+        manager: "manager",
+        operation: "operation",
+        api_version: "apiVersion",
+        time: "time",
+        fields_type: "fieldsType",
+        fields_v1: "fieldsV1",
+        subresource: "subresource",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::ObjectMeta {
+        name: "name",
+        generate_name: "generateName",
+        namespace: "namespace",
+        self_link: "selfLink",
+        uid: "uid",
+        resource_version: "resourceVersion",
+        generation: "generation",
+        creation_timestamp: "creationTimestamp",
+        deletion_timestamp: "deletionTimestamp",
+        deletion_grace_period_seconds: "deletionGracePeriodSeconds",
+        labels: "labels",
+        annotations: "annotations",
+        owner_references: "ownerReferences",
+        finalizers: "finalizers",
+        managed_fields: "managedFields",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::OwnerReference {
+        api_version: "apiVersion",
+        kind: "kind",
+        name: "name",
+        uid: "uid",
+        controller: "controller",
+        block_owner_deletion: "blockOwnerDeletion",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::Status {
+        metadata: "metadata",
+        status: "status",
+        message: "message",
+        reason: "reason",
+        details: "details",
+        code: "code",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::StatusCause {
+        reason: "reason",
+        message: "message",
+        field: "field",
+    }
+    k8s::io::apimachinery::pkg::apis::meta::v1::StatusDetails {
+        name: "name",
+        group: "group",
+        kind: "kind",
+        uid: "uid",
+        causes: "causes",
+        retry_after_seconds: "retryAfterSeconds",
+    }
+    k8s::io::api::core::v1::AwsElasticBlockStoreVolumeSource {
+        volume_id: "volumeID",
+        fs_type: "fsType",
+        partition: "partition",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::Affinity {
+        node_affinity: "nodeAffinity",
+        pod_affinity: "podAffinity",
+        pod_anti_affinity: "podAntiAffinity",
+    }
+    k8s::io::api::core::v1::AppArmorProfile {
+        r#type: "type",
+        localhost_profile: "localhostProfile",
+    }
+    k8s::io::api::core::v1::AttachedVolume {
+        name: "name",
+        device_path: "devicePath",
+    }
+    k8s::io::api::core::v1::AzureDiskVolumeSource {
+        disk_name: "diskName",
+        disk_uri: "diskURI",
+        caching_mode: "cachingMode",
+        fs_type: "fsType",
+        read_only: "readOnly",
+        kind: "kind",
+    }
+    k8s::io::api::core::v1::AzureFileVolumeSource {
+        secret_name: "secretName",
+        share_name: "shareName",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::CsiVolumeSource {
+        driver: "driver",
+        read_only: "readOnly",
+        fs_type: "fsType",
+        volume_attributes: "volumeAttributes",
+        node_publish_secret_ref: "nodePublishSecretRef",
+    }
+    k8s::io::api::core::v1::Capabilities {
+        add: "add",
+        drop: "drop",
+    }
+    k8s::io::api::core::v1::CephFsVolumeSource {
+        monitors: "monitors",
+        path: "path",
+        user: "user",
+        secret_file: "secretFile",
+        secret_ref: "secretRef",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::CinderVolumeSource {
+        volume_id: "volumeID",
+        fs_type: "fsType",
+        read_only: "readOnly",
+        secret_ref: "secretRef",
+    }
+    k8s::io::api::core::v1::ClaimSource {
+        resource_claim_name: "resourceClaimName",
+        resource_claim_template_name: "resourceClaimTemplateName",
+    }
+    k8s::io::api::core::v1::ClusterTrustBundleProjection {
+        name: "name",
+        signer_name: "signerName",
+        label_selector: "labelSelector",
+        optional: "optional",
+        path: "path",
+    }
+    k8s::io::api::core::v1::ConfigMapEnvSource {
+        local_object_reference: (inline k8s::io::api::core::v1::LocalObjectReference),
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::ConfigMapKeySelector {
+        local_object_reference: (inline k8s::io::api::core::v1::LocalObjectReference),
+        key: "key",
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::ConfigMapNodeConfigSource {
+        namespace: "namespace",
+        name: "name",
+        uid: "uid",
+        resource_version: "resourceVersion",
+        kubelet_config_key: "kubeletConfigKey",
+    }
+    k8s::io::api::core::v1::ConfigMapProjection {
+        local_object_reference: (inline k8s::io::api::core::v1::LocalObjectReference),
+        items: "items",
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::ConfigMapVolumeSource {
+        local_object_reference: (inline k8s::io::api::core::v1::LocalObjectReference),
+        items: "items",
+        default_mode: "defaultMode",
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::Container {
+        name: "name",
+        image: "image",
+        command: "command",
+        args: "args",
+        working_dir: "workingDir",
+        ports: "ports",
+        env_from: "envFrom",
+        env: "env",
+        resources: "resources",
+        resize_policy: "resizePolicy",
+        restart_policy: "restartPolicy",
+        volume_mounts: "volumeMounts",
+        volume_devices: "volumeDevices",
+        liveness_probe: "livenessProbe",
+        readiness_probe: "readinessProbe",
+        startup_probe: "startupProbe",
+        lifecycle: "lifecycle",
+        termination_message_path: "terminationMessagePath",
+        termination_message_policy: "terminationMessagePolicy",
+        image_pull_policy: "imagePullPolicy",
+        security_context: "securityContext",
+        stdin: "stdin",
+        stdin_once: "stdinOnce",
+        tty: "tty",
+    }
+    k8s::io::api::core::v1::ContainerImage {
+        names: "names",
+        size_bytes: "sizeBytes",
+    }
+    k8s::io::api::core::v1::ContainerPort {
+        name: "name",
+        host_port: "hostPort",
+        container_port: "containerPort",
+        protocol: "protocol",
+        host_ip: "hostIP",
+    }
+    k8s::io::api::core::v1::ContainerResizePolicy {
+        resource_name: "resourceName",
+        restart_policy: "restartPolicy",
+    }
+    k8s::io::api::core::v1::ContainerState {
+        waiting: "waiting",
+        running: "running",
+        terminated: "terminated",
+    }
+    k8s::io::api::core::v1::ContainerStateRunning {
+        started_at: "startedAt",
+    }
+    k8s::io::api::core::v1::ContainerStateTerminated {
+        exit_code: "exitCode",
+        signal: "signal",
+        reason: "reason",
+        message: "message",
+        started_at: "startedAt",
+        finished_at: "finishedAt",
+        container_id: "containerID",
+    }
+    k8s::io::api::core::v1::ContainerStateWaiting {
+        reason: "reason",
+        message: "message",
+    }
+    k8s::io::api::core::v1::ContainerStatus {
+        name: "name",
+        state: "state",
+        last_state: "lastState",
+        ready: "ready",
+        restart_count: "restartCount",
+        image: "image",
+        image_id: "imageID",
+        container_id: "containerID",
+        started: "started",
+        allocated_resources: "allocatedResources",
+        resources: "resources",
+        volume_mounts: "volumeMounts",
+        user: "user",
+        allocated_resources_status: "allocatedResourcesStatus",
+    }
+    k8s::io::api::core::v1::ContainerUser {
+        linux: "linux",
+    }
+    k8s::io::api::core::v1::DaemonEndpoint {
+        port: "Port",
+    }
+    k8s::io::api::core::v1::DownwardApiProjection {
+        items: "items",
+    }
+    k8s::io::api::core::v1::DownwardApiVolumeFile {
+        path: "path",
+        field_ref: "fieldRef",
+        resource_field_ref: "resourceFieldRef",
+        mode: "mode",
+    }
+    k8s::io::api::core::v1::DownwardApiVolumeSource {
+        items: "items",
+        default_mode: "defaultMode",
+    }
+    k8s::io::api::core::v1::EmptyDirVolumeSource {
+        medium: "medium",
+        size_limit: "sizeLimit",
+    }
+    k8s::io::api::core::v1::EndpointAddress {
+        ip: "ip",
+        hostname: "hostname",
+        node_name: "nodeName",
+        target_ref: "targetRef",
+    }
+    k8s::io::api::core::v1::EndpointPort {
+        name: "name",
+        port: "port",
+        protocol: "protocol",
+        app_protocol: "appProtocol",
+    }
+    k8s::io::api::core::v1::EndpointSubset {
+        addresses: "addresses",
+        not_ready_addresses: "notReadyAddresses",
+        ports: "ports",
+    }
+    k8s::io::api::core::v1::Endpoints {
+        metadata: "metadata",
+        subsets: "subsets",
+    }
+    k8s::io::api::core::v1::EndpointsList {
+        metadata: "metadata",
+        items: "items",
+    }
+    k8s::io::api::core::v1::EnvFromSource {
+        prefix: "prefix",
+        config_map_ref: "configMapRef",
+        secret_ref: "secretRef",
+    }
+    k8s::io::api::core::v1::EnvVar {
+        name: "name",
+        value: "value",
+        value_from: "valueFrom",
+    }
+    k8s::io::api::core::v1::EnvVarSource {
+        field_ref: "fieldRef",
+        resource_field_ref: "resourceFieldRef",
+        config_map_key_ref: "configMapKeyRef",
+        secret_key_ref: "secretKeyRef",
+    }
+    k8s::io::api::core::v1::EphemeralContainer {
+        ephemeral_container_common: (inline k8s::io::api::core::v1::EphemeralContainerCommon),
+        target_container_name: "targetContainerName",
+    }
+    k8s::io::api::core::v1::EphemeralContainerCommon {
+        name: "name",
+        image: "image",
+        command: "command",
+        args: "args",
+        working_dir: "workingDir",
+        ports: "ports",
+        env_from: "envFrom",
+        env: "env",
+        resources: "resources",
+        resize_policy: "resizePolicy",
+        restart_policy: "restartPolicy",
+        volume_mounts: "volumeMounts",
+        volume_devices: "volumeDevices",
+        liveness_probe: "livenessProbe",
+        readiness_probe: "readinessProbe",
+        startup_probe: "startupProbe",
+        lifecycle: "lifecycle",
+        termination_message_path: "terminationMessagePath",
+        termination_message_policy: "terminationMessagePolicy",
+        image_pull_policy: "imagePullPolicy",
+        security_context: "securityContext",
+        stdin: "stdin",
+        stdin_once: "stdinOnce",
+        tty: "tty",
+    }
+    k8s::io::api::core::v1::EphemeralVolumeSource {
+        volume_claim_template: "volumeClaimTemplate",
+    }
+    k8s::io::api::core::v1::ExecAction {
+        command: "command",
+    }
+    k8s::io::api::core::v1::FcVolumeSource {
+        target_ww_ns: "targetWWNs",
+        lun: "lun",
+        fs_type: "fsType",
+        read_only: "readOnly",
+        wwids: "wwids",
+    }
+    k8s::io::api::core::v1::FlexVolumeSource {
+        driver: "driver",
+        fs_type: "fsType",
+        secret_ref: "secretRef",
+        read_only: "readOnly",
+        options: "options",
+    }
+    k8s::io::api::core::v1::FlockerVolumeSource {
+        dataset_name: "datasetName",
+        dataset_uuid: "datasetUUID",
+    }
+    k8s::io::api::core::v1::GcePersistentDiskVolumeSource {
+        pd_name: "pdName",
+        fs_type: "fsType",
+        partition: "partition",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::GrpcAction {
+        port: "port",
+        service: "service",
+    }
+    k8s::io::api::core::v1::GitRepoVolumeSource {
+        repository: "repository",
+        revision: "revision",
+        directory: "directory",
+    }
+    k8s::io::api::core::v1::GlusterfsVolumeSource {
+        endpoints: "endpoints",
+        path: "path",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::HttpGetAction {
+        path: "path",
+        port: "port",
+        host: "host",
+        scheme: "scheme",
+        http_headers: "httpHeaders",
+    }
+    k8s::io::api::core::v1::HttpHeader {
+        name: "name",
+        value: "value",
+    }
+    k8s::io::api::core::v1::HostAlias {
+        ip: "ip",
+        hostnames: "hostnames",
+    }
+    k8s::io::api::core::v1::HostIp {
+        ip: "ip",
+    }
+    k8s::io::api::core::v1::HostPathVolumeSource {
+        path: "path",
+        r#type: "type",
+    }
+    k8s::io::api::core::v1::IscsiVolumeSource {
+        target_portal: "targetPortal",
+        iqn: "iqn",
+        lun: "lun",
+        iscsi_interface: "iscsiInterface",
+        fs_type: "fsType",
+        read_only: "readOnly",
+        portals: "portals",
+        chap_auth_discovery: "chapAuthDiscovery",
+        chap_auth_session: "chapAuthSession",
+        secret_ref: "secretRef",
+        initiator_name: "initiatorName",
+    }
+    k8s::io::api::core::v1::ImageVolumeSource {
+        reference: "reference",
+        pull_policy: "pullPolicy",
+    }
+    k8s::io::api::core::v1::KeyToPath {
+        key: "key",
+        path: "path",
+        mode: "mode",
+    }
+    k8s::io::api::core::v1::Lifecycle {
+        post_start: "postStart",
+        pre_stop: "preStop",
+    }
+    k8s::io::api::core::v1::LifecycleHandler {
+        exec: "exec",
+        http_get: "httpGet",
+        tcp_socket: "tcpSocket",
+        sleep: "sleep",
+    }
+    k8s::io::api::core::v1::LinuxContainerUser {
+        uid: "uid",
+        gid: "gid",
+        supplemental_groups: "supplementalGroups",
+    }
+    k8s::io::api::core::v1::LocalObjectReference {
+        name: "name",
+    }
+    k8s::io::api::core::v1::NfsVolumeSource {
+        server: "server",
+        path: "path",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::Node {
+        metadata: "metadata",
+        spec: "spec",
+        status: "status",
+    }
+    k8s::io::api::core::v1::NodeAddress {
+        r#type: "type",
+        address: "address",
+    }
+    k8s::io::api::core::v1::NodeAffinity {
+        required_during_scheduling_ignored_during_execution: "requiredDuringSchedulingIgnoredDuringExecution",
+        preferred_during_scheduling_ignored_during_execution: "preferredDuringSchedulingIgnoredDuringExecution",
+    }
+    k8s::io::api::core::v1::NodeCondition {
+        r#type: "type",
+        status: "status",
+        last_heartbeat_time: "lastHeartbeatTime",
+        last_transition_time: "lastTransitionTime",
+        reason: "reason",
+        message: "message",
+    }
+    k8s::io::api::core::v1::NodeConfigSource {
+        config_map: "configMap",
+    }
+    k8s::io::api::core::v1::NodeConfigStatus {
+        assigned: "assigned",
+        active: "active",
+        last_known_good: "lastKnownGood",
+        error: "error",
+    }
+    k8s::io::api::core::v1::NodeDaemonEndpoints {
+        kubelet_endpoint: "kubeletEndpoint",
+    }
+    k8s::io::api::core::v1::NodeFeatures {
+        supplemental_groups_policy: "supplementalGroupsPolicy",
+    }
+    k8s::io::api::core::v1::NodeList {
+        metadata: "metadata",
+        items: "items",
+    }
+    k8s::io::api::core::v1::NodeRuntimeHandler {
+        name: "name",
+        features: "features",
+    }
+    k8s::io::api::core::v1::NodeRuntimeHandlerFeatures {
+        recursive_read_only_mounts: "recursiveReadOnlyMounts",
+        user_namespaces: "userNamespaces",
+    }
+    k8s::io::api::core::v1::NodeSelector {
+        node_selector_terms: "nodeSelectorTerms",
+    }
+    k8s::io::api::core::v1::NodeSelectorRequirement {
+        key: "key",
+        operator: "operator",
+        values: "values",
+    }
+    k8s::io::api::core::v1::NodeSelectorTerm {
+        match_expressions: "matchExpressions",
+        match_fields: "matchFields",
+    }
+    k8s::io::api::core::v1::NodeSpec {
+        pod_cidr: "podCIDR",
+        pod_cid_rs: "podCIDRs",
+        provider_id: "providerID",
+        unschedulable: "unschedulable",
+        taints: "taints",
+        config_source: "configSource",
+        external_id: "externalID",
+    }
+    k8s::io::api::core::v1::NodeStatus {
+        capacity: "capacity",
+        allocatable: "allocatable",
+        phase: "phase",
+        conditions: "conditions",
+        addresses: "addresses",
+        daemon_endpoints: "daemonEndpoints",
+        node_info: "nodeInfo",
+        images: "images",
+        volumes_in_use: "volumesInUse",
+        volumes_attached: "volumesAttached",
+        config: "config",
+        runtime_handlers: "runtimeHandlers",
+        features: "features",
+    }
+    k8s::io::api::core::v1::NodeSystemInfo {
+        machine_id: "machineID",
+        system_uuid: "systemUUID",
+        boot_id: "bootID",
+        kernel_version: "kernelVersion",
+        os_image: "osImage",
+        container_runtime_version: "containerRuntimeVersion",
+        kubelet_version: "kubeletVersion",
+        kube_proxy_version: "kubeProxyVersion",
+        operating_system: "operatingSystem",
+        architecture: "architecture",
+    }
+    k8s::io::api::core::v1::ObjectFieldSelector {
+        api_version: "apiVersion",
+        field_path: "fieldPath",
+    }
+    k8s::io::api::core::v1::ObjectReference {
+        kind: "kind",
+        namespace: "namespace",
+        name: "name",
+        uid: "uid",
+        api_version: "apiVersion",
+        resource_version: "resourceVersion",
+        field_path: "fieldPath",
+    }
+    k8s::io::api::core::v1::PersistentVolumeClaimSpec {
+        access_modes: "accessModes",
+        selector: "selector",
+        resources: "resources",
+        volume_name: "volumeName",
+        storage_class_name: (present "storageClassName"),
+        volume_mode: "volumeMode",
+        data_source: "dataSource",
+        data_source_ref: "dataSourceRef",
+        volume_attributes_class_name: "volumeAttributesClassName",
+    }
+    k8s::io::api::core::v1::PersistentVolumeClaimTemplate {
+        metadata: "metadata",
+        spec: "spec",
+    }
+    k8s::io::api::core::v1::PersistentVolumeClaimVolumeSource {
+        claim_name: "claimName",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::PhotonPersistentDiskVolumeSource {
+        pd_id: "pdID",
+        fs_type: "fsType",
+    }
+    k8s::io::api::core::v1::Pod {
+        metadata: "metadata",
+        spec: "spec",
+        status: "status",
+    }
+    k8s::io::api::core::v1::PodAffinity {
+        required_during_scheduling_ignored_during_execution: "requiredDuringSchedulingIgnoredDuringExecution",
+        preferred_during_scheduling_ignored_during_execution: "preferredDuringSchedulingIgnoredDuringExecution",
+    }
+    k8s::io::api::core::v1::PodAffinityTerm {
+        label_selector: "labelSelector",
+        namespaces: "namespaces",
+        topology_key: "topologyKey",
+        namespace_selector: "namespaceSelector",
+        match_label_keys: "matchLabelKeys",
+        mismatch_label_keys: "mismatchLabelKeys",
+    }
+    k8s::io::api::core::v1::PodAntiAffinity {
+        required_during_scheduling_ignored_during_execution: "requiredDuringSchedulingIgnoredDuringExecution",
+        preferred_during_scheduling_ignored_during_execution: "preferredDuringSchedulingIgnoredDuringExecution",
+    }
+    k8s::io::api::core::v1::PodCondition {
+        r#type: "type",
+        status: "status",
+        last_probe_time: "lastProbeTime",
+        last_transition_time: "lastTransitionTime",
+        reason: "reason",
+        message: "message",
+    }
+    k8s::io::api::core::v1::PodDnsConfig {
+        nameservers: "nameservers",
+        searches: "searches",
+        options: "options",
+    }
+    k8s::io::api::core::v1::PodDnsConfigOption {
+        name: "name",
+        value: "value",
+    }
+    k8s::io::api::core::v1::PodIp {
+        ip: "ip",
+    }
+    k8s::io::api::core::v1::PodList {
+        metadata: "metadata",
+        items: "items",
+    }
+    k8s::io::api::core::v1::PodOs {
+        name: "name",
+    }
+    k8s::io::api::core::v1::PodReadinessGate {
+        condition_type: "conditionType",
+    }
+    k8s::io::api::core::v1::PodResourceClaim {
+        name: "name",
+        source: "source",
+        resource_claim_name: "resourceClaimName",
+        resource_claim_template_name: "resourceClaimTemplateName",
+    }
+    k8s::io::api::core::v1::PodResourceClaimStatus {
+        name: "name",
+        resource_claim_name: "resourceClaimName",
+    }
+    k8s::io::api::core::v1::PodSchedulingGate {
+        name: "name",
+    }
+    k8s::io::api::core::v1::PodSecurityContext {
+        se_linux_options: "seLinuxOptions",
+        windows_options: "windowsOptions",
+        run_as_user: "runAsUser",
+        run_as_group: "runAsGroup",
+        run_as_non_root: "runAsNonRoot",
+        supplemental_groups: "supplementalGroups",
+        supplemental_groups_policy: "supplementalGroupsPolicy",
+        fs_group: "fsGroup",
+        sysctls: "sysctls",
+        fs_group_change_policy: "fsGroupChangePolicy",
+        seccomp_profile: "seccompProfile",
+        app_armor_profile: "appArmorProfile",
+        se_linux_change_policy: "seLinuxChangePolicy",
+    }
+    k8s::io::api::core::v1::PodSpec {
+        volumes: "volumes",
+        init_containers: "initContainers",
+        containers: "containers",
+        ephemeral_containers: "ephemeralContainers",
+        restart_policy: "restartPolicy",
+        termination_grace_period_seconds: "terminationGracePeriodSeconds",
+        active_deadline_seconds: "activeDeadlineSeconds",
+        dns_policy: "dnsPolicy",
+        node_selector: "nodeSelector",
+        service_account_name: "serviceAccountName",
+        service_account: "serviceAccount",
+        automount_service_account_token: "automountServiceAccountToken",
+        node_name: "nodeName",
+        host_network: "hostNetwork",
+        host_pid: "hostPID",
+        host_ipc: "hostIPC",
+        share_process_namespace: "shareProcessNamespace",
+        security_context: "securityContext",
+        image_pull_secrets: "imagePullSecrets",
+        hostname: "hostname",
+        subdomain: "subdomain",
+        affinity: "affinity",
+        scheduler_name: "schedulerName",
+        tolerations: "tolerations",
+        host_aliases: "hostAliases",
+        priority_class_name: "priorityClassName",
+        priority: "priority",
+        dns_config: "dnsConfig",
+        readiness_gates: "readinessGates",
+        runtime_class_name: "runtimeClassName",
+        enable_service_links: "enableServiceLinks",
+        preemption_policy: "preemptionPolicy",
+        overhead: "overhead",
+        topology_spread_constraints: "topologySpreadConstraints",
+        set_hostname_as_fqdn: "setHostnameAsFQDN",
+        os: "os",
+        host_users: "hostUsers",
+        scheduling_gates: "schedulingGates",
+        resource_claims: "resourceClaims",
+        resources: "resources",
+    }
+    k8s::io::api::core::v1::PodStatus {
+        phase: "phase",
+        conditions: "conditions",
+        message: "message",
+        reason: "reason",
+        nominated_node_name: "nominatedNodeName",
+        host_ip: "hostIP",
+        host_i_ps: "hostIPs",
+        pod_ip: "podIP",
+        pod_i_ps: "podIPs",
+        start_time: "startTime",
+        init_container_statuses: "initContainerStatuses",
+        container_statuses: "containerStatuses",
+        qos_class: "qosClass",
+        ephemeral_container_statuses: "ephemeralContainerStatuses",
+        resize: "resize",
+        resource_claim_statuses: "resourceClaimStatuses",
+    }
+    k8s::io::api::core::v1::PortworxVolumeSource {
+        volume_id: "volumeID",
+        fs_type: "fsType",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::PreferredSchedulingTerm {
+        weight: "weight",
+        preference: "preference",
+    }
+    k8s::io::api::core::v1::Probe {
+        handler: (inline k8s::io::api::core::v1::ProbeHandler),
+        initial_delay_seconds: "initialDelaySeconds",
+        timeout_seconds: "timeoutSeconds",
+        period_seconds: "periodSeconds",
+        success_threshold: "successThreshold",
+        failure_threshold: "failureThreshold",
+        termination_grace_period_seconds: "terminationGracePeriodSeconds",
+    }
+    k8s::io::api::core::v1::ProbeHandler {
+        exec: "exec",
+        http_get: "httpGet",
+        tcp_socket: "tcpSocket",
+        grpc: "grpc",
+    }
+    k8s::io::api::core::v1::ProjectedVolumeSource {
+        sources: "sources",
+        default_mode: "defaultMode",
+    }
+    k8s::io::api::core::v1::QuobyteVolumeSource {
+        registry: "registry",
+        volume: "volume",
+        read_only: "readOnly",
+        user: "user",
+        group: "group",
+        tenant: "tenant",
+    }
+    k8s::io::api::core::v1::RbdVolumeSource {
+        monitors: "monitors",
+        image: "image",
+        fs_type: "fsType",
+        pool: "pool",
+        user: "user",
+        keyring: "keyring",
+        secret_ref: "secretRef",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::ResourceClaim {
+        name: "name",
+        request: "request",
+    }
+    k8s::io::api::core::v1::ResourceFieldSelector {
+        container_name: "containerName",
+        resource: "resource",
+        divisor: "divisor",
+    }
+    k8s::io::api::core::v1::ResourceHealth {
+        resource_id: "resourceID",
+        health: "health",
+    }
+    k8s::io::api::core::v1::ResourceRequirements {
+        limits: "limits",
+        requests: "requests",
+        claims: "claims",
+    }
+    k8s::io::api::core::v1::ResourceStatus {
+        name: "name",
+        resources: "resources",
+    }
+    k8s::io::api::core::v1::SeLinuxOptions {
+        user: "user",
+        role: "role",
+        r#type: "type",
+        level: "level",
+    }
+    k8s::io::api::core::v1::ScaleIoVolumeSource {
+        gateway: "gateway",
+        system: "system",
+        secret_ref: "secretRef",
+        ssl_enabled: "sslEnabled",
+        protection_domain: "protectionDomain",
+        storage_pool: "storagePool",
+        storage_mode: "storageMode",
+        volume_name: "volumeName",
+        fs_type: "fsType",
+        read_only: "readOnly",
+    }
+    k8s::io::api::core::v1::SeccompProfile {
+        r#type: "type",
+        localhost_profile: "localhostProfile",
+    }
+    k8s::io::api::core::v1::SecretEnvSource {
+        local_object_reference: (inline k8s::io::api::core::v1::LocalObjectReference),
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::SecretKeySelector {
+        local_object_reference: (inline k8s::io::api::core::v1::LocalObjectReference),
+        key: "key",
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::SecretProjection {
+        local_object_reference: (inline k8s::io::api::core::v1::LocalObjectReference),
+        items: "items",
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::SecretVolumeSource {
+        secret_name: "secretName",
+        items: "items",
+        default_mode: "defaultMode",
+        optional: "optional",
+    }
+    k8s::io::api::core::v1::SecurityContext {
+        capabilities: "capabilities",
+        privileged: "privileged",
+        se_linux_options: "seLinuxOptions",
+        windows_options: "windowsOptions",
+        run_as_user: "runAsUser",
+        run_as_group: "runAsGroup",
+        run_as_non_root: "runAsNonRoot",
+        read_only_root_filesystem: "readOnlyRootFilesystem",
+        allow_privilege_escalation: "allowPrivilegeEscalation",
+        proc_mount: "procMount",
+        seccomp_profile: "seccompProfile",
+        app_armor_profile: "appArmorProfile",
+    }
+    k8s::io::api::core::v1::ServiceAccountTokenProjection {
+        audience: "audience",
+        expiration_seconds: "expirationSeconds",
+        path: "path",
+    }
+    k8s::io::api::core::v1::SleepAction {
+        seconds: "seconds",
+    }
+    k8s::io::api::core::v1::StorageOsVolumeSource {
+        volume_name: "volumeName",
+        volume_namespace: "volumeNamespace",
+        fs_type: "fsType",
+        read_only: "readOnly",
+        secret_ref: "secretRef",
+    }
+    k8s::io::api::core::v1::Sysctl {
+        name: "name",
+        value: "value",
+    }
+    k8s::io::api::core::v1::TcpSocketAction {
+        port: "port",
+        host: "host",
+    }
+    k8s::io::api::core::v1::Taint {
+        key: "key",
+        value: "value",
+        effect: "effect",
+        time_added: "timeAdded",
+    }
+    k8s::io::api::core::v1::Toleration {
+        key: "key",
+        operator: "operator",
+        value: "value",
+        effect: "effect",
+        toleration_seconds: "tolerationSeconds",
+    }
+    k8s::io::api::core::v1::TopologySpreadConstraint {
+        max_skew: "maxSkew",
+        topology_key: "topologyKey",
+        when_unsatisfiable: "whenUnsatisfiable",
+        label_selector: "labelSelector",
+        min_domains: "minDomains",
+        node_affinity_policy: "nodeAffinityPolicy",
+        node_taints_policy: "nodeTaintsPolicy",
+        match_label_keys: "matchLabelKeys",
+    }
+    k8s::io::api::core::v1::TypedLocalObjectReference {
+        api_group: "apiGroup",
+        kind: "kind",
+        name: "name",
+    }
+    k8s::io::api::core::v1::TypedObjectReference {
+        api_group: "apiGroup",
+        kind: "kind",
+        name: "name",
+        namespace: "namespace",
+    }
+    k8s::io::api::core::v1::Volume {
+        name: "name",
+        volume_source: (inline k8s::io::api::core::v1::VolumeSource),
+    }
+    k8s::io::api::core::v1::VolumeDevice {
+        name: "name",
+        device_path: "devicePath",
+    }
+    k8s::io::api::core::v1::VolumeMount {
+        name: "name",
+        read_only: "readOnly",
+        recursive_read_only: "recursiveReadOnly",
+        mount_path: "mountPath",
+        sub_path: "subPath",
+        mount_propagation: "mountPropagation",
+        sub_path_expr: "subPathExpr",
+    }
+    k8s::io::api::core::v1::VolumeMountStatus {
+        name: "name",
+        mount_path: "mountPath",
+        read_only: "readOnly",
+        recursive_read_only: "recursiveReadOnly",
+    }
+    k8s::io::api::core::v1::VolumeProjection {
+        secret: "secret",
+        downward_api: "downwardAPI",
+        config_map: "configMap",
+        service_account_token: "serviceAccountToken",
+        cluster_trust_bundle: "clusterTrustBundle",
+    }
+    k8s::io::api::core::v1::VolumeResourceRequirements {
+        limits: "limits",
+        requests: "requests",
+    }
+    k8s::io::api::core::v1::VolumeSource {
+        host_path: "hostPath",
+        empty_dir: "emptyDir",
+        gce_persistent_disk: "gcePersistentDisk",
+        aws_elastic_block_store: "awsElasticBlockStore",
+        git_repo: "gitRepo",
+        secret: "secret",
+        nfs: "nfs",
+        iscsi: "iscsi",
+        glusterfs: "glusterfs",
+        persistent_volume_claim: "persistentVolumeClaim",
+        rbd: "rbd",
+        flex_volume: "flexVolume",
+        cinder: "cinder",
+        cephfs: "cephfs",
+        flocker: "flocker",
+        downward_api: "downwardAPI",
+        fc: "fc",
+        azure_file: "azureFile",
+        config_map: "configMap",
+        vsphere_volume: "vsphereVolume",
+        quobyte: "quobyte",
+        azure_disk: "azureDisk",
+        photon_persistent_disk: "photonPersistentDisk",
+        projected: "projected",
+        portworx_volume: "portworxVolume",
+        scale_io: "scaleIO",
+        storageos: "storageos",
+        csi: "csi",
+        ephemeral: "ephemeral",
+        image: "image",
+    }
+    k8s::io::api::core::v1::VsphereVirtualDiskVolumeSource {
+        volume_path: "volumePath",
+        fs_type: "fsType",
+        storage_policy_name: "storagePolicyName",
+        storage_policy_id: "storagePolicyID",
+    }
+    k8s::io::api::core::v1::WeightedPodAffinityTerm {
+        weight: "weight",
+        pod_affinity_term: "podAffinityTerm",
+    }
+    k8s::io::api::core::v1::WindowsSecurityContextOptions {
+        gmsa_credential_spec_name: "gmsaCredentialSpecName",
+        gmsa_credential_spec: "gmsaCredentialSpec",
+        run_as_user_name: "runAsUserName",
+        host_process: "hostProcess",
+    }
+    k8s::io::api::discovery::v1::Endpoint {
+        addresses: "addresses",
+        conditions: "conditions",
+        hostname: "hostname",
+        target_ref: "targetRef",
+        deprecated_topology: "deprecatedTopology",
+        node_name: "nodeName",
+        zone: "zone",
+        hints: "hints",
+    }
+    k8s::io::api::discovery::v1::EndpointConditions {
+        ready: "ready",
+        serving: "serving",
+        terminating: "terminating",
+    }
+    k8s::io::api::discovery::v1::EndpointHints {
+        for_zones: "forZones",
+    }
+    k8s::io::api::discovery::v1::EndpointPort {
+        name: "name",
+        protocol: "protocol",
+        port: "port",
+        app_protocol: "appProtocol",
+    }
+    k8s::io::api::discovery::v1::EndpointSlice {
+        metadata: "metadata",
+        address_type: "addressType",
+        endpoints: "endpoints",
+        ports: "ports",
+    }
+    k8s::io::api::discovery::v1::EndpointSliceList {
+        metadata: "metadata",
+        items: "items",
+    }
+    k8s::io::api::discovery::v1::ForZone {
+        name: "name",
+    }
+}
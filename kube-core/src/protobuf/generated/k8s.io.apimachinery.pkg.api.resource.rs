@@ -0,0 +1,6 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct Quantity {
+    #[prost(string, optional, tag = "1")]
+    pub string: ::core::option::Option<::prost::alloc::string::String>,
+}
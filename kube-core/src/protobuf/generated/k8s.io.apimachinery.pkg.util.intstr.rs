@@ -0,0 +1,10 @@
+// This file is @generated by prost-build.
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct IntOrString {
+    #[prost(int64, optional, tag = "1")]
+    pub r#type: ::core::option::Option<i64>,
+    #[prost(int32, optional, tag = "2")]
+    pub int_val: ::core::option::Option<i32>,
+    #[prost(string, optional, tag = "3")]
+    pub str_val: ::core::option::Option<::prost::alloc::string::String>,
+}
@@ -0,0 +1,41 @@
+// This file is @generated by prost-build.
+pub mod k8s {
+    pub mod io {
+        pub mod api {
+            pub mod core {
+                pub mod v1 {
+                    include!("k8s.io.api.core.v1.rs");
+                }
+            }
+            pub mod discovery {
+                pub mod v1 {
+                    include!("k8s.io.api.discovery.v1.rs");
+                }
+            }
+        }
+        pub mod apimachinery {
+            pub mod pkg {
+                pub mod api {
+                    pub mod resource {
+                        include!("k8s.io.apimachinery.pkg.api.resource.rs");
+                    }
+                }
+                pub mod apis {
+                    pub mod meta {
+                        pub mod v1 {
+                            include!("k8s.io.apimachinery.pkg.apis.meta.v1.rs");
+                        }
+                    }
+                }
+                pub mod runtime {
+                    include!("k8s.io.apimachinery.pkg.runtime.rs");
+                }
+                pub mod util {
+                    pub mod intstr {
+                        include!("k8s.io.apimachinery.pkg.util.intstr.rs");
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,332 @@
+//! Decoding of the Kubernetes protobuf wire format
+//!
+//! The apiserver serves built-in resources as `application/vnd.kubernetes.protobuf` when asked to,
+//! which is cheaper to decode than JSON for large objects like Pods and Nodes.
+//! Every response body is an envelope made of the `k8s\0` magic prefix followed by a `runtime.Unknown`
+//! message, whose `raw` field holds the encoded object. Watch responses are a stream of length-prefixed
+//! `meta.v1.WatchEvent` messages, whose objects are enveloped in the same way.
+//!
+//! The messages are decoded with [`prost`], from the subset of the upstream `generated.proto` files
+//! in `kube-core/protos`, and deserialized as if they were JSON. Fields that are missing from this subset
+//! are dropped, so the protobuf encoding is only meant for types that do not keep unknown fields,
+//! like the `k8s-openapi` structs of the kinds in [`is_supported_path`]. Types like
+//! [`DynamicObject`](crate::DynamicObject) should be read as JSON.
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use crate::watch::WatchEvent;
+
+mod de;
+#[allow(missing_docs, clippy::all, clippy::pedantic)] mod generated;
+
+/// Media type of the Kubernetes protobuf encoding
+pub const PROTOBUF_MIME: &str = "application/vnd.kubernetes.protobuf";
+
+/// Accept header preferring protobuf, with a fallback to JSON for resources that are not served as protobuf
+pub const ACCEPT_PROTOBUF: &str = "application/vnd.kubernetes.protobuf,application/json";
+
+/// Media type of protobuf watch streams
+pub const PROTOBUF_WATCH_MIME: &str = "application/vnd.kubernetes.protobuf;stream=watch";
+
+/// Prefix of every enveloped protobuf object
+pub const MAGIC: &[u8; 4] = b"k8s\0";
+
+/// The resources that can be decoded, as `(group, plural)`
+const RESOURCES: &[(&str, &str)] = &[
+    ("", "pods"),
+    ("", "nodes"),
+    ("", "endpoints"),
+    ("discovery.k8s.io", "endpointslices"),
+];
+
+/// Possible errors when decoding protobuf messages
+#[derive(Debug, Error)]
+pub enum DecodeError {
+    /// The envelope did not start with the `k8s\0` prefix
+    #[error("missing k8s protobuf magic prefix")]
+    MissingMagic,
+    /// A message was not valid protobuf
+    #[error("invalid protobuf message: {0}")]
+    Invalid(#[source] prost::DecodeError),
+    /// The enveloped object was compressed
+    #[error("unsupported content encoding {0:?} of protobuf object")]
+    UnsupportedEncoding(String),
+    /// The enveloped object was neither protobuf nor JSON
+    #[error("unsupported content type {0:?} of protobuf object")]
+    UnsupportedContentType(String),
+    /// The enveloped object was of a kind that cannot be decoded
+    #[error("unsupported kind {kind} in {api_version} of protobuf object")]
+    UnsupportedKind {
+        /// apiVersion of the object
+        api_version: String,
+        /// kind of the object
+        kind: String,
+    },
+    /// A JSON encoded part of the object was invalid
+    #[error("invalid json in protobuf object: {0}")]
+    Json(#[source] serde_json::Error),
+    /// The decoded object did not match the requested type
+    #[error("failed to deserialize protobuf object: {0}")]
+    Deserialize(String),
+}
+
+impl serde::de::Error for DecodeError {
+    fn custom<T: std::fmt::Display>(msg: T) -> Self {
+        Self::Deserialize(msg.to_string())
+    }
+}
+
+/// Deserialize an enveloped object, including its `k8s\0` prefix
+///
+/// Lists and `Status` errors of the supported kinds are decoded as well.
+pub fn from_slice<T: DeserializeOwned>(buf: &[u8]) -> Result<T, DecodeError> {
+    T::deserialize(de::Envelope::decode(buf)?)
+}
+
+/// Deserialize an event of a protobuf watch stream, without its length prefix
+pub fn watch_event_from_slice<K: DeserializeOwned>(buf: &[u8]) -> Result<WatchEvent<K>, DecodeError> {
+    serde::Deserialize::deserialize(serde::de::value::MapAccessDeserializer::new(
+        de::WatchEventAccess::decode(buf)?,
+    ))
+}
+
+/// Whether the resources at a request path can be decoded from protobuf
+///
+/// These are Pods, Nodes, Endpoints and EndpointSlices, and their `status` subresources.
+pub fn is_supported_path(path: &str) -> bool {
+    let segments = path.trim_matches('/').split('/').collect::<Vec<_>>();
+    let (group, rest) = match segments.as_slice() {
+        ["api", "v1", rest @ ..] => ("", rest),
+        ["apis", group, "v1", rest @ ..] => (*group, rest),
+        _ => return false,
+    };
+    let rest = match rest {
+        ["namespaces", _, rest @ ..] if !rest.is_empty() => rest,
+        rest => rest,
+    };
+    match rest {
+        [resource] | [resource, _] | [resource, _, "status"] => RESOURCES.contains(&(group, *resource)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        generated::k8s::io::{
+            api::core::v1 as core,
+            apimachinery::pkg::{api::resource, apis::meta::v1 as meta, runtime, util::intstr},
+        },
+        *,
+    };
+    use crate::{object::ObjectList, ErrorResponse};
+    use k8s_openapi::api::core::v1::Pod;
+    use prost::Message;
+
+    fn envelope(api_version: &str, kind: &str, raw: Vec<u8>) -> Vec<u8> {
+        let unknown = runtime::Unknown {
+            type_meta: Some(runtime::TypeMeta {
+                api_version: Some(api_version.into()),
+                kind: Some(kind.into()),
+            }),
+            raw: Some(raw),
+            content_encoding: Some(String::new()),
+            content_type: Some(String::new()),
+        };
+        [MAGIC.as_slice(), &unknown.encode_to_vec()].concat()
+    }
+
+    fn pod(name: &str) -> core::Pod {
+        let container = core::Container {
+            name: Some("app".into()),
+            image: Some("nginx".into()),
+            resources: Some(core::ResourceRequirements {
+                limits: [("cpu".to_owned(), resource::Quantity {
+                    string: Some("500m".into()),
+                })]
+                .into(),
+                ..Default::default()
+            }),
+            readiness_probe: Some(core::Probe {
+                handler: Some(core::ProbeHandler {
+                    http_get: Some(core::HttpGetAction {
+                        port: Some(intstr::IntOrString {
+                            r#type: Some(1),
+                            int_val: Some(0),
+                            str_val: Some("http".into()),
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                period_seconds: Some(5),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+        core::Pod {
+            metadata: Some(meta::ObjectMeta {
+                name: Some(name.into()),
+                namespace: Some("default".into()),
+                // empty strings are always encoded, and left out of JSON
+                generate_name: Some(String::new()),
+                creation_timestamp: Some(meta::Time {
+                    seconds: Some(1_700_000_000),
+                    nanos: Some(0),
+                }),
+                // the zero time is an empty message
+                deletion_timestamp: Some(meta::Time::default()),
+                labels: [("app".to_owned(), "web".to_owned())].into(),
+                managed_fields: vec![meta::ManagedFieldsEntry {
+                    manager: Some("kubectl".into()),
+                    fields_v1: Some(meta::FieldsV1 {
+                        raw: Some(br#"{"f:metadata":{}}"#.to_vec()),
+                    }),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }),
+            spec: Some(core::PodSpec {
+                containers: vec![container],
+                volumes: vec![core::Volume {
+                    name: Some("config".into()),
+                    volume_source: Some(core::VolumeSource {
+                        config_map: Some(core::ConfigMapVolumeSource {
+                            local_object_reference: Some(core::LocalObjectReference {
+                                name: Some("settings".into()),
+                            }),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                }],
+                host_network: Some(false),
+                ..Default::default()
+            }),
+            status: Some(core::PodStatus {
+                phase: Some("Running".into()),
+                ..Default::default()
+            }),
+        }
+    }
+
+    #[test]
+    fn decodes_objects_like_json() {
+        let buf = envelope("v1", "Pod", pod("web-0").encode_to_vec());
+        let decoded: Pod = from_slice(&buf).unwrap();
+        let expected: Pod = serde_json::from_value(serde_json::json!({
+            "apiVersion": "v1",
+            "kind": "Pod",
+            "metadata": {
+                "name": "web-0",
+                "namespace": "default",
+                "creationTimestamp": "2023-11-14T22:13:20Z",
+                "labels": { "app": "web" },
+                "managedFields": [{ "manager": "kubectl", "fieldsV1": { "f:metadata": {} } }],
+            },
+            "spec": {
+                "containers": [{
+                    "name": "app",
+                    "image": "nginx",
+                    "resources": { "limits": { "cpu": "500m" } },
+                    "readinessProbe": { "httpGet": { "port": "http" }, "periodSeconds": 5 },
+                }],
+                "volumes": [{ "name": "config", "configMap": { "name": "settings" } }],
+                "hostNetwork": false,
+            },
+            "status": { "phase": "Running" },
+        }))
+        .unwrap();
+        assert_eq!(decoded, expected);
+    }
+
+    #[test]
+    fn decodes_lists_and_status() {
+        let list = core::PodList {
+            metadata: Some(meta::ListMeta {
+                resource_version: Some("42".into()),
+                r#continue: Some("next".into()),
+                ..Default::default()
+            }),
+            items: vec![pod("web-0"), pod("web-1")],
+        };
+        let buf = envelope("v1", "PodList", list.encode_to_vec());
+        let list: ObjectList<Pod> = from_slice(&buf).unwrap();
+        assert_eq!(list.types.kind, "PodList");
+        assert_eq!(list.metadata.continue_.as_deref(), Some("next"));
+        let names = list
+            .items
+            .iter()
+            .map(|p| p.metadata.name.clone().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["web-0", "web-1"]);
+
+        let status = meta::Status {
+            status: Some("Failure".into()),
+            message: Some("pods \"web-2\" not found".into()),
+            reason: Some("NotFound".into()),
+            code: Some(404),
+            ..Default::default()
+        };
+        let buf = envelope("v1", "Status", status.encode_to_vec());
+        let status: ErrorResponse = from_slice(&buf).unwrap();
+        assert_eq!(status.code, 404);
+        assert_eq!(status.reason, "NotFound");
+    }
+
+    #[test]
+    fn decodes_watch_events() {
+        let event = meta::WatchEvent {
+            r#type: Some("MODIFIED".into()),
+            object: Some(runtime::RawExtension {
+                raw: Some(envelope("v1", "Pod", pod("web-0").encode_to_vec())),
+            }),
+        };
+        match watch_event_from_slice::<Pod>(&event.encode_to_vec()).unwrap() {
+            WatchEvent::Modified(pod) => assert_eq!(pod.metadata.name.as_deref(), Some("web-0")),
+            event => panic!("unexpected {event:?}"),
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported_envelopes() {
+        let mut unknown = runtime::Unknown::decode(&envelope("v1", "Pod", vec![])[4..]).unwrap();
+        unknown.content_encoding = Some("gzip".into());
+        let buf = [MAGIC.as_slice(), &unknown.encode_to_vec()].concat();
+        assert!(matches!(
+            from_slice::<Pod>(&buf),
+            Err(DecodeError::UnsupportedEncoding(encoding)) if encoding == "gzip"
+        ));
+
+        let buf = envelope("v1", "ConfigMap", vec![]);
+        assert!(matches!(
+            from_slice::<Pod>(&buf),
+            Err(DecodeError::UnsupportedKind { kind, .. }) if kind == "ConfigMap"
+        ));
+        assert!(matches!(from_slice::<Pod>(b"{}"), Err(DecodeError::MissingMagic)));
+    }
+
+    #[test]
+    fn supported_paths() {
+        for path in [
+            "/api/v1/pods",
+            "/api/v1/namespaces/default/pods",
+            "/api/v1/namespaces/default/pods/web-0",
+            "/api/v1/namespaces/default/pods/web-0/status",
+            "/api/v1/nodes/node-1",
+            "/api/v1/namespaces/default/endpoints",
+            "/apis/discovery.k8s.io/v1/endpointslices",
+        ] {
+            assert!(is_supported_path(path), "{path}");
+        }
+        for path in [
+            "/api/v1/namespaces/default",
+            "/api/v1/namespaces/default/configmaps",
+            "/api/v1/namespaces/default/pods/web-0/log",
+            "/apis/apps/v1/namespaces/default/deployments",
+            "/apis/discovery.k8s.io/v1beta1/endpointslices",
+        ] {
+            assert!(!is_supported_path(path), "{path}");
+        }
+    }
+}
@@ -18,6 +18,11 @@ pub(crate) const JSON_METADATA_MIME: &str = "application/json;as=PartialObjectMe
 pub(crate) const JSON_METADATA_LIST_MIME: &str =
     "application/json;as=PartialObjectMetadataList;g=meta.k8s.io;v=v1";
 
+/// Extended Accept Header
+///
+/// Requests a meta.k8s.io/v1 Table containing the columns that `kubectl get` prints
+pub(crate) const JSON_TABLE_MIME: &str = "application/json;as=Table;g=meta.k8s.io;v=v1";
+
 /// Possible errors when building a request.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -334,6 +339,32 @@ impl Request {
     }
 }
 
+/// Table request implementations
+///
+/// Requests set an extended Accept header that asks the apiserver to render
+/// the resources as a [`Table`](crate::Table) for server-side printing.
+impl Request {
+    /// Get a single named resource as a table
+    pub fn get_table(&self, name: &str, gp: &GetParams) -> Result<http::Request<Vec<u8>>, Error> {
+        let mut req = self.get(name, gp)?;
+        req.headers_mut().insert(
+            http::header::ACCEPT,
+            http::HeaderValue::from_static(JSON_TABLE_MIME),
+        );
+        Ok(req)
+    }
+
+    /// List a collection of a resource as a table
+    pub fn list_table(&self, lp: &ListParams) -> Result<http::Request<Vec<u8>>, Error> {
+        let mut req = self.list(lp)?;
+        req.headers_mut().insert(
+            http::header::ACCEPT,
+            http::HeaderValue::from_static(JSON_TABLE_MIME),
+        );
+        Ok(req)
+    }
+}
+
 /// Names must not be empty as otherwise API server would interpret a `get` as `list`, or a `delete` as `delete_collection`
 fn validate_name(name: &str) -> Result<(), Error> {
     if name.is_empty() {
@@ -538,6 +569,29 @@ mod test {
         );
     }
     #[test]
+    fn list_table_path() {
+        let url = corev1::Pod::url_path(&(), Some("ns"));
+        let lp = ListParams::default().labels("app=blog").limit(10);
+        let req = Request::new(url).list_table(&lp).unwrap();
+        assert_eq!(
+            req.uri(),
+            "/api/v1/namespaces/ns/pods?&labelSelector=app%3Dblog&limit=10"
+        );
+        assert_eq!(req.headers().get(header::ACCEPT).unwrap(), super::JSON_TABLE_MIME);
+    }
+    #[test]
+    fn get_table_path() {
+        let url = corev1::Pod::url_path(&(), Some("ns"));
+        let req = Request::new(url)
+            .get_table("mypod", &GetParams::default())
+            .unwrap();
+        assert_eq!(req.uri(), "/api/v1/namespaces/ns/pods/mypod");
+        assert_eq!(req.headers().get(header::ACCEPT).unwrap(), super::JSON_TABLE_MIME);
+        assert!(Request::new("/api/v1/pods")
+            .get_table("", &GetParams::default())
+            .is_err());
+    }
+    #[test]
     fn watch_path() {
         let url = corev1::Pod::url_path(&(), Some("ns"));
         let wp = WatchParams::default();
@@ -7,6 +7,10 @@ use crate::{
 };
 
 pub use k8s_openapi::api::autoscaling::v1::{Scale, ScaleSpec, ScaleStatus};
+use k8s_openapi::{
+    api::core::v1::{Binding, ObjectReference},
+    apimachinery::pkg::apis::meta::v1::ObjectMeta,
+};
 
 // ----------------------------------------------------------------------------
 // Log subresource
@@ -123,6 +127,36 @@ impl Request {
     }
 }
 
+// ----------------------------------------------------------------------------
+// Binding subresource
+// ----------------------------------------------------------------------------
+
+impl Request {
+    /// Bind a pod to a node
+    pub fn bind(&self, name: &str, node: &str, pp: &PostParams) -> Result<http::Request<Vec<u8>>, Error> {
+        let target = format!("{}/{}/binding?", self.url_path, name);
+        pp.validate()?;
+        let mut qp = form_urlencoded::Serializer::new(target);
+        pp.populate_qp(&mut qp);
+        let urlstr = qp.finish();
+        let binding = Binding {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                ..ObjectMeta::default()
+            },
+            target: ObjectReference {
+                api_version: Some("v1".into()),
+                kind: Some("Node".into()),
+                name: Some(node.to_string()),
+                ..ObjectReference::default()
+            },
+        };
+        let data = serde_json::to_vec(&binding).map_err(Error::SerializeBody)?;
+        let req = http::Request::post(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
+        req.body(data).map_err(Error::BuildRequest)
+    }
+}
+
 // ----------------------------------------------------------------------------
 // Attach subresource
 // ----------------------------------------------------------------------------
@@ -172,6 +206,17 @@ pub struct AttachParams {
     ///
     /// This is not sent to the server.
     pub max_stderr_buf_size: Option<usize>,
+    /// Data written to `stdin` before closing it, when collecting the output of a command
+    /// with [`Api::exec_output`](https://docs.rs/kube/*/kube/struct.Api.html#method.exec_output).
+    ///
+    /// This is not sent to the server.
+    pub stdin_data: Option<Vec<u8>>,
+    /// The maximum duration of a command
+    /// run with [`Api::exec_output`](https://docs.rs/kube/*/kube/struct.Api.html#method.exec_output).
+    /// Defaults to no timeout.
+    ///
+    /// This is not sent to the server.
+    pub timeout: Option<std::time::Duration>,
 }
 
 #[cfg(feature = "ws")]
@@ -188,6 +233,8 @@ impl Default for AttachParams {
             max_stdin_buf_size: None,
             max_stdout_buf_size: None,
             max_stderr_buf_size: None,
+            stdin_data: None,
+            timeout: None,
         }
     }
 }
@@ -263,6 +310,21 @@ impl AttachParams {
         self
     }
 
+    /// Set `stdin_data` field, and attach to `stdin`.
+    #[must_use]
+    pub fn stdin_data(mut self, data: impl Into<Vec<u8>>) -> Self {
+        self.stdin = true;
+        self.stdin_data = Some(data.into());
+        self
+    }
+
+    /// Set `timeout` field.
+    #[must_use]
+    pub fn timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
     pub(crate) fn validate(&self) -> Result<(), Error> {
         if !self.stdin && !self.stdout && !self.stderr {
             return Err(Error::Validation(
@@ -405,6 +467,31 @@ impl Request {
     }
 }
 
+// ----------------------------------------------------------------------------
+// Proxy subresource
+// ----------------------------------------------------------------------------
+
+impl Request {
+    /// Proxy a request to a port of an object through the apiserver
+    ///
+    /// The path and query of `req` are appended to the proxy url, e.g. `/metrics` is sent to
+    /// `/api/v1/namespaces/{ns}/pods/{name}:{port}/proxy/metrics`. The method, headers and body are kept.
+    pub fn proxy<B>(&self, name: &str, port: u16, req: http::Request<B>) -> Result<http::Request<B>, Error> {
+        let (mut parts, body) = req.into_parts();
+        let path_and_query = parts.uri.path_and_query().map_or("/", |pq| pq.as_str());
+        let path_and_query = if path_and_query.starts_with('/') {
+            path_and_query.to_string()
+        } else {
+            format!("/{path_and_query}")
+        };
+        let urlstr = format!("{}/{}:{}/proxy{}", self.url_path, name, port, path_and_query);
+        parts.uri = urlstr
+            .parse()
+            .map_err(|err| Error::BuildRequest(http::Error::from(err)))?;
+        Ok(http::Request::from_parts(parts, body))
+    }
+}
+
 // ----------------------------------------------------------------------------
 // tests
 // ----------------------------------------------------------------------------
@@ -419,6 +506,47 @@ mod test {
 
     use crate::subresource::LogParams;
 
+    #[test]
+    fn bind_body() {
+        let url = corev1::Pod::url_path(&(), Some("ns"));
+        let req = Request::new(url)
+            .bind("mypod", "node-1", &Default::default())
+            .unwrap();
+        assert_eq!(req.uri(), "/api/v1/namespaces/ns/pods/mypod/binding?");
+        assert_eq!(req.method(), http::Method::POST);
+        let body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
+        assert_eq!(
+            body,
+            serde_json::json!({
+                "apiVersion": "v1",
+                "kind": "Binding",
+                "metadata": { "name": "mypod" },
+                "target": { "apiVersion": "v1", "kind": "Node", "name": "node-1" }
+            })
+        );
+    }
+
+    #[test]
+    fn proxy_keeps_path_and_query() {
+        let url = corev1::Pod::url_path(&(), Some("ns"));
+        let req = http::Request::post("/metrics?format=text")
+            .header("x-test", "1")
+            .body(vec![1])
+            .unwrap();
+        let req = Request::new(url).proxy("mypod", 8080, req).unwrap();
+        assert_eq!(
+            req.uri(),
+            "/api/v1/namespaces/ns/pods/mypod:8080/proxy/metrics?format=text"
+        );
+        assert_eq!(req.method(), http::Method::POST);
+        assert_eq!(req.headers()["x-test"], "1");
+
+        let url = corev1::Node::url_path(&(), None);
+        let req = http::Request::get("/").body(()).unwrap();
+        let req = Request::new(url).proxy("node-1", 10250, req).unwrap();
+        assert_eq!(req.uri(), "/api/v1/nodes/node-1:10250/proxy/");
+    }
+
     #[test]
     fn logs_all_params() {
         let url = corev1::Pod::url_path(&(), Some("ns"));
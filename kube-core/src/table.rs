@@ -0,0 +1,143 @@
+//! Types for server-side printing via the `meta.k8s.io/v1` Table api
+//!
+//! Requests made with the [`Request::list_table`](crate::Request::list_table) or
+//! [`Request::get_table`](crate::Request::get_table) methods return a [`Table`] containing
+//! the same columns that `kubectl get` prints, including `additionalPrinterColumns` of CRDs.
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::metadata::{ListMeta, TypeMeta};
+
+/// A tabular representation of a set of api resources
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Table {
+    /// The type fields, always `meta.k8s.io/v1` `Table`
+    #[serde(flatten, default)]
+    pub types: TypeMeta,
+
+    /// Standard list metadata
+    #[serde(default)]
+    pub metadata: ListMeta,
+
+    /// Describes each column returned in the [`TableRow::cells`]
+    #[serde(default)]
+    pub column_definitions: Vec<TableColumnDefinition>,
+
+    /// The rows of the table, one per resource
+    #[serde(default)]
+    pub rows: Vec<TableRow>,
+}
+
+impl Table {
+    /// Get the position of a named column in the [`TableRow::cells`]
+    pub fn column_index(&self, name: &str) -> Option<usize> {
+        self.column_definitions.iter().position(|c| c.name == name)
+    }
+}
+
+/// Describes a column in a [`Table`]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableColumnDefinition {
+    /// Human readable name of the column
+    pub name: String,
+
+    /// An OpenAPI type definition for this column, such as `number`, `integer`, `string` or `array`
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// An optional OpenAPI type modifier for this column, such as `name` or `date-time`
+    #[serde(default)]
+    pub format: String,
+
+    /// Human readable description of this column
+    #[serde(default)]
+    pub description: String,
+
+    /// Relative importance of this column
+    ///
+    /// Columns with a priority greater than 0 are only shown by kubectl in wide output.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// A single row of a [`Table`]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRow {
+    /// The cells of the row, in the same order as the [`Table::column_definitions`]
+    ///
+    /// Cells may be strings, numbers, booleans, simple maps or lists, or null.
+    #[serde(default)]
+    pub cells: Vec<Value>,
+
+    /// Conditions describing the state of the row
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub conditions: Option<Vec<TableRowCondition>>,
+
+    /// The object this row represents
+    ///
+    /// By default the apiserver includes the `PartialObjectMetadata` of the object.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object: Option<Value>,
+}
+
+/// A condition that applies to a [`TableRow`]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRowCondition {
+    /// Type of the condition, currently only `Completed`
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// Status of the condition, one of `True`, `False` or `Unknown`
+    pub status: String,
+
+    /// Machine readable reason for the last transition of the condition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+
+    /// Human readable message about the last transition of the condition
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::Table;
+
+    #[test]
+    fn deserializes_pod_table() {
+        let table: Table = serde_json::from_value(serde_json::json!({
+            "kind": "Table",
+            "apiVersion": "meta.k8s.io/v1",
+            "metadata": { "resourceVersion": "1234" },
+            "columnDefinitions": [
+                { "name": "Name", "type": "string", "format": "name", "description": "Name must be unique", "priority": 0 },
+                { "name": "Ready", "type": "string", "format": "", "description": "The aggregate readiness state", "priority": 0 },
+                { "name": "IP", "type": "string", "format": "", "description": "IP address", "priority": 1 }
+            ],
+            "rows": [{
+                "cells": ["blog-0", "1/1", "10.0.0.1"],
+                "object": {
+                    "kind": "PartialObjectMetadata",
+                    "apiVersion": "meta.k8s.io/v1",
+                    "metadata": { "name": "blog-0", "namespace": "apps" }
+                }
+            }]
+        }))
+        .unwrap();
+        assert_eq!(table.types.kind, "Table");
+        assert_eq!(table.metadata.resource_version.as_deref(), Some("1234"));
+        assert_eq!(table.column_definitions[0].format, "name");
+        assert_eq!(table.column_definitions[2].priority, 1);
+        let ip = table.column_index("IP").unwrap();
+        assert_eq!(table.rows[0].cells[ip], "10.0.0.1");
+        assert_eq!(
+            table.rows[0].object.as_ref().unwrap()["metadata"]["name"],
+            "blog-0"
+        );
+        assert!(table.rows[0].conditions.is_none());
+    }
+}
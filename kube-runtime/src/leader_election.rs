@@ -0,0 +1,448 @@
+//! Leader election on `coordination.k8s.io` [`Lease`] objects
+//!
+//! Allows running multiple replicas of a controller where only one of them (the leader) is actively reconciling,
+//! following the same [`Lease`] protocol as `client-go`'s `leaderelection` package.
+//!
+//! ## Usage
+//!
+//! Wait for leadership before starting the [`Controller`](crate::Controller), and shut it down
+//! gracefully once leadership has been lost:
+//!
+//! ```no_run
+//! use kube::{Api, Client, ResourceExt, runtime::{controller::Action, Controller, watcher}};
+//! use kube::runtime::leader_election::{LeaseLock, LeaseLockParams};
+//! use k8s_openapi::api::core::v1::ConfigMap;
+//! use std::{convert::Infallible, sync::Arc};
+//! # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+//! # let client: Client = todo!();
+//! let holder_id = std::env::var("POD_NAME")?;
+//! let lock = LeaseLock::new(client.clone(), "default", "my-controller", LeaseLockParams::new(holder_id));
+//! let mut leadership = lock.spawn();
+//! leadership.acquired().await;
+//!
+//! Controller::new(Api::<ConfigMap>::all(client), watcher::Config::default())
+//!     .graceful_shutdown_on(leadership.lost())
+//!     .run(
+//!         |o, _| async move {
+//!             println!("Reconciling {}", o.name_any());
+//!             Ok(Action::await_change())
+//!         },
+//!         |_, err: &Infallible, _| Err(err).unwrap(),
+//!         Arc::new(()),
+//!     );
+//! // ..poll the controller stream to completion..
+//!
+//! // give up the lease so that another replica can take over immediately
+//! leadership.release().await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::utils::CancelableJoinHandle;
+use futures::{Future, Stream};
+use k8s_openapi::{
+    api::coordination::v1::{Lease, LeaseSpec},
+    apimachinery::pkg::apis::meta::v1::MicroTime,
+    chrono::{DateTime, Utc},
+};
+use kube_client::{
+    api::{Api, ObjectMeta, PostParams},
+    Client,
+};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::{runtime::Handle, sync::watch};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to get lease: {0}")]
+    GetLease(#[source] kube_client::Error),
+    #[error("failed to create lease: {0}")]
+    CreateLease(#[source] kube_client::Error),
+    #[error("failed to update lease: {0}")]
+    UpdateLease(#[source] kube_client::Error),
+    #[error("lease was changed by someone else while renewing it")]
+    RenewConflict,
+    #[error("timed out while trying to acquire or renew lease")]
+    Timeout,
+}
+
+/// The leadership state of a [`LeaseLock`] holder
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LeaderState {
+    /// This instance holds the lease, and is expected to be doing the work
+    Leading,
+    /// Another instance (or nobody, if `leader` is `None`) holds the lease
+    Following {
+        /// The holder identity of the current leader, if known
+        leader: Option<String>,
+    },
+}
+
+impl LeaderState {
+    /// Whether this instance is currently the leader
+    #[must_use]
+    pub fn is_leading(&self) -> bool {
+        matches!(self, Self::Leading)
+    }
+}
+
+/// Parameters for taking part in a [`LeaseLock`] election
+///
+/// The defaults mirror those used by the core Kubernetes controllers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LeaseLockParams {
+    /// The identity of this candidate
+    ///
+    /// This must be unique between all candidates, and is typically the name of the pod.
+    pub holder_id: String,
+
+    /// How long non-leaders wait after the last renewal before forcefully taking over the lease
+    ///
+    /// Defaults to 15s.
+    pub lease_duration: Duration,
+
+    /// How long the leader keeps retrying to renew the lease before giving up leadership
+    ///
+    /// This must be shorter than `lease_duration`. Defaults to 10s.
+    pub renew_deadline: Duration,
+
+    /// How long to wait between attempts to acquire or renew the lease
+    ///
+    /// Defaults to 2s.
+    pub retry_period: Duration,
+}
+
+impl LeaseLockParams {
+    /// Parameters for the candidate identified by `holder_id` with default timings
+    pub fn new(holder_id: impl Into<String>) -> Self {
+        Self {
+            holder_id: holder_id.into(),
+            lease_duration: Duration::from_secs(15),
+            renew_deadline: Duration::from_secs(10),
+            retry_period: Duration::from_secs(2),
+        }
+    }
+
+    /// Sets how long non-leaders wait before taking over a lease that is no longer renewed
+    #[must_use]
+    pub fn lease_duration(mut self, lease_duration: Duration) -> Self {
+        self.lease_duration = lease_duration;
+        self
+    }
+
+    /// Sets how long the leader keeps retrying renewals before giving up leadership
+    #[must_use]
+    pub fn renew_deadline(mut self, renew_deadline: Duration) -> Self {
+        self.renew_deadline = renew_deadline;
+        self
+    }
+
+    /// Sets the interval between acquire and renew attempts
+    #[must_use]
+    pub fn retry_period(mut self, retry_period: Duration) -> Self {
+        self.retry_period = retry_period;
+        self
+    }
+}
+
+/// A distributed lock backed by a [`Lease`] object
+///
+/// Expiry of another candidate's lease is judged against the local clock,
+/// so clocks between candidates are assumed to be reasonably synchronized.
+#[derive(Clone, Debug)]
+pub struct LeaseLock {
+    api: Api<Lease>,
+    lease_name: String,
+    params: LeaseLockParams,
+}
+
+impl LeaseLock {
+    /// Create a lock for the [`Lease`] called `lease_name` in `namespace`
+    ///
+    /// The [`Lease`] is created on the first acquisition attempt if it does not exist.
+    #[must_use]
+    pub fn new(
+        client: Client,
+        namespace: &str,
+        lease_name: impl Into<String>,
+        params: LeaseLockParams,
+    ) -> Self {
+        Self {
+            api: Api::namespaced(client, namespace),
+            lease_name: lease_name.into(),
+            params,
+        }
+    }
+
+    /// Make a single attempt to acquire the lease, or renew it if we are already holding it
+    ///
+    /// Returns the resulting [`LeaderState`]. Losing a race against another candidate is not considered an error.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the [`Lease`] cannot be read or written, or with [`Error::RenewConflict`] if someone else changed
+    /// the lease while we were renewing it, but we still hold it.
+    pub async fn try_acquire_or_renew(&self) -> Result<LeaderState, Error> {
+        let now = Utc::now();
+        let Some(lease) = self
+            .api
+            .get_opt(&self.lease_name)
+            .await
+            .map_err(Error::GetLease)?
+        else {
+            let lease = Lease {
+                metadata: ObjectMeta {
+                    name: Some(self.lease_name.clone()),
+                    ..ObjectMeta::default()
+                },
+                spec: Some(self.spec(now, now, 0)),
+            };
+            return match self.api.create(&PostParams::default(), &lease).await {
+                Ok(_) => Ok(LeaderState::Leading),
+                // Someone else created it first, we'll find out who on the next attempt
+                Err(kube_client::Error::Api(err)) if err.code == 409 => {
+                    Ok(LeaderState::Following { leader: None })
+                }
+                Err(err) => Err(Error::CreateLease(err)),
+            };
+        };
+
+        let spec = lease.spec.clone().unwrap_or_default();
+        let leader = spec.holder_identity.clone().filter(|holder| !holder.is_empty());
+        let held_by_us = leader.as_deref() == Some(self.params.holder_id.as_str());
+        if leader.is_some() && !held_by_us && !is_expired(&spec, now) {
+            return Ok(LeaderState::Following { leader });
+        }
+
+        let transitions = spec.lease_transitions.unwrap_or_default();
+        let spec = if held_by_us {
+            self.spec(spec.acquire_time.map_or(now, |t| t.0), now, transitions)
+        } else {
+            self.spec(now, now, transitions + 1)
+        };
+        // The resourceVersion of the observed lease makes this fail if anyone else changed it in the meantime
+        let lease = Lease {
+            metadata: lease.metadata,
+            spec: Some(spec),
+        };
+        match self
+            .api
+            .replace(&self.lease_name, &PostParams::default(), &lease)
+            .await
+        {
+            Ok(_) => Ok(LeaderState::Leading),
+            // Someone else changed the lease since we read it, so find out who holds it now
+            Err(kube_client::Error::Api(err)) if err.code == 409 => self.holder_after_conflict().await,
+            Err(err) => Err(Error::UpdateLease(err)),
+        }
+    }
+
+    /// The [`LeaderState`] after failing to update the lease because it was changed in the meantime
+    ///
+    /// Like `client-go`, a conflict while we still hold the lease is a failed renewal rather than lost leadership,
+    /// so that [`LeaseLock::leadership`] keeps leading until `renew_deadline`.
+    async fn holder_after_conflict(&self) -> Result<LeaderState, Error> {
+        let lease = self
+            .api
+            .get_opt(&self.lease_name)
+            .await
+            .map_err(Error::GetLease)?;
+        let leader = lease
+            .and_then(|lease| lease.spec?.holder_identity)
+            .filter(|holder| !holder.is_empty());
+        if leader.as_deref() == Some(self.params.holder_id.as_str()) {
+            return Err(Error::RenewConflict);
+        }
+        Ok(LeaderState::Following { leader })
+    }
+
+    /// Give up the lease if we are currently holding it
+    ///
+    /// This allows another candidate to take over without waiting for the lease to expire.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the [`Lease`] cannot be read or written.
+    pub async fn release(&self) -> Result<(), Error> {
+        let Some(lease) = self
+            .api
+            .get_opt(&self.lease_name)
+            .await
+            .map_err(Error::GetLease)?
+        else {
+            return Ok(());
+        };
+        let spec = lease.spec.unwrap_or_default();
+        if spec.holder_identity.as_deref() != Some(self.params.holder_id.as_str()) {
+            return Ok(());
+        }
+        let lease = Lease {
+            metadata: lease.metadata,
+            spec: Some(LeaseSpec {
+                holder_identity: None,
+                lease_duration_seconds: Some(1),
+                renew_time: Some(MicroTime(Utc::now())),
+                ..spec
+            }),
+        };
+        self.api
+            .replace(&self.lease_name, &PostParams::default(), &lease)
+            .await
+            .map_err(Error::UpdateLease)?;
+        Ok(())
+    }
+
+    /// Continuously take part in the election, returning a stream of [`LeaderState`] changes
+    ///
+    /// Every `retry_period` the lease is acquired or renewed. Leadership is considered lost if another candidate
+    /// took over the lease, or if the lease could not be renewed within `renew_deadline`.
+    /// Errors are logged and retried rather than returned.
+    ///
+    /// The first item is emitted after the first attempt, and after that only when the state changes.
+    /// The lease is not released when the stream is dropped, see [`LeaseLock::release`].
+    pub fn leadership(&self) -> impl Stream<Item = LeaderState> + Send + 'static {
+        let lock = self.clone();
+        async_stream::stream! {
+            let mut state: Option<LeaderState> = None;
+            let mut last_renewal = tokio::time::Instant::now();
+            loop {
+                let attempt = tokio::time::timeout(lock.params.renew_deadline, lock.try_acquire_or_renew())
+                    .await
+                    .unwrap_or(Err(Error::Timeout));
+                let next = match attempt {
+                    Ok(next) => {
+                        if next.is_leading() {
+                            last_renewal = tokio::time::Instant::now();
+                        }
+                        next
+                    }
+                    Err(err) => {
+                        tracing::warn!(lease = %lock.lease_name, error = %err, "failed to acquire or renew lease");
+                        match &state {
+                            Some(LeaderState::Leading) if last_renewal.elapsed() < lock.params.renew_deadline => {
+                                LeaderState::Leading
+                            }
+                            Some(LeaderState::Following { leader }) => LeaderState::Following { leader: leader.clone() },
+                            _ => LeaderState::Following { leader: None },
+                        }
+                    }
+                };
+                if state.as_ref() != Some(&next) {
+                    tracing::debug!(lease = %lock.lease_name, state = ?next, "leadership changed");
+                    state = Some(next.clone());
+                    yield next;
+                }
+                tokio::time::sleep(lock.params.retry_period).await;
+            }
+        }
+    }
+
+    /// Run [`LeaseLock::leadership`] in a background task, returning a [`Leadership`] handle to observe it
+    ///
+    /// The election stops when the [`Leadership`] is dropped.
+    ///
+    /// # Panics
+    ///
+    /// Panics if called outside of a Tokio runtime.
+    #[must_use]
+    pub fn spawn(&self) -> Leadership {
+        let (state_tx, state_rx) = watch::channel(LeaderState::Following { leader: None });
+        let states = self.leadership();
+        let task = CancelableJoinHandle::spawn(
+            async move {
+                let mut states = std::pin::pin!(states);
+                while let Some(state) = futures::StreamExt::next(&mut states).await {
+                    if state_tx.send(state).is_err() {
+                        break;
+                    }
+                }
+            },
+            &Handle::current(),
+        );
+        Leadership {
+            lock: self.clone(),
+            state: state_rx,
+            task,
+        }
+    }
+
+    fn spec(&self, acquire_time: DateTime<Utc>, renew_time: DateTime<Utc>, transitions: i32) -> LeaseSpec {
+        LeaseSpec {
+            holder_identity: Some(self.params.holder_id.clone()),
+            lease_duration_seconds: Some(duration_seconds(self.params.lease_duration)),
+            acquire_time: Some(MicroTime(acquire_time)),
+            renew_time: Some(MicroTime(renew_time)),
+            lease_transitions: Some(transitions),
+            ..LeaseSpec::default()
+        }
+    }
+}
+
+/// A handle to a running [`LeaseLock`] election, created by [`LeaseLock::spawn`]
+pub struct Leadership {
+    lock: LeaseLock,
+    state: watch::Receiver<LeaderState>,
+    task: CancelableJoinHandle<()>,
+}
+
+impl Leadership {
+    /// The most recently observed [`LeaderState`]
+    #[must_use]
+    pub fn state(&self) -> LeaderState {
+        self.state.borrow().clone()
+    }
+
+    /// Whether this instance is currently the leader
+    #[must_use]
+    pub fn is_leading(&self) -> bool {
+        self.state.borrow().is_leading()
+    }
+
+    /// Wait until this instance has become the leader
+    pub async fn acquired(&mut self) {
+        while !self.state.borrow().is_leading() {
+            if self.state.changed().await.is_err() {
+                return;
+            }
+        }
+    }
+
+    /// A [`Future`] that resolves once this instance is no longer the leader
+    ///
+    /// Resolves immediately if we are not currently leading, so this is meant to be called after [`Leadership::acquired`].
+    /// Suitable for passing to [`Controller::graceful_shutdown_on`](crate::Controller::graceful_shutdown_on).
+    pub fn lost(&self) -> impl Future<Output = ()> + Send + Sync + 'static {
+        let mut state = self.state.clone();
+        async move {
+            while state.borrow().is_leading() {
+                if state.changed().await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Stop taking part in the election, and give up the lease if we are holding it
+    ///
+    /// # Errors
+    ///
+    /// Fails if the [`Lease`] cannot be read or written.
+    pub async fn release(self) -> Result<(), Error> {
+        let Self { lock, task, .. } = self;
+        drop(task);
+        lock.release().await
+    }
+}
+
+fn is_expired(spec: &LeaseSpec, now: DateTime<Utc>) -> bool {
+    let Some(MicroTime(renewed)) = spec.renew_time.as_ref().or(spec.acquire_time.as_ref()) else {
+        return true;
+    };
+    let duration =
+        k8s_openapi::chrono::Duration::seconds(spec.lease_duration_seconds.unwrap_or_default().into());
+    *renewed + duration < now
+}
+
+fn duration_seconds(duration: Duration) -> i32 {
+    i32::try_from(duration.as_secs()).unwrap_or(i32::MAX).max(1)
+}
@@ -24,6 +24,8 @@ pub mod controller;
 pub mod events;
 
 pub mod finalizer;
+pub mod leader_election;
+pub mod logs;
 pub mod reflector;
 pub mod scheduler;
 pub mod utils;
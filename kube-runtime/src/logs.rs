@@ -0,0 +1,734 @@
+//! Follow the logs of every pod matching a selector
+//!
+//! [`log_stream`] is the library equivalent of `kubectl logs -l app=x --all-containers --prefix -f`,
+//! and [`follow_logs`] follows a single container across disconnects and restarts.
+use crate::{
+    utils::{Backoff, CancelableJoinHandle},
+    watcher::{self, watcher, DefaultBackoff},
+    WatchStreamExt,
+};
+use futures::{
+    channel::mpsc, future::Either, stream, AsyncBufReadExt, SinkExt, Stream, StreamExt, TryStreamExt,
+};
+use k8s_openapi::{
+    api::core::v1::{ContainerStatus, Pod},
+    chrono::{DateTime, Utc},
+};
+use kube_client::{
+    api::{Api, LogParams},
+    ResourceExt,
+};
+use std::{
+    collections::{HashMap, HashSet},
+    future,
+};
+use thiserror::Error;
+use tokio::runtime::Handle;
+
+/// Number of buffered lines per container before log streams wait for the consumer
+const BUFFERED_LINES: usize = 64;
+/// Number of connections to read the logs of a previous container instance before giving up
+const PREVIOUS_ATTEMPTS: usize = 3;
+
+/// A line of logs from a container
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLine {
+    /// Name of the pod
+    pub pod: String,
+    /// Name of the container
+    pub container: String,
+    /// The log line, without the trailing newline
+    pub line: String,
+}
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to watch pods: {0}")]
+    Watch(#[source] watcher::Error),
+    #[error("failed to stream logs of {pod}/{container}: {source}")]
+    Logs {
+        pod: String,
+        container: String,
+        #[source]
+        source: kube_client::Error,
+    },
+    #[error("failed to read logs of {pod}/{container}: {source}")]
+    ReadLogs {
+        pod: String,
+        container: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("failed to get pod {pod}: {source}")]
+    GetPod {
+        pod: String,
+        #[source]
+        source: kube_client::Error,
+    },
+    #[error("log line of {pod}/{container} has no timestamp: {line:?}")]
+    MissingTimestamp {
+        pod: String,
+        container: String,
+        line: String,
+    },
+}
+
+/// Follow the logs of all containers in all pods matching the `watcher_config`
+///
+/// Pods are found with a [`watcher`], so that logs of new pods are added to the stream as their containers start,
+/// and logs of deleted pods are dropped. Restarted containers are followed again from their new instance.
+/// Every container is followed with the same [`LogParams`], except that [`LogParams::container`]
+/// selects which container to follow in each pod instead of following all of them.
+///
+/// Lines of different containers are interleaved in the order they are received.
+/// Errors from single containers are returned in the stream without stopping it,
+/// and watcher errors are retried with the [default backoff](crate::WatchStreamExt::default_backoff).
+///
+/// Without [`LogParams::follow`], the logs of the containers that have started when the pods are first listed are read once,
+/// and the stream ends after all of them have been read.
+///
+/// Log streams are run as tasks on the current tokio runtime, and aborted when the stream is dropped.
+///
+/// ```no_run
+/// use futures::TryStreamExt;
+/// use k8s_openapi::api::core::v1::Pod;
+/// use kube::{api::{Api, LogParams}, runtime::{logs, watcher}, Client};
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client: Client = todo!();
+/// let pods: Api<Pod> = Api::default_namespaced(client);
+/// let lp = LogParams { follow: true, tail_lines: Some(10), ..LogParams::default() };
+/// let stream = logs::log_stream(pods, watcher::Config::default().labels("app=blog"), lp);
+/// futures::pin_mut!(stream);
+/// while let Some(line) = stream.try_next().await? {
+///     println!("[pod/{}/{}] {}", line.pod, line.container, line.line);
+/// }
+/// # Ok(())
+/// # }
+/// ```
+///
+/// # Panics
+///
+/// Polling the stream panics if it is not run on a tokio runtime.
+pub fn log_stream(
+    api: Api<Pod>,
+    watcher_config: watcher::Config,
+    lp: LogParams,
+) -> impl Stream<Item = Result<LogLine, Error>> + Send {
+    let (line_tx, line_rx) = mpsc::channel(BUFFERED_LINES);
+    let follow = lp.follow;
+    let pods = watcher(api.clone(), watcher_config)
+        .default_backoff()
+        // without following, only the pods of the initial list are read
+        .take_while(move |event| future::ready(follow || !matches!(event, Ok(watcher::Event::InitDone))))
+        .map(Some)
+        .chain(stream::once(future::ready(None)))
+        .boxed();
+    let mut events = stream::select(pods.map(Either::Left), line_rx.map(Either::Right));
+
+    async_stream::stream! {
+        let mut followed = Followed::default();
+        // dropped once the pods are no longer watched, so that the stream ends after the last log stream
+        let mut line_tx = Some(line_tx);
+        // pods listed since the last `Init`, to drop pods deleted while the watcher was restarting
+        let mut relisted: Option<HashSet<String>> = None;
+        while let Some(event) = events.next().await {
+            let pod_event = match event {
+                Either::Right(line) => {
+                    yield line;
+                    continue;
+                }
+                Either::Left(Some(pod_event)) => pod_event,
+                Either::Left(None) => {
+                    line_tx = None;
+                    continue;
+                }
+            };
+            let Some(line_tx) = &line_tx else { continue };
+            match pod_event {
+                Err(err) => yield Err(Error::Watch(err)),
+                Ok(watcher::Event::Init) => relisted = Some(HashSet::new()),
+                Ok(watcher::Event::InitApply(pod)) => {
+                    if let Some(relisted) = &mut relisted {
+                        relisted.insert(pod.name_any());
+                    }
+                    followed.update(&api, &pod, &lp, line_tx);
+                }
+                Ok(watcher::Event::InitDone) => {
+                    if let Some(relisted) = relisted.take() {
+                        followed.retain(|pod| relisted.contains(pod));
+                    }
+                }
+                Ok(watcher::Event::Apply(pod)) => followed.update(&api, &pod, &lp, line_tx),
+                Ok(watcher::Event::Delete(pod)) => {
+                    let name = pod.name_any();
+                    followed.retain(|pod| pod != name);
+                }
+            }
+        }
+    }
+}
+
+/// Identifies one instance of a container, which changes when the container restarts
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+struct ContainerKey {
+    pod: String,
+    container: String,
+    restart_count: i32,
+}
+
+/// Log streams of the followed containers, aborted when dropped
+#[derive(Default)]
+struct Followed(HashMap<ContainerKey, CancelableJoinHandle<()>>);
+
+impl Followed {
+    /// Start following the containers of a pod that have started since the last update
+    fn update(
+        &mut self,
+        api: &Api<Pod>,
+        pod: &Pod,
+        lp: &LogParams,
+        line_tx: &mpsc::Sender<Result<LogLine, Error>>,
+    ) {
+        let name = pod.name_any();
+        let Some(status) = &pod.status else { return };
+        let statuses = [
+            &status.init_container_statuses,
+            &status.container_statuses,
+            &status.ephemeral_container_statuses,
+        ];
+        for container in statuses.into_iter().flatten().flatten() {
+            if !has_logs(container) || lp.container.as_ref().is_some_and(|c| c != &container.name) {
+                continue;
+            }
+            let key = ContainerKey {
+                pod: name.clone(),
+                container: container.name.clone(),
+                restart_count: container.restart_count,
+            };
+            // older instances of the container have stopped, and are forgotten once their stream has ended
+            self.0.retain(|old, task| {
+                old.pod != key.pod
+                    || old.container != key.container
+                    || old.restart_count == key.restart_count
+                    || !task.is_finished()
+            });
+            if self.0.contains_key(&key) {
+                continue;
+            }
+            let lp = LogParams {
+                container: Some(key.container.clone()),
+                ..lp.clone()
+            };
+            let task = follow(api.clone(), key.clone(), lp, line_tx.clone());
+            self.0
+                .insert(key, CancelableJoinHandle::spawn(task, &Handle::current()));
+        }
+    }
+
+    /// Stop following pods for which `keep` returns false
+    fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) {
+        self.0.retain(|key, _| keep(&key.pod));
+    }
+}
+
+/// Logs can only be requested for containers that are running or terminated
+fn has_logs(container: &ContainerStatus) -> bool {
+    container
+        .state
+        .as_ref()
+        .is_some_and(|state| state.running.is_some() || state.terminated.is_some())
+}
+
+async fn follow(
+    api: Api<Pod>,
+    key: ContainerKey,
+    lp: LogParams,
+    mut line_tx: mpsc::Sender<Result<LogLine, Error>>,
+) {
+    let ContainerKey { pod, container, .. } = key;
+    let lines = match api.log_stream(&pod, &lp).await {
+        Ok(logs) => logs.lines(),
+        Err(source) => {
+            let _ = line_tx
+                .send(Err(Error::Logs {
+                    pod,
+                    container,
+                    source,
+                }))
+                .await;
+            return;
+        }
+    };
+    let lines = lines
+        .map_ok(|line| LogLine {
+            pod: pod.clone(),
+            container: container.clone(),
+            line,
+        })
+        .map_err(|source| Error::ReadLogs {
+            pod: pod.clone(),
+            container: container.clone(),
+            source,
+        });
+    // stops once the consumer is gone
+    let _ = line_tx.send_all(&mut lines.map(Ok)).await;
+}
+
+/// An event from [`follow_logs`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LogEvent {
+    /// A line of logs, with the timestamp that the container runtime recorded for it
+    Line { timestamp: DateTime<Utc>, line: String },
+    /// Lines may have been lost between the two timestamps
+    ///
+    /// `since` is the timestamp of the last returned line, if any.
+    /// `until` is the timestamp of the next returned line, or `None` when the rest of a container instance was lost.
+    Gap {
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
+    },
+    /// The container restarted, and the following lines come from its new instance
+    Restarted { restart_count: i32 },
+}
+
+/// Follow the logs of a container, resuming after disconnects
+///
+/// A plain [`Api::log_stream`] ends whenever the connection to the apiserver or kubelet drops.
+/// This reconnects from the timestamp of the last returned line instead, and drops the lines that were already returned.
+/// Lines are requested with [`LogParams::timestamps`], which are returned with every [`LogEvent::Line`].
+///
+/// When the container restarts, the remaining lines of the previous instance are read before switching to the new instance,
+/// which is announced with a [`LogEvent::Restarted`].
+/// Lines that could not be recovered, for example because logs were rotated or several restarts were missed,
+/// are reported as a [`LogEvent::Gap`].
+///
+/// [`LogParams::container`] defaults to the `kubectl.kubernetes.io/default-container` or the first container of the pod.
+/// [`LogParams::since_seconds`], [`LogParams::since_time`], [`LogParams::tail_lines`] and [`LogParams::limit_bytes`]
+/// only apply to the first connection, and [`LogParams::follow`] and [`LogParams::previous`] are ignored.
+///
+/// The stream ends when the pod is deleted, or when the container has terminated and will not be restarted.
+/// Errors are returned in the stream and retried with the [`DefaultBackoff`].
+///
+/// ```no_run
+/// use futures::TryStreamExt;
+/// use k8s_openapi::api::core::v1::Pod;
+/// use kube::{api::{Api, LogParams}, runtime::logs::{self, LogEvent}, Client};
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client: Client = todo!();
+/// let pods: Api<Pod> = Api::default_namespaced(client);
+/// let stream = logs::follow_logs(pods, "blog", LogParams::default());
+/// futures::pin_mut!(stream);
+/// while let Some(event) = stream.try_next().await? {
+///     match event {
+///         LogEvent::Line { line, .. } => println!("{line}"),
+///         LogEvent::Gap { since, until } => println!("lines lost between {since:?} and {until:?}"),
+///         LogEvent::Restarted { restart_count } => println!("container restarted ({restart_count})"),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn follow_logs(
+    api: Api<Pod>,
+    name: &str,
+    lp: LogParams,
+) -> impl Stream<Item = Result<LogEvent, Error>> + Send {
+    let name = name.to_string();
+    async_stream::stream! {
+        let mut backoff = DefaultBackoff::default();
+        let mut container = lp.container.clone();
+        let mut restart_count = None;
+        let mut cursor = Cursor::default();
+        let mut first_connection = true;
+        loop {
+            let pod = match api.get_opt(&name).await {
+                Ok(Some(pod)) => pod,
+                Ok(None) => return,
+                Err(source) => {
+                    yield Err(Error::GetPod { pod: name.clone(), source });
+                    sleep(&mut backoff).await;
+                    continue;
+                }
+            };
+            let Some(container_name) = container.clone().or_else(|| default_container(&pod)) else { return };
+            container = Some(container_name.clone());
+            let Some((status, ephemeral)) = container_status(&pod, &container_name) else {
+                // not started yet
+                sleep(&mut backoff).await;
+                continue;
+            };
+
+            if let Some(previous) = restart_count.filter(|previous| *previous < status.restart_count) {
+                if previous + 1 == status.restart_count {
+                    for event in read_previous(&api, &name, &container_name, &mut cursor).await {
+                        yield event;
+                    }
+                } else {
+                    // whole instances were missed between two connections
+                    yield Ok(LogEvent::Gap { since: cursor.last, until: None });
+                }
+                yield Ok(LogEvent::Restarted { restart_count: status.restart_count });
+                cursor = Cursor::default();
+            }
+            restart_count = Some(status.restart_count);
+
+            if !has_logs(status) {
+                sleep(&mut backoff).await;
+                continue;
+            }
+            let lp = if first_connection {
+                LogParams {
+                    container: Some(container_name.clone()),
+                    follow: true,
+                    previous: false,
+                    timestamps: true,
+                    ..lp.clone()
+                }
+            } else {
+                cursor.resume(LogParams {
+                    container: Some(container_name.clone()),
+                    follow: true,
+                    timestamps: true,
+                    ..LogParams::default()
+                })
+            };
+            let mut lines = match api.log_stream(&name, &lp).await {
+                Ok(logs) => logs.lines(),
+                Err(source) => {
+                    yield Err(Error::Logs { pod: name.clone(), container: container_name, source });
+                    sleep(&mut backoff).await;
+                    continue;
+                }
+            };
+            first_connection = false;
+            while let Some(line) = lines.next().await {
+                match line {
+                    Ok(line) => {
+                        for event in cursor.next_events(&name, &container_name, line) {
+                            // only new lines show that the connection works, duplicates are dropped
+                            if matches!(event, Ok(LogEvent::Line { .. })) {
+                                backoff.reset();
+                            }
+                            yield event;
+                        }
+                    }
+                    Err(source) => {
+                        // reconnect from the last returned line
+                        yield Err(Error::ReadLogs { pod: name.clone(), container: container_name.clone(), source });
+                        break;
+                    }
+                }
+            }
+
+            let terminated = status.state.as_ref().and_then(|state| state.terminated.as_ref());
+            if let Some(terminated) = terminated {
+                if ephemeral || !will_restart(&pod, &container_name, terminated.exit_code) {
+                    return;
+                }
+            }
+            sleep(&mut backoff).await;
+        }
+    }
+}
+
+/// Read the rest of the logs of the previous instance of a container
+///
+/// Interrupted reads are resumed from the last returned line, up to [`PREVIOUS_ATTEMPTS`] times,
+/// after which the rest of the instance is reported as a [`LogEvent::Gap`].
+async fn read_previous(
+    api: &Api<Pod>,
+    name: &str,
+    container: &str,
+    cursor: &mut Cursor,
+) -> Vec<Result<LogEvent, Error>> {
+    let mut events = Vec::new();
+    for _ in 0..PREVIOUS_ATTEMPTS {
+        let lp = cursor.resume(LogParams {
+            container: Some(container.to_string()),
+            previous: true,
+            timestamps: true,
+            ..LogParams::default()
+        });
+        let mut lines = match api.log_stream(name, &lp).await {
+            Ok(logs) => logs.lines(),
+            Err(source) => {
+                events.push(Err(Error::Logs {
+                    pod: name.to_string(),
+                    container: container.to_string(),
+                    source,
+                }));
+                break;
+            }
+        };
+        let mut interrupted = false;
+        while let Some(line) = lines.next().await {
+            match line {
+                Ok(line) => events.extend(cursor.next_events(name, container, line)),
+                Err(source) => {
+                    events.push(Err(Error::ReadLogs {
+                        pod: name.to_string(),
+                        container: container.to_string(),
+                        source,
+                    }));
+                    interrupted = true;
+                    break;
+                }
+            }
+        }
+        if !interrupted {
+            return events;
+        }
+    }
+    events.push(Ok(LogEvent::Gap {
+        since: cursor.last,
+        until: None,
+    }));
+    events
+}
+
+async fn sleep(backoff: &mut DefaultBackoff) {
+    if let Some(delay) = backoff.next() {
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// The container that `kubectl logs` picks when none is given
+fn default_container(pod: &Pod) -> Option<String> {
+    pod.annotations()
+        .get("kubectl.kubernetes.io/default-container")
+        .cloned()
+        .or_else(|| Some(pod.spec.as_ref()?.containers.first()?.name.clone()))
+}
+
+/// Finds the status of a container, and whether it is an ephemeral container
+fn container_status<'a>(pod: &'a Pod, container: &str) -> Option<(&'a ContainerStatus, bool)> {
+    let status = pod.status.as_ref()?;
+    let find =
+        |statuses: &'a Option<Vec<ContainerStatus>>| statuses.iter().flatten().find(|c| c.name == container);
+    find(&status.container_statuses)
+        .or_else(|| find(&status.init_container_statuses))
+        .map(|c| (c, false))
+        .or_else(|| find(&status.ephemeral_container_statuses).map(|c| (c, true)))
+}
+
+/// Whether the kubelet restarts a container that terminated with `exit_code`
+fn will_restart(pod: &Pod, container: &str, exit_code: i32) -> bool {
+    let phase = pod.status.as_ref().and_then(|status| status.phase.as_deref());
+    if matches!(phase, Some("Succeeded" | "Failed")) {
+        return false;
+    }
+    let spec = pod.spec.as_ref();
+    let restart_policy = spec.and_then(|spec| spec.restart_policy.as_deref());
+    let init_container = spec
+        .and_then(|spec| spec.init_containers.as_ref())
+        .and_then(|containers| containers.iter().find(|c| c.name == container));
+    if let Some(init_container) = init_container {
+        // sidecars always restart, other init containers only run again when they failed
+        return init_container.restart_policy.as_deref() == Some("Always")
+            || (exit_code != 0 && restart_policy != Some("Never"));
+    }
+    match restart_policy {
+        Some("Never") => false,
+        Some("OnFailure") => exit_code != 0,
+        _ => true,
+    }
+}
+
+/// Position in the logs of a container instance, to resume after the last returned line
+#[derive(Debug, Default)]
+struct Cursor {
+    /// Timestamp of the last returned line
+    last: Option<DateTime<Utc>>,
+    /// Number of returned lines with the `last` timestamp
+    returned_at_last: usize,
+    /// Number of lines with the `last` timestamp to skip after reconnecting
+    skip: usize,
+    /// Whether the next line is the first one after reconnecting
+    resumed: bool,
+}
+
+impl Cursor {
+    /// Request the logs from the last returned line
+    ///
+    /// `sinceTime` only has a precision of seconds, so earlier lines are skipped as well.
+    fn resume(&mut self, lp: LogParams) -> LogParams {
+        self.skip = self.returned_at_last;
+        self.resumed = self.last.is_some();
+        LogParams {
+            since_time: self.last,
+            ..lp
+        }
+    }
+
+    /// Parse a line with a timestamp, and return it unless it was already returned
+    fn next_events(&mut self, pod: &str, container: &str, line: String) -> Vec<Result<LogEvent, Error>> {
+        let Some((timestamp, text)) = parse_timestamp(&line) else {
+            return vec![Err(Error::MissingTimestamp {
+                pod: pod.to_string(),
+                container: container.to_string(),
+                line,
+            })];
+        };
+        let mut events = Vec::new();
+        if let Some(last) = self.last {
+            if std::mem::take(&mut self.resumed) && timestamp > last {
+                // the line we resumed from is gone
+                events.push(Ok(LogEvent::Gap {
+                    since: Some(last),
+                    until: Some(timestamp),
+                }));
+            }
+            if timestamp < last {
+                return events;
+            }
+            if timestamp == last && self.skip > 0 {
+                self.skip -= 1;
+                return events;
+            }
+        }
+        if self.last == Some(timestamp) {
+            self.returned_at_last += 1;
+        } else {
+            self.last = Some(timestamp);
+            self.returned_at_last = 1;
+        }
+        self.skip = 0;
+        events.push(Ok(LogEvent::Line {
+            timestamp,
+            line: text.to_string(),
+        }));
+        events
+    }
+}
+
+/// Split the RFC3339 timestamp that the kubelet adds in front of log lines
+fn parse_timestamp(line: &str) -> Option<(DateTime<Utc>, &str)> {
+    let (timestamp, text) = line.split_once(' ').unwrap_or((line, ""));
+    let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?;
+    Some((timestamp.with_timezone(&Utc), text))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_started_containers_have_logs() {
+        let statuses: Vec<ContainerStatus> = serde_json::from_value(serde_json::json!([
+            { "name": "app", "image": "app", "imageID": "", "ready": true, "restartCount": 0, "state": { "running": {} } },
+            { "name": "init", "image": "init", "imageID": "", "ready": false, "restartCount": 0, "state": { "terminated": { "exitCode": 0 } } },
+            { "name": "sidecar", "image": "sidecar", "imageID": "", "ready": false, "restartCount": 0, "state": { "waiting": { "reason": "ContainerCreating" } } }
+        ]))
+        .unwrap();
+        let started: Vec<_> = statuses.iter().filter(|c| has_logs(c)).map(|c| &c.name).collect();
+        assert_eq!(started, ["app", "init"]);
+    }
+
+    #[test]
+    fn terminated_containers_restart_by_policy() {
+        let pod = |restart_policy: &str, phase: &str| -> Pod {
+            serde_json::from_value(serde_json::json!({
+                "metadata": { "name": "blog" },
+                "spec": {
+                    "restartPolicy": restart_policy,
+                    "initContainers": [
+                        { "name": "init" },
+                        { "name": "sidecar", "restartPolicy": "Always" },
+                    ],
+                    "containers": [{ "name": "app" }],
+                },
+                "status": { "phase": phase },
+            }))
+            .unwrap()
+        };
+
+        let always = pod("Always", "Running");
+        assert!(will_restart(&always, "app", 0));
+        assert!(will_restart(&always, "app", 1));
+        assert!(!will_restart(&always, "init", 0), "completed init containers never run again");
+        assert!(will_restart(&always, "init", 1));
+        assert!(will_restart(&always, "sidecar", 0));
+
+        let on_failure = pod("OnFailure", "Running");
+        assert!(!will_restart(&on_failure, "app", 0));
+        assert!(will_restart(&on_failure, "app", 1));
+        assert!(!will_restart(&on_failure, "init", 0));
+        assert!(will_restart(&on_failure, "init", 1));
+
+        let never = pod("Never", "Pending");
+        assert!(!will_restart(&never, "app", 1));
+        assert!(!will_restart(&never, "init", 1));
+        assert!(will_restart(&never, "sidecar", 0));
+
+        assert!(!will_restart(&pod("Always", "Succeeded"), "app", 0));
+        assert!(!will_restart(&pod("Always", "Failed"), "sidecar", 1));
+    }
+
+    fn lines(cursor: &mut Cursor, lines: &[&str]) -> Vec<LogEvent> {
+        lines
+            .iter()
+            .flat_map(|line| cursor.next_events("blog", "app", (*line).to_string()))
+            .map(Result::unwrap)
+            .collect()
+    }
+
+    fn line(timestamp: &str, line: &str) -> LogEvent {
+        LogEvent::Line {
+            timestamp: timestamp.parse().unwrap(),
+            line: line.to_string(),
+        }
+    }
+
+    #[test]
+    fn resuming_skips_returned_lines() {
+        let mut cursor = Cursor::default();
+        let events = lines(&mut cursor, &[
+            "2024-01-01T00:00:00.5Z a",
+            "2024-01-01T00:00:01.5Z b",
+            "2024-01-01T00:00:01.5Z c",
+        ]);
+        assert_eq!(events.len(), 3);
+
+        let lp = cursor.resume(LogParams::default());
+        assert_eq!(lp.since_time, Some("2024-01-01T00:00:01.5Z".parse().unwrap()));
+        // sinceTime is truncated to seconds by the apiserver
+        let events = lines(&mut cursor, &[
+            "2024-01-01T00:00:01.2Z x",
+            "2024-01-01T00:00:01.5Z b",
+            "2024-01-01T00:00:01.5Z c",
+            "2024-01-01T00:00:01.5Z d",
+            "2024-01-01T00:00:02Z e",
+        ]);
+        assert_eq!(events, [
+            line("2024-01-01T00:00:01.5Z", "d"),
+            line("2024-01-01T00:00:02Z", "e")
+        ]);
+    }
+
+    #[test]
+    fn resuming_after_lost_lines_reports_gap() {
+        let mut cursor = Cursor::default();
+        lines(&mut cursor, &["2024-01-01T00:00:01Z a"]);
+        cursor.resume(LogParams::default());
+        let events = lines(&mut cursor, &["2024-01-01T00:00:05Z e", "2024-01-01T00:00:06Z f"]);
+        assert_eq!(events, [
+            LogEvent::Gap {
+                since: Some("2024-01-01T00:00:01Z".parse().unwrap()),
+                until: Some("2024-01-01T00:00:05Z".parse().unwrap()),
+            },
+            line("2024-01-01T00:00:05Z", "e"),
+            line("2024-01-01T00:00:06Z", "f"),
+        ]);
+    }
+
+    #[test]
+    fn lines_need_timestamps() {
+        let mut cursor = Cursor::default();
+        let events = cursor.next_events("blog", "app", "no timestamp".to_string());
+        assert!(matches!(&events[..], [Err(Error::MissingTimestamp { .. })]));
+        assert_eq!(lines(&mut cursor, &["2024-01-01T00:00:01Z"]), [line(
+            "2024-01-01T00:00:01Z",
+            ""
+        )]);
+    }
+}
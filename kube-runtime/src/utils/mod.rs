@@ -162,6 +162,11 @@ where
             inner: runtime.spawn(future),
         }
     }
+
+    #[must_use]
+    pub fn is_finished(&self) -> bool {
+        self.inner.is_finished()
+    }
 }
 
 impl<T> Drop for CancelableJoinHandle<T> {
@@ -163,10 +163,15 @@ impl<K, F: Fn(Option<&K>) -> bool> Condition<K> for F {
 pub mod conditions {
     pub use super::Condition;
     use k8s_openapi::{
-        api::{batch::v1::Job, core::v1::Pod},
+        api::{
+            batch::v1::Job,
+            core::v1::{Pod, ResourceRequirements},
+        },
         apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition,
+        apimachinery::pkg::api::resource::Quantity,
     };
     use kube_client::Resource;
+    use std::collections::BTreeMap;
 
     /// An await condition that returns `true` once the object has been deleted.
     ///
@@ -220,6 +225,66 @@ pub mod conditions {
         }
     }
 
+    /// An await condition for `Pod` that returns `true` once an in-place resize has been applied
+    ///
+    /// A resize has been applied once the `cpu` and `memory` resources of every container in the spec
+    /// match the resources reported in `status.containerStatuses`, and the pod has neither a
+    /// `PodResizePending` nor a `PodResizeInProgress` condition, nor a `Proposed`, `InProgress`,
+    /// `Deferred` or `Infeasible` `status.resize` as reported by older clusters.
+    /// Containers without reported resources are not compared.
+    ///
+    /// An infeasible resize never completes, so combine this with a timeout.
+    ///
+    /// Use it after [`Api::patch_resize`](kube_client::Api::patch_resize).
+    #[must_use]
+    pub fn is_pod_resized() -> impl Condition<Pod> {
+        |obj: Option<&Pod>| {
+            if let Some(pod) = obj {
+                if let Some(status) = &pod.status {
+                    if status.resize.is_some() {
+                        return false;
+                    }
+                    if let Some(conds) = &status.conditions {
+                        let resizing = conds.iter().any(|c| {
+                            matches!(c.type_.as_str(), "PodResizePending" | "PodResizeInProgress")
+                                && c.status == "True"
+                        });
+                        if resizing {
+                            return false;
+                        }
+                    }
+                    let containers = pod
+                        .spec
+                        .as_ref()
+                        .map(|s| s.containers.as_slice())
+                        .unwrap_or_default();
+                    let statuses = status.container_statuses.as_deref().unwrap_or_default();
+                    return containers.iter().all(|container| {
+                        let actual = statuses
+                            .iter()
+                            .find(|s| s.name == container.name)
+                            .and_then(|s| s.resources.as_ref());
+                        actual.map_or(true, |actual| {
+                            resources_applied(container.resources.as_ref(), actual)
+                        })
+                    });
+                }
+            }
+            false
+        }
+    }
+
+    /// Whether the resizable resources of a container spec match the resources in its status
+    fn resources_applied(desired: Option<&ResourceRequirements>, actual: &ResourceRequirements) -> bool {
+        let get = |resources: Option<&BTreeMap<String, Quantity>>, name: &str| {
+            resources.and_then(|r| r.get(name)).cloned()
+        };
+        ["cpu", "memory"].into_iter().all(|name| {
+            get(desired.and_then(|r| r.requests.as_ref()), name) == get(actual.requests.as_ref(), name)
+                && get(desired.and_then(|r| r.limits.as_ref()), name) == get(actual.limits.as_ref(), name)
+        })
+    }
+
     /// An await condition for `Job` that returns `true` once it is completed
     #[must_use]
     pub fn is_job_completed() -> impl Condition<Job> {
@@ -317,3 +382,46 @@ pub mod delete {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{conditions::is_pod_resized, Condition};
+    use k8s_openapi::api::core::v1::Pod;
+
+    fn pod(status: &serde_json::Value) -> Pod {
+        serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "app" },
+            "spec": { "containers": [{
+                "name": "app",
+                "resources": { "requests": { "cpu": "500m", "memory": "128Mi" } }
+            }]},
+            "status": status,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pod_resized_once_resources_are_applied() {
+        let resized = is_pod_resized();
+        let applied = serde_json::json!({ "containerStatuses": [{
+            "name": "app", "image": "app", "imageID": "", "ready": true, "restartCount": 0,
+            "resources": { "requests": { "cpu": "500m", "memory": "128Mi" } }
+        }]});
+        assert!(resized.matches_object(Some(&pod(&applied))));
+        assert!(!resized.matches_object(None));
+
+        let mut pending = serde_json::json!({ "containerStatuses": [{
+            "name": "app", "image": "app", "imageID": "", "ready": true, "restartCount": 0,
+            "resources": { "requests": { "cpu": "250m", "memory": "128Mi" } }
+        }]});
+        assert!(!resized.matches_object(Some(&pod(&pending))));
+        pending["containerStatuses"][0]["resources"] = applied["containerStatuses"][0]["resources"].clone();
+        pending["conditions"] = serde_json::json!([{ "type": "PodResizePending", "status": "True" }]);
+        assert!(!resized.matches_object(Some(&pod(&pending))));
+        pending["conditions"][0]["status"] = "False".into();
+        assert!(resized.matches_object(Some(&pod(&pending))));
+
+        let legacy = serde_json::json!({ "resize": "InProgress" });
+        assert!(!resized.matches_object(Some(&pod(&legacy))));
+    }
+}
@@ -10,7 +10,10 @@ use educe::Educe;
 use futures::{stream::BoxStream, Stream, StreamExt};
 use kube_client::{
     api::{ListParams, Resource, ResourceExt, VersionMatch, WatchEvent, WatchParams},
-    core::{metadata::PartialObjectMeta, ObjectList, Selector},
+    core::{
+        metadata::PartialObjectMeta, request::Error as RequestError, FieldSelector, ObjectList,
+        SelectableFields, Selector,
+    },
     error::ErrorResponse,
     Api, Error as ClientErr,
 };
@@ -328,6 +331,31 @@ impl Config {
         self
     }
 
+    /// Configure a typed field selector
+    ///
+    /// The fields are validated against the [`SelectableFields`] of the kind `K`.
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube_client::core::{FieldExpression, FieldSelector};
+    /// # use kube_runtime::watcher::Config;
+    /// let selector: FieldSelector = FieldExpression::Equal("spec.nodeName".into(), "node-1".into()).into();
+    /// let cfg = Config::default().fields_from::<Pod>(&selector)?;
+    /// # Ok::<(), kube_client::core::request::Error>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails when a field of the selector is not selectable for the kind.
+    pub fn fields_from<K: SelectableFields>(
+        mut self,
+        selector: &FieldSelector,
+    ) -> Result<Self, RequestError> {
+        selector.validate::<K>()?;
+        self.field_selector = Some(selector.to_string());
+        Ok(self)
+    }
+
     /// Configure the selector to restrict the list of returned objects by their labels.
     ///
     /// Defaults to everything.
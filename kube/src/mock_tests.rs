@@ -1,5 +1,9 @@
 use crate::{
+    api::LogParams,
+    discovery::{verbs, Discovery, Scope},
     runtime::{
+        leader_election::{LeaderState, LeaseLock, LeaseLockParams},
+        logs::log_stream,
         watcher::{watcher, Config},
         WatchStreamExt,
     },
@@ -7,7 +11,11 @@ use crate::{
 };
 use anyhow::Result;
 use futures::{poll, StreamExt, TryStreamExt};
-use http::{Request, Response};
+use http::{Request, Response, StatusCode};
+use k8s_openapi::{
+    api::{coordination::v1::Lease, core::v1::Pod},
+    chrono::Utc,
+};
 use kube_client::client::Body;
 use kube_derive::CustomResource;
 use schemars::JsonSchema;
@@ -47,6 +55,153 @@ async fn watchers_respect_pagination_limits() {
     timeout_after_1s(mocksrv).await;
 }
 
+#[tokio::test]
+async fn leader_election_creates_missing_lease() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::LeaseCreate);
+
+    let lock = LeaseLock::new(client, "default", "hack-lock", LeaseLockParams::new("us"));
+    let state = lock.try_acquire_or_renew().await.unwrap();
+    assert_eq!(state, LeaderState::Leading);
+    timeout_after_1s(mocksrv).await;
+}
+
+#[tokio::test]
+async fn leader_election_follows_active_leader() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::LeaseHeldByOther);
+
+    let lock = LeaseLock::new(client, "default", "hack-lock", LeaseLockParams::new("us"));
+    let state = lock.try_acquire_or_renew().await.unwrap();
+    assert_eq!(state, LeaderState::Following {
+        leader: Some("them".into())
+    });
+    timeout_after_1s(mocksrv).await;
+}
+
+#[tokio::test]
+async fn leader_election_takes_over_expired_lease() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::LeaseExpired);
+
+    let lock = LeaseLock::new(client, "default", "hack-lock", LeaseLockParams::new("us"));
+    let state = lock.try_acquire_or_renew().await.unwrap();
+    assert_eq!(state, LeaderState::Leading);
+    timeout_after_1s(mocksrv).await;
+}
+
+#[tokio::test]
+async fn leadership_is_lost_when_renewal_conflicts() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::LeaseLostOnRenewal);
+
+    let params = LeaseLockParams::new("us").retry_period(std::time::Duration::from_millis(10));
+    let lock = LeaseLock::new(client, "default", "hack-lock", params);
+    let mut leadership = lock.spawn();
+    leadership.acquired().await;
+    assert!(leadership.is_leading());
+    tokio::time::timeout(std::time::Duration::from_secs(1), leadership.lost())
+        .await
+        .expect("leadership lost");
+    assert_eq!(leadership.state(), LeaderState::Following {
+        leader: Some("them".into())
+    });
+    timeout_after_1s(mocksrv).await;
+}
+
+#[tokio::test]
+async fn leadership_is_kept_when_renewal_conflicts_while_holding() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::LeaseConflictWhileHolding);
+
+    let params = LeaseLockParams::new("us").retry_period(std::time::Duration::from_millis(10));
+    let lock = LeaseLock::new(client, "default", "hack-lock", params);
+    let mut leadership = lock.spawn();
+    leadership.acquired().await;
+    tokio::select! {
+        () = leadership.lost() => panic!("leadership lost on conflict"),
+        () = timeout_after_1s(mocksrv) => {}
+    }
+    assert!(leadership.is_leading());
+}
+
+#[tokio::test]
+async fn discovery_uses_aggregated_discovery() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::AggregatedDiscovery);
+
+    let discovery = Discovery::new(client).run().await.unwrap();
+    let apps = discovery.get("apps").unwrap();
+    assert_eq!(apps.preferred_version(), Some("v1"));
+    let (ar, caps) = apps.recommended_kind("Deployment").unwrap();
+    assert_eq!(ar.api_version, "apps/v1");
+    assert_eq!(caps.scope, Scope::Namespaced);
+    assert!(caps.supports_operation(verbs::LIST));
+    let (pods, _) = discovery.get("").unwrap().recommended_kind("Pod").unwrap();
+    assert_eq!(pods.plural, "pods");
+    timeout_after_1s(mocksrv).await;
+}
+
+#[tokio::test]
+async fn discovery_falls_back_to_legacy_discovery() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::LegacyDiscovery);
+
+    let discovery = Discovery::new(client).run().await.unwrap();
+    let (ar, caps) = discovery
+        .get("apps")
+        .unwrap()
+        .recommended_kind("Deployment")
+        .unwrap();
+    assert_eq!(ar.api_version, "apps/v1");
+    assert_eq!(caps.subresources[0].0.plural, "scale");
+    let (pods, _) = discovery.get("").unwrap().recommended_kind("Pod").unwrap();
+    assert_eq!(pods.plural, "pods");
+    timeout_after_1s(mocksrv).await;
+}
+
+#[tokio::test]
+async fn log_stream_reads_started_containers_once_without_follow() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::PodLogs);
+
+    let pods: Api<Pod> = Api::default_namespaced(client);
+    let stream = log_stream(pods, Config::default(), LogParams::default());
+    let lines: Vec<_> = stream.map_ok(|line| line.line).try_collect().await.unwrap();
+    assert_eq!(lines, ["one", "two"]);
+    timeout_after_1s(mocksrv).await;
+}
+
+#[tokio::test]
+async fn log_stream_follows_pods_and_restarted_containers() {
+    let (client, fakeserver) = testcontext();
+    let mocksrv = fakeserver.run(Scenario::FollowedPodLogs);
+
+    let pods: Api<Pod> = Api::default_namespaced(client);
+    let lp = LogParams {
+        follow: true,
+        ..LogParams::default()
+    };
+    let stream = log_stream(pods, Config::default(), lp);
+    // the relist after the expired watch is the only error
+    let lines = stream
+        .filter_map(|line| std::future::ready(line.ok()))
+        .map(|line| format!("{}: {}", line.pod, line.line))
+        .take(3)
+        .collect::<Vec<_>>();
+    let mut lines = tokio::time::timeout(std::time::Duration::from_secs(5), lines)
+        .await
+        .expect("all lines are read");
+    // the first two instances of `a` are read concurrently
+    lines.sort();
+    assert_eq!(lines, [
+        "a: first instance",
+        "a: second instance",
+        "b: added again"
+    ]);
+    timeout_after_1s(mocksrv).await;
+}
+
 // ------------------------------------------------------------------------
 // mock test setup cruft
 // ------------------------------------------------------------------------
@@ -65,6 +220,15 @@ async fn timeout_after_1s(handle: tokio::task::JoinHandle<()>) {
 /// Scenarios we test for in ApiServerVerifier above
 enum Scenario {
     PaginatedList,
+    LeaseCreate,
+    LeaseHeldByOther,
+    LeaseExpired,
+    LeaseLostOnRenewal,
+    LeaseConflictWhileHolding,
+    AggregatedDiscovery,
+    LegacyDiscovery,
+    PodLogs,
+    FollowedPodLogs,
     #[allow(dead_code)] // remove when/if we start doing better mock tests that use this
     RadioSilence,
 }
@@ -83,6 +247,15 @@ impl ApiServerVerifier {
             // moving self => one scenario per test
             match scenario {
                 Scenario::PaginatedList => self.handle_paged_lists().await,
+                Scenario::LeaseCreate => self.handle_lease_acquired().await,
+                Scenario::LeaseHeldByOther => self.handle_lease_get("them", 0).await,
+                Scenario::LeaseExpired => self.handle_lease_takeover().await,
+                Scenario::LeaseLostOnRenewal => self.handle_lease_lost().await,
+                Scenario::LeaseConflictWhileHolding => self.handle_lease_conflict_while_holding().await,
+                Scenario::AggregatedDiscovery => self.handle_aggregated_discovery().await,
+                Scenario::LegacyDiscovery => self.handle_legacy_discovery().await,
+                Scenario::PodLogs => self.handle_pod_logs().await,
+                Scenario::FollowedPodLogs => self.handle_followed_pod_logs().await,
                 Scenario::RadioSilence => Ok(self),
             }
             .expect("scenario completed without errors");
@@ -149,6 +322,325 @@ impl ApiServerVerifier {
         }
         Ok(self)
     }
+
+    async fn handle_lease_acquired(self) -> Result<Self> {
+        self.handle_lease_missing().await?.handle_lease_create().await
+    }
+
+    async fn handle_lease_takeover(self) -> Result<Self> {
+        self.handle_lease_get("them", 60)
+            .await?
+            .handle_lease_replace(2)
+            .await
+    }
+
+    async fn handle_lease_lost(self) -> Result<Self> {
+        self.handle_lease_acquired()
+            .await?
+            .handle_lease_get("us", 0)
+            .await?
+            .handle_lease_conflict()
+            .await?
+            // the lease is re-read to find out who took it over
+            .handle_lease_get("them", 0)
+            .await
+    }
+
+    async fn handle_lease_conflict_while_holding(self) -> Result<Self> {
+        self.handle_lease_acquired()
+            .await?
+            .handle_lease_get("us", 0)
+            .await?
+            .handle_lease_conflict()
+            .await?
+            .handle_lease_get("us", 0)
+            .await?
+            // renewed on the next attempt
+            .handle_lease_get("us", 0)
+            .await?
+            .handle_lease_replace(1)
+            .await
+    }
+
+    async fn handle_lease_missing(mut self) -> Result<Self> {
+        let (request, send) = self.0.next_request().await.expect("service not called");
+        assert_eq!(request.method(), http::Method::GET);
+        assert_eq!(
+            request.uri().to_string(),
+            "/apis/coordination.k8s.io/v1/namespaces/default/leases/hack-lock"
+        );
+        let respdata = json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "status": "Failure",
+            "message": "leases.coordination.k8s.io \"hack-lock\" not found",
+            "reason": "NotFound",
+            "code": 404
+        });
+        let response = serde_json::to_vec(&respdata).unwrap();
+        send.send_response(
+            Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::from(response))
+                .unwrap(),
+        );
+        Ok(self)
+    }
+
+    async fn handle_lease_create(mut self) -> Result<Self> {
+        let (request, send) = self.0.next_request().await.expect("service not called");
+        assert_eq!(request.method(), http::Method::POST);
+        let body = request.into_body().collect_bytes().await.unwrap();
+        let lease: Lease = serde_json::from_slice(&body).expect("valid lease");
+        let spec = lease.spec.as_ref().unwrap();
+        assert_eq!(spec.holder_identity.as_deref(), Some("us"));
+        assert_eq!(spec.lease_duration_seconds, Some(15));
+        assert_eq!(spec.lease_transitions, Some(0));
+        let response = serde_json::to_vec(&lease).unwrap();
+        send.send_response(Response::builder().body(Body::from(response)).unwrap());
+        Ok(self)
+    }
+
+    async fn handle_lease_get(mut self, holder: &str, age_secs: i64) -> Result<Self> {
+        let (request, send) = self.0.next_request().await.expect("service not called");
+        assert_eq!(request.method(), http::Method::GET);
+        let renewed = Utc::now() - k8s_openapi::chrono::Duration::seconds(age_secs);
+        let respdata = json!({
+            "kind": "Lease",
+            "apiVersion": "coordination.k8s.io/v1",
+            "metadata": {
+                "name": "hack-lock",
+                "namespace": "default",
+                "resourceVersion": "1"
+            },
+            "spec": {
+                "holderIdentity": holder,
+                "leaseDurationSeconds": 15,
+                "leaseTransitions": 1,
+                "acquireTime": renewed,
+                "renewTime": renewed
+            }
+        });
+        let response = serde_json::to_vec(&respdata).unwrap();
+        send.send_response(Response::builder().body(Body::from(response)).unwrap());
+        Ok(self)
+    }
+
+    async fn handle_lease_replace(mut self, transitions: i32) -> Result<Self> {
+        let (request, send) = self.0.next_request().await.expect("service not called");
+        assert_eq!(request.method(), http::Method::PUT);
+        let body = request.into_body().collect_bytes().await.unwrap();
+        let lease: Lease = serde_json::from_slice(&body).expect("valid lease");
+        // optimistic concurrency against the observed lease
+        assert_eq!(lease.metadata.resource_version.as_deref(), Some("1"));
+        let spec = lease.spec.as_ref().unwrap();
+        assert_eq!(spec.holder_identity.as_deref(), Some("us"));
+        assert_eq!(spec.lease_transitions, Some(transitions));
+        let response = serde_json::to_vec(&lease).unwrap();
+        send.send_response(Response::builder().body(Body::from(response)).unwrap());
+        Ok(self)
+    }
+
+    async fn handle_lease_conflict(mut self) -> Result<Self> {
+        let (request, send) = self.0.next_request().await.expect("service not called");
+        assert_eq!(request.method(), http::Method::PUT);
+        let respdata = json!({
+            "kind": "Status",
+            "apiVersion": "v1",
+            "status": "Failure",
+            "message": "the object has been modified",
+            "reason": "Conflict",
+            "code": 409
+        });
+        let response = serde_json::to_vec(&respdata).unwrap();
+        send.send_response(
+            Response::builder()
+                .status(StatusCode::CONFLICT)
+                .body(Body::from(response))
+                .unwrap(),
+        );
+        Ok(self)
+    }
+
+    async fn handle_aggregated_discovery(self) -> Result<Self> {
+        let deployments = json!({
+            "resource": "deployments",
+            "responseKind": { "group": "apps", "version": "v1", "kind": "Deployment" },
+            "scope": "Namespaced",
+            "verbs": ["get", "list", "watch"]
+        });
+        let pods = json!({
+            "resource": "pods",
+            "responseKind": { "group": "", "version": "v1", "kind": "Pod" },
+            "scope": "Namespaced",
+            "verbs": ["get", "list", "watch"]
+        });
+        self.handle_discovery_get(
+            "/apis",
+            json!({
+                "kind": "APIGroupDiscoveryList",
+                "apiVersion": "apidiscovery.k8s.io/v2",
+                "metadata": {},
+                "items": [{
+                    "metadata": { "name": "apps" },
+                    "versions": [{ "version": "v1", "resources": [deployments], "freshness": "Current" }]
+                }]
+            }),
+        )
+        .await?
+        .handle_discovery_get(
+            "/api",
+            json!({
+                "kind": "APIGroupDiscoveryList",
+                "apiVersion": "apidiscovery.k8s.io/v2",
+                "metadata": {},
+                "items": [{
+                    "metadata": {},
+                    "versions": [{ "version": "v1", "resources": [pods], "freshness": "Current" }]
+                }]
+            }),
+        )
+        .await
+    }
+
+    async fn handle_legacy_discovery(self) -> Result<Self> {
+        self.handle_discovery_get("/apis", json!({
+            "kind": "APIGroupList",
+            "apiVersion": "v1",
+            "groups": [{
+                "name": "apps",
+                "versions": [{ "groupVersion": "apps/v1", "version": "v1" }],
+                "preferredVersion": { "groupVersion": "apps/v1", "version": "v1" }
+            }]
+        }))
+        .await?
+        .handle_discovery_get("/apis/apps/v1", json!({
+            "kind": "APIResourceList",
+            "apiVersion": "v1",
+            "groupVersion": "apps/v1",
+            "resources": [
+                { "name": "deployments", "singularName": "deployment", "namespaced": true, "kind": "Deployment", "verbs": ["get", "list"] },
+                { "name": "deployments/scale", "singularName": "", "namespaced": true, "group": "autoscaling", "version": "v1", "kind": "Scale", "verbs": ["get", "patch"] }
+            ]
+        }))
+        .await?
+        .handle_discovery_get("/api", json!({
+            "kind": "APIVersions",
+            "versions": ["v1"],
+            "serverAddressByClientCIDRs": []
+        }))
+        .await?
+        .handle_discovery_get("/api/v1", json!({
+            "kind": "APIResourceList",
+            "groupVersion": "v1",
+            "resources": [
+                { "name": "pods", "singularName": "pod", "namespaced": true, "kind": "Pod", "verbs": ["get", "list"] }
+            ]
+        }))
+        .await
+    }
+
+    async fn handle_pod_logs(self) -> Result<Self> {
+        let running = log_pod("running", 0, "1", json!({ "running": {} }));
+        let waiting = log_pod(
+            "waiting",
+            0,
+            "1",
+            json!({ "waiting": { "reason": "ContainerCreating" } }),
+        );
+        // without follow, the pods are only listed
+        self.handle_pod_requests(vec![("/pods?", Some(pod_list(vec![running, waiting], "1")))])
+            .await?
+            .handle_pod_requests(vec![("/pods/running/log?", Some(text("one\ntwo\n")))])
+            .await
+    }
+
+    async fn handle_followed_pod_logs(self) -> Result<Self> {
+        let running = json!({ "running": {} });
+        let a = |restart_count, rv| log_pod("a", restart_count, rv, running.clone());
+        let b = |rv| log_pod("b", 0, rv, running.clone());
+        let expired = json!({ "kind": "Status", "apiVersion": "v1", "status": "Failure", "reason": "Expired", "code": 410 });
+        self.handle_pod_requests(vec![("/pods?", Some(pod_list(vec![a(0, "1")], "1")))])
+            .await?
+            .handle_pod_requests(vec![
+                ("/pods/a/log?", Some(text("first instance\n"))),
+                // a restarts, and b is added
+                (
+                    "resourceVersion=1",
+                    Some(events(vec![("MODIFIED", a(1, "2")), ("ADDED", b("3"))])),
+                ),
+            ])
+            .await?
+            .handle_pod_requests(vec![
+                ("/pods/a/log?", Some(text("second instance\n"))),
+                ("/pods/b/log?", Some(text(""))),
+                // b is only followed again after it was forgotten on delete
+                (
+                    "resourceVersion=3",
+                    Some(events(vec![("DELETED", b("4")), ("ADDED", b("5"))])),
+                ),
+            ])
+            .await?
+            .handle_pod_requests(vec![
+                ("/pods/b/log?", Some(text(""))),
+                ("resourceVersion=5", Some(events(vec![("ERROR", expired)]))),
+            ])
+            .await?
+            // b is missing from the relist, and forgotten once it is done
+            .handle_pod_requests(vec![("/pods?", Some(pod_list(vec![a(1, "6")], "6")))])
+            .await?
+            .handle_pod_requests(vec![("resourceVersion=6", Some(events(vec![("ADDED", b("7"))])))])
+            .await?
+            .handle_pod_requests(vec![
+                ("/pods/b/log?", Some(text("added again\n"))),
+                ("resourceVersion=7", None),
+            ])
+            .await
+    }
+
+    /// Respond to requests that are expected in any order, once all of them were made
+    ///
+    /// Requests are matched by a part of their uri, and responded to in the given order.
+    /// Requests without a response are left pending until the client is dropped.
+    async fn handle_pod_requests(mut self, expected: Vec<(&str, Option<Response<Body>>)>) -> Result<Self> {
+        let mut requests = vec![];
+        for _ in 0..expected.len() {
+            let (request, send) = self.0.next_request().await.expect("service not called");
+            requests.push((request.uri().to_string(), send));
+        }
+        let mut pending = vec![];
+        for (part, response) in expected {
+            let i = requests
+                .iter()
+                .position(|(uri, _)| uri.contains(part))
+                .unwrap_or_else(|| {
+                    panic!(
+                        "no request matching {part:?} in {:?}",
+                        requests.iter().map(|r| &r.0).collect::<Vec<_>>()
+                    )
+                });
+            let (_, send) = requests.remove(i);
+            match response {
+                Some(response) => send.send_response(response),
+                None => pending.push(send),
+            }
+        }
+        if !pending.is_empty() {
+            if let Some((request, _)) = self.0.next_request().await {
+                panic!("unexpected request {}", request.uri());
+            }
+        }
+        Ok(self)
+    }
+
+    async fn handle_discovery_get(mut self, path: &str, respdata: serde_json::Value) -> Result<Self> {
+        let (request, send) = self.0.next_request().await.expect("service not called");
+        assert_eq!(request.method(), http::Method::GET);
+        assert_eq!(request.uri().to_string(), path);
+        let response = serde_json::to_vec(&respdata).unwrap();
+        send.send_response(Response::builder().body(Body::from(response)).unwrap());
+        Ok(self)
+    }
 }
 
 // Create a test context with a mocked kube client
@@ -157,3 +649,54 @@ fn testcontext() -> (Client, ApiServerVerifier) {
     let mock_client = Client::new(mock_service, "default");
     (mock_client, ApiServerVerifier(handle))
 }
+
+fn log_pod(
+    name: &str,
+    restart_count: i32,
+    resource_version: &str,
+    state: serde_json::Value,
+) -> serde_json::Value {
+    json!({
+        "apiVersion": "v1",
+        "kind": "Pod",
+        "metadata": { "name": name, "namespace": "default", "resourceVersion": resource_version },
+        "spec": { "containers": [{ "name": "app" }] },
+        "status": {
+            "containerStatuses": [{
+                "name": "app",
+                "image": "app",
+                "imageID": "",
+                "ready": true,
+                "restartCount": restart_count,
+                "state": state
+            }]
+        }
+    })
+}
+
+fn pod_list(pods: Vec<serde_json::Value>, resource_version: &str) -> Response<Body> {
+    let list = json!({
+        "kind": "PodList",
+        "apiVersion": "v1",
+        "metadata": { "resourceVersion": resource_version },
+        "items": pods
+    });
+    Response::builder()
+        .body(Body::from(serde_json::to_vec(&list).unwrap()))
+        .unwrap()
+}
+
+fn events(events: Vec<(&str, serde_json::Value)>) -> Response<Body> {
+    let mut body = vec![];
+    for (kind, object) in events {
+        serde_json::to_writer(&mut body, &json!({ "type": kind, "object": object })).unwrap();
+        body.push(b'\n');
+    }
+    Response::builder().body(Body::from(body)).unwrap()
+}
+
+fn text(text: &str) -> Response<Body> {
+    Response::builder()
+        .body(Body::from(text.as_bytes().to_vec()))
+        .unwrap()
+}